@@ -10,19 +10,29 @@
 #![allow(clippy::collapsible_if)]
 #![allow(clippy::collapsible_else_if)]
 #![allow(clippy::redundant_field_names)]
+#![allow(clippy::manual_is_multiple_of)]
 
 use core::panic;
 use std::{
+    cmp,
     collections::{btree_map::Values, HashMap},
     env,
     error::Error,
     fmt::{self, Formatter},
-    i32,
+    fs, i32,
+    io::{self, BufRead, BufReader, Write},
     num::ParseIntError,
-    path::Path,
+    panic::RefUnwindSafe,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
 };
 
-use crate::{arg, shared::DumbError};
+use crate::{
+    arg, dlt_comps, dltc,
+    ltemp::{DumbLineTemplate, LineTempComp, LineTempCompTrait, MappedLineTempCompBuilder},
+    shared::DumbError,
+};
 
 /// ***please consider using the macro [`crate::dap_arg!`] instead, since this macro will be deprecated***
 ///
@@ -64,6 +74,7 @@ macro_rules! sap_arg {
 /// the compulsory argument is the name of the argument, be it a positional argument or a flag argument like "-v";
 /// the other optional "ordered but named" arguments are:
 /// - `flag2` - the the second (alias) flag name, like "--verbose" in the above example
+/// - `aliases` - more alias flag names, like `aliases=["--verb", "-V"]`; also see [`DumbArgBuilder::add_alias`]
 /// - `value` - like calling [`DumbArgBuilder::value`]
 /// - `default` - like calling [`DumbArgBuilder::default`]
 /// - `fixed` - like calling [`DumbArgBuilder::fixed`]
@@ -74,6 +85,7 @@ macro_rules! dap_arg {
     // for case like "name, flag2=flag2, value=value, default=default, fixed=fixed"
     ($name:expr
         $(, flag2=$flag2:expr)?
+        $(, aliases=[$($alias:expr),* $(,)?])?
         $(, value=$value:expr)?
         $(, default=$default:expr)?
         $(, fixed=$fixed:expr)?) => {
@@ -81,6 +93,7 @@ macro_rules! dap_arg {
               let mut name_or_flags = Vec::new();
               name_or_flags.push($name.to_string());
               $(name_or_flags.push($flag2.to_string());)?
+              $($(name_or_flags.push($alias.to_string());)*)?
               let mut builder = DumbArgBuilder::new(name_or_flags);
               $(builder.value($value);)?
               $(builder.default($default);)?
@@ -112,6 +125,84 @@ macro_rules! dap_arg {
     // };
   }
 
+/// use this macro to declare a struct of typed fields, each associated with an argument (specification) of [`DumbArgParser`]; like
+/// ```
+/// use rusty_dumb_tools::prelude::*;
+/// dap_struct! {
+///     #[derive(Debug)]
+///     pub struct DemoArgs {
+///         pub verbose: bool = dap_arg!("-v", flag2 = "--verbose", fixed = true).set_description("turn on verbose mode"),
+///         pub name: String = dap_arg!("-n", flag2 = "--name", default = "nobody"),
+///         pub level: Option<i32> = dap_arg!("-l", value = 0).set_range(0, 9),
+///         pub input: String = dap_arg!("input"),
+///         pub rest: Vec<i32> = dap_arg!("rest", value = 0).set_multi(),
+///     }
+/// }
+/// let demo_args = DemoArgs::process_args(vec!["-v", "-l", "3", "in", "1", "2"]);
+/// assert!(demo_args.verbose);
+/// assert_eq!("nobody", demo_args.name);
+/// assert_eq!(Some(3), demo_args.level);
+/// assert_eq!("in", demo_args.input);
+/// assert_eq!(vec![1, 2], demo_args.rest);
+/// ```
+/// each field is declared with its type as well as the [`DumbArgBuilder`] that builds the argument, normally with the macro [`dap_arg!`];
+/// the field type must agree with the argument type, and can be
+/// - the argument type like [`String`] and [`i32`] -- the argument must be provided, or has default
+/// - [`bool`] -- for [`DumbArgBuilder::fixed`] flag; `false` if the flag is not provided
+/// - [`Option`] of the argument type -- [`None`] if the argument is not provided (see [`DumbArgParser::set_allow_missing_arguments`])
+/// - [`Vec`] of the argument type -- for multi-argument (see [`DumbArgBuilder::set_multi`] and [`DumbArgBuilder::append`])
+///
+/// the generated functions are
+/// - `add_args_to(parser: &mut DumbArgParser)` -- add the arguments of the fields to the parser
+/// - `create_parser() -> DumbArgParser` -- create a parser with the arguments of the fields added
+/// - `from_parser(parser: &DumbArgParser) -> Result<Self, DumbError>` -- construct the struct from the parsed argument values
+/// - `parse_args() -> Self` -- like [`DumbArgParser::parse_args`], parse from the input program arguments
+/// - `process_args(in_args: Vec<&str>) -> Self` -- like [`DumbArgParser::process_args`], parse from the provided arguments
+#[macro_export]
+macro_rules! dap_struct {
+    ($(#[$meta:meta])*
+     $vis:vis struct $name:ident {
+        $($(#[$field_meta:meta])* $field_vis:vis $field:ident : $ty:ty = $builder:expr),* $(,)?
+    }) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty,)*
+        }
+        impl $name {
+            #[allow(unused)]
+            pub fn add_args_to(parser: &mut $crate::arg::DumbArgParser) {
+                $($builder.add_to(parser).unwrap();)*
+            }
+            #[allow(unused)]
+            pub fn create_parser() -> $crate::arg::DumbArgParser {
+                let mut parser = $crate::arg::DumbArgParser::new();
+                Self::add_args_to(&mut parser);
+                parser
+            }
+            #[allow(unused)]
+            pub fn from_parser(
+                parser: &$crate::arg::DumbArgParser,
+            ) -> Result<Self, $crate::shared::DumbError> {
+                Ok($name {
+                    $($field: $crate::arg::ArgFieldTrait::from_parser(parser, &$builder.get_name())?,)*
+                })
+            }
+            #[allow(unused)]
+            pub fn parse_args() -> Self {
+                let mut parser = Self::create_parser();
+                parser.parse_args();
+                Self::from_parser(&parser).unwrap()
+            }
+            #[allow(unused)]
+            pub fn process_args(in_args: Vec<&str>) -> Self {
+                let mut parser = Self::create_parser();
+                parser.process_args(in_args);
+                Self::from_parser(&parser).unwrap()
+            }
+        }
+    };
+}
+
 #[test]
 fn test_arg() {}
 
@@ -204,8 +295,19 @@ fn debug_arg_sap() {
 /// Notes:
 /// * -h and --help are reserved for showing help message; after showing the help message, the program will exit
 /// * in case of invalid input argument, will show the error message as well as the help message, then the program will exit
-/// * arguments are typed; the default is [`String`]; others are [`std::i32`], [`std::i64`], [`std::f32`], [`std::f64`] and [`bool`]
-/// * also see the macro [`dap_arg`]
+/// * arguments are typed; the default is [`String`]; others are [`std::i32`], [`std::i64`], [`std::f32`], [`std::f64`] and [`bool`],
+///   as well as [`u32`], [`u64`], [`usize`], [`PathBuf`], [`Duration`] (like "5s") and [`ArgByteSize`] (like "10MiB");
+///   a user-defined conversion can be set with [`DumbArgBuilder::set_parse_fn`]
+/// * besides the usual flags, there can be counted flags like `-vvv` ([`DumbArgBuilder::count`]), repeatable flags like `-I path1 -I path2` ([`DumbArgBuilder::append`])
+///   and negatable flags like `--color` / `--no-color` ([`DumbArgBuilder::negatable`])
+/// * long invocations can be put in response files like `@args.txt`, if enabled with [`DumbArgParser::set_allow_response_files`]
+/// * instead of failing, missing required arguments can be prompted on the terminal, if enabled with [`DumbArgParser::set_prompt_missing_arguments`]
+/// * enum values can have aliases ([`DumbArgBuilder::set_enum_aliases`]) and be matched case-insensitively ([`DumbArgBuilder::set_enums_ignore_case`])
+/// * besides [`DumbArgBuilder::set_range`] and [`DumbArgBuilder::set_enums`], input values can be validated with [`DumbArgBuilder::add_validator`] and [`DumbArgParser::add_validator`]
+/// * arguments can be hidden from the help message with [`DumbArgBuilder::set_hidden`], or be deprecated with [`DumbArgBuilder::set_deprecated`] / [`DumbArgBuilder::add_deprecated_alias`]
+/// * relationships among arguments can be set with [`DumbArgParser::add_group`] (e.g. mutually exclusive) and [`DumbArgParser::add_requires`]
+/// * the help screen and error messages can be localized with [`DumbArgParser::set_message_catalog`]
+/// * also see the macro [`dap_arg`], as well as [`dap_struct`] for declaring the arguments as a struct
 ///
 /// The above code, if run with invalid argument like `-x`, you will see the help screen like
 /// ```_no_run
@@ -214,21 +316,26 @@ fn debug_arg_sap() {
 /// | !!!
 /// | USAGE: rusty_dumb_tools [-h] [-v] [-n name] <str-arg> <i32-arg> <multi-arg>
 /// | : This is a simple argument parser.
-/// | . -h, --help : HELP
-/// | . -v, --verbose : FLAG [true]
-/// | . -n name, --name name : OPTIONAL; default [nobody]
-/// | . <str-arg> : REQUIRED
-/// | . <i32-arg> : REQUIRED; e.g. 123
-/// | . <multi-arg> ... : REQUIRED ...
+/// | POSITIONAL ARGUMENTS:
+/// |   <str-arg>             REQUIRED
+/// |   <i32-arg>             REQUIRED; e.g. 123
+/// |   <multi-arg> ...       REQUIRED ...
+/// | OPTIONS:
+/// |   -h, --help            HELP
+/// |   -v, --verbose         FLAG [true]
+/// |   -n name, --name name  OPTIONAL; default [nobody]
 /// ```
 /// - `USAGE ...` -- the line that shows how the program should be invoked
 /// - `This is a simple argument parser` -- the provided description
-/// - `-h, --help : HELP` -- **special** for showing the `HELP` screen
-/// - '-v, --verbose : FLAG [true]' -- a *fixed* `FLAG` argument; when such *fixed* `FLAG` is supplied, the argument is treated as the *fixed* value `true`
-/// - `-n name, --name name : OPTIONAL; default [nobody]` -- an optional argument that requires a string (`name`) following it; if not supplied, the default value is `nobody`
-/// - `<str-arg> : REQUIRED` -- a required positional argument of type `String`
-/// - `<i32-arg> : REQUIRED; e.g. 123` -- a required positional argument of type `i32`; the example value `123` is provided
-/// - `<multi-arg> ... : REQUIRED ...` -- positional argument that can accept multiple values; at least one argument is required; notice the `...`
+/// - `<str-arg>  REQUIRED` -- a required positional argument of type `String`
+/// - `<i32-arg>  REQUIRED; e.g. 123` -- a required positional argument of type `i32`; the example value `123` is provided
+/// - `<multi-arg> ...  REQUIRED ...` -- positional argument that can accept multiple values; at least one argument is required; notice the `...`
+/// - `-h, --help  HELP` -- **special** for showing the `HELP` screen
+/// - '-v, --verbose  FLAG [true]' -- a *fixed* `FLAG` argument; when such *fixed* `FLAG` is supplied, the argument is treated as the *fixed* value `true`
+/// - `-n name, --name name  OPTIONAL; default [nobody]` -- an optional argument that requires a string (`name`) following it; if not supplied, the default value is `nobody`
+///
+/// The help screen is word-wrapped to the terminal width as specified by the environment variable `COLUMNS` (default 80); also see [`DumbArgParser::compose_help`].
+/// The same information can be rendered as a man page with [`DumbArgParser::compose_man_page`], or as a Markdown reference with [`DumbArgParser::compose_markdown`].
 ///
 /// You may want to refer to [`crate::demo::run_demo`] for a demo program that uses [`DumbArgParser`].
 #[derive(Debug)]
@@ -238,9 +345,19 @@ pub struct DumbArgParser {
     input_arg_index_map: HashMap<String, usize>,
     input_multi_arg_data: Option<(Vec<String>, Vec<ArgValue>)>,
     input_rest_arg_data: Option<(Vec<String>, Vec<String>)>,
+    input_append_arg_data: HashMap<usize, Vec<ArgValue>>,
+    input_arg_provided: Vec<bool>,
+    groups: Vec<ArgGroup>,
+    requires: Vec<ArgRequires>,
     program_name: Option<String>,
     description: Option<String>,
     allow_none: bool,
+    allow_flag_prefix: bool,
+    allow_response_files: bool,
+    prompt_reader: Option<ArgPromptReader>,
+    validators: Vec<ArgParserValidateFn>,
+    warnings: Vec<String>,
+    messages: ArgMessages,
 }
 impl DumbArgParser {
     /// create and instance of [DumbArgParser]; program name will be extracted from [`env::args`] when [`DumbArgParser::parse_args`] is called
@@ -261,9 +378,19 @@ impl DumbArgParser {
             input_arg_index_map: HashMap::new(),
             input_multi_arg_data: None,
             input_rest_arg_data: None,
+            input_append_arg_data: HashMap::new(),
+            input_arg_provided: Vec::new(),
+            groups: Vec::new(),
+            requires: Vec::new(),
             program_name: settings.program_name,
             description: settings.description,
             allow_none: settings.allow_none,
+            allow_flag_prefix: false,
+            allow_response_files: false,
+            prompt_reader: None,
+            validators: Vec::new(),
+            warnings: Vec::new(),
+            messages: ArgMessages(Arc::new(ArgDefaultMessageCatalog)),
         }
     }
     fn _new(program_name: Option<String>) -> DumbArgParser {
@@ -273,9 +400,19 @@ impl DumbArgParser {
             input_arg_index_map: HashMap::new(),
             input_multi_arg_data: None,
             input_rest_arg_data: None,
+            input_append_arg_data: HashMap::new(),
+            input_arg_provided: Vec::new(),
+            groups: Vec::new(),
+            requires: Vec::new(),
             program_name: program_name,
             description: None,
             allow_none: false,
+            allow_flag_prefix: false,
+            allow_response_files: false,
+            prompt_reader: None,
+            validators: Vec::new(),
+            warnings: Vec::new(),
+            messages: ArgMessages(Arc::new(ArgDefaultMessageCatalog)),
         }
     }
     /// set the program description to be shown in the help message
@@ -286,9 +423,117 @@ impl DumbArgParser {
     pub fn set_allow_missing_arguments(&mut self) {
         self.allow_none = true;
     }
+    /// accept unambiguous prefix of "--" flags; e.g. "--verb" for "--verbose"
+    pub fn set_allow_flag_prefix(&mut self) {
+        self.allow_flag_prefix = true;
+    }
+    /// expand `@file` input arguments to the arguments read from the file (response file); e.g. `@args.txt`
+    /// * arguments in the file are separated by whitespaces (including newlines)
+    /// * `'...'` quotes the text as is; `"..."` quotes the text, with `\"` and `\\` escaped; `\` escapes the next character otherwise
+    /// * `#` at the beginning of an argument starts a comment till the end of the line
+    /// * `@file` inside a response file is expanded recursively; relative path is relative to the directory of the including file
+    pub fn set_allow_response_files(&mut self) {
+        self.allow_response_files = true;
+    }
+    /// instead of failing, prompt for the missing required arguments on the terminal (stdin)
+    /// * the prompt shows the argument description, as well as the range if set
+    /// * enum values set with [`DumbArgBuilder::set_with_desc_enums`] are shown as a numbered menu; either the number or the value can be entered;
+    ///   an input that is an enum value is taken as the value, rather than the number
+    /// * in case the input is invalid, the error is shown and the argument is prompted again
+    /// * with [`DumbArgParser::set_allow_missing_arguments`], all the arguments are optional, and so nothing is prompted
+    pub fn set_prompt_missing_arguments(&mut self) {
+        self.set_prompt_reader(BufReader::new(io::stdin()));
+    }
+    /// like [`DumbArgParser::set_prompt_missing_arguments`] but the input is read from the given reader
+    pub fn set_prompt_reader<R: BufRead + Send + 'static>(&mut self, reader: R) {
+        self.prompt_reader = Some(ArgPromptReader(Arc::new(Mutex::new(reader))));
+    }
+    /// use the given message catalog, instead of [`ArgDefaultMessageCatalog`], for the user-facing messages like those of the help screen; see [`ArgMessageCatalogTrait`]
+    pub fn set_message_catalog<
+        C: ArgMessageCatalogTrait + Send + Sync + RefUnwindSafe + 'static,
+    >(
+        &mut self,
+        catalog: C,
+    ) {
+        self.messages = ArgMessages(Arc::new(catalog));
+    }
+    fn _messages(&self) -> &ArgMessagesType {
+        self.messages.0.as_ref()
+    }
     fn add_arg(&mut self, arg: Arg) {
         self.args.push(arg);
     }
+    /// add a group of arguments, with the rule [`ArgGroupRule`] of the group to be checked after parsing; e.g.
+    /// ```
+    /// use rusty_dumb_tools::prelude::*;
+    /// let mut parser = DumbArgParser::new();
+    /// dap_arg!("--json", fixed = true).add_to(&mut parser).unwrap();
+    /// dap_arg!("--csv", fixed = true).add_to(&mut parser).unwrap();
+    /// parser.add_group("format", vec!["--json", "--csv"], ArgGroupRule::AtMostOne).unwrap();
+    /// let process_res = parser.check_process_args(vec!["--json", "--csv"], false);
+    /// assert_eq!("arguments [--json] and [--csv] are mutually exclusive", process_res.unwrap_err().to_string());
+    /// ```
+    /// * `name` - the name of the group to be shown in the help message
+    /// * `arg_names` - the names of the arguments (already added) in the group
+    /// * `rule` - the rule of the group
+    ///
+    /// note that an argument in a group with rule (other than [`ArgGroupRule::None`]) is not required by itself; the group rule decides
+    pub fn add_group(
+        &mut self,
+        name: &str,
+        arg_names: Vec<&str>,
+        rule: ArgGroupRule,
+    ) -> Result<(), DumbError> {
+        let arg_indexes = self._find_arg_indexes(&arg_names)?;
+        self.groups.push(ArgGroup {
+            name: name.to_string(),
+            arg_indexes,
+            rule,
+        });
+        Ok(())
+    }
+    /// add the dependency that when the argument is provided, the required arguments must be provided as well; e.g. "--user" requires "--password"
+    /// * `arg_name` - the name of the argument (already added)
+    /// * `required_arg_names` - the names of the arguments (already added) required by the argument
+    ///
+    /// note that a required argument here is only required when the argument requiring it is provided
+    pub fn add_requires(
+        &mut self,
+        arg_name: &str,
+        required_arg_names: Vec<&str>,
+    ) -> Result<(), DumbError> {
+        let arg_idx = self._find_arg_indexes(&[arg_name])?[0];
+        let required_arg_indexes = self._find_arg_indexes(&required_arg_names)?;
+        self.requires.push(ArgRequires {
+            arg_idx,
+            required_arg_indexes,
+        });
+        Ok(())
+    }
+    /// add a validator of the parsed arguments as a whole, which is called after all the arguments are parsed and verified;
+    /// e.g. `add_validator(|parser| if ... { Err("...".to_owned()) } else { Ok(()) })`;
+    /// the error returned is treated like other invalid input argument errors; also see [`DumbArgBuilder::add_validator`]
+    pub fn add_validator<F>(&mut self, validator: F)
+    where
+        F: Fn(&DumbArgParser) -> Result<(), String> + Send + Sync + RefUnwindSafe + 'static,
+    {
+        self.validators
+            .push(ArgParserValidateFn(Arc::new(validator)));
+    }
+    fn _find_arg_indexes(&self, arg_names: &[&str]) -> Result<Vec<usize>, String> {
+        let mut arg_indexes = Vec::new();
+        for arg_name in arg_names.iter() {
+            let arg_idx = self.args.iter().position(|arg| match &arg.key {
+                ArgKey::Name(name) => name == arg_name,
+                ArgKey::Flags(_, flags) => flags.iter().any(|flag| flag == arg_name),
+            });
+            match arg_idx {
+                Some(arg_idx) => arg_indexes.push(arg_idx),
+                None => return Err(format!("no argument [{}] found", arg_name)),
+            }
+        }
+        Ok(arg_indexes)
+    }
     /// compose the "usage" part of the help message, mostly for illustration use
     pub fn compose_usage(&self) -> String {
         let (flag_args, position_args) = self._split_args();
@@ -298,6 +543,9 @@ impl DumbArgParser {
         let mut position_args: Vec<Arg> = Vec::new();
         let mut flag_args: Vec<Arg> = Vec::new();
         for arg in self.args.iter() {
+            if arg.hidden {
+                continue;
+            }
             match &arg.key {
                 ArgKey::Name(name) => {
                     position_args.push(arg.clone());
@@ -317,18 +565,49 @@ impl DumbArgParser {
                 ArgKey::Name(name) => (name, None),
                 ArgKey::Flags(_, flags) => (&flags[0], Some(&flags[0])),
             };
-            let value = match self.input_arg_index_map.get(key) {
-                Some(idx) => self.input_arg_values[*idx].as_ref(),
-                None => None,
+            let (value, arg_idx) = match self.input_arg_index_map.get(key) {
+                Some(idx) => (self.input_arg_values[*idx].as_ref(), *idx),
+                None => (None, 0),
             };
             if value.is_none() {
                 continue;
             }
+            if arg.nature == ArgNature::Count {
+                let count = match value.unwrap() {
+                    ArgValue::I32(count) => *count,
+                    _ => 0,
+                };
+                for _ in 0..count {
+                    if !parameters.is_empty() {
+                        parameters.push(' ');
+                    }
+                    parameters.push_str(flag.unwrap());
+                }
+                continue;
+            }
+            if arg.nature == ArgNature::Append {
+                for value in self.input_append_arg_data[&arg_idx].iter() {
+                    if !parameters.is_empty() {
+                        parameters.push(' ');
+                    }
+                    parameters.push_str(flag.unwrap());
+                    parameters.push(' ');
+                    parameters.push_str(value.to_string().as_str());
+                }
+                continue;
+            }
             if !parameters.is_empty() {
                 parameters.push(' ');
             }
             if let ArgNature::Fixed = arg.nature {
                 parameters.push_str(flag.unwrap());
+            } else if let ArgNature::Negatable = arg.nature {
+                let flag = Self::_pick_negatable_flag(&arg.key);
+                if let ArgValue::Bool(false) = value.unwrap() {
+                    parameters.push_str(format!("--no-{}", &flag[2..]).as_str());
+                } else {
+                    parameters.push_str(flag.as_str());
+                }
             } else {
                 let value = value.unwrap();
                 if let Some(flag) = flag {
@@ -340,6 +619,198 @@ impl DumbArgParser {
         }
         parameters
     }
+    /// compose the canonical input arguments (without the program name) equivalent to the parsed argument values, including the defaults;
+    /// the result can be fed to [`DumbArgParser::process_args`] to replay the invocation
+    /// * flag arguments come first, followed by the positional arguments; multi-argument flag, which takes the rest of the input arguments, comes last
+    /// * if any positional argument value starts with "-", the positional arguments are preceded by "--", which ends the flags
+    ///   (unless "--" is itself an argument)
+    /// * also see [`DumbArgParser::compose_command_line`]
+    pub fn compose_argv(&self) -> Vec<String> {
+        let mut flag_in_args = Vec::new();
+        let mut position_in_args = Vec::new();
+        let mut multi_flag_in_args = Vec::new();
+        let mut position_missing = false;
+        for (arg_idx, arg) in self.args.iter().enumerate() {
+            let values = match self._resolve_arg_values(arg_idx) {
+                Some(values) => values,
+                None => {
+                    if let ArgKey::Name(_) = arg.key {
+                        // positional arguments are taken in order; those after the missing one can only be the defaults
+                        position_missing = true;
+                    }
+                    continue;
+                }
+            };
+            let flag = match &arg.key {
+                ArgKey::Name(_) => {
+                    if position_missing {
+                        continue;
+                    }
+                    for value in values.iter() {
+                        position_in_args.push(value.to_string());
+                    }
+                    continue;
+                }
+                ArgKey::Flags(_, flags) => &flags[0],
+            };
+            match arg.nature {
+                ArgNature::Fixed => flag_in_args.push(flag.clone()),
+                ArgNature::Count => {
+                    let count = match values[0] {
+                        ArgValue::I32(count) => count,
+                        _ => 0,
+                    };
+                    for _ in 0..count {
+                        flag_in_args.push(flag.clone());
+                    }
+                }
+                ArgNature::Negatable => {
+                    let flag = Self::_pick_negatable_flag(&arg.key);
+                    if let ArgValue::Bool(false) = values[0] {
+                        flag_in_args.push(format!("--no-{}", &flag[2..]));
+                    } else {
+                        flag_in_args.push(flag);
+                    }
+                }
+                _ => {
+                    if arg.multi_mode != ArgMultiMode::None {
+                        multi_flag_in_args.push(flag.clone());
+                        for value in values.iter() {
+                            multi_flag_in_args.push(value.to_string());
+                        }
+                    } else {
+                        for value in values.iter() {
+                            flag_in_args.push(flag.clone());
+                            flag_in_args.push(value.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        if position_in_args
+            .iter()
+            .any(|position_in_arg| position_in_arg.starts_with('-'))
+            && DumbArgParser::_scan_arg_index(&self.args, "--", -1).is_none()
+        {
+            flag_in_args.push("--".to_owned());
+        }
+        flag_in_args.extend(position_in_args);
+        flag_in_args.extend(multi_flag_in_args);
+        flag_in_args
+    }
+    /// like [`DumbArgParser::compose_argv`] but composed as a command line, with the program name,
+    /// and with the arguments quoted for POSIX shells when needed
+    pub fn compose_command_line(&self) -> String {
+        let mut command_line = Self::_quote_shell_arg(&self._get_program_name());
+        for in_arg in self.compose_argv().iter() {
+            command_line.push(' ');
+            command_line.push_str(&Self::_quote_shell_arg(in_arg));
+        }
+        command_line
+    }
+    /// dump all the resolved argument values, including the defaults, as a JSON object keyed by the argument names
+    /// (for flag arguments, the name is the first "--" flag without the dashes, or the first "-" flag without the dash if no "--" flag);
+    /// multi-argument values are dumped as arrays, [`bool`] fixed flag not provided as `false` (like [`dap_struct!`]), and other missing values as `null`
+    pub fn compose_json(&self) -> String {
+        let mut json = String::from("{");
+        for (arg_idx, arg) in self.args.iter().enumerate() {
+            let name = match &arg.key {
+                ArgKey::Name(name) => name,
+                ArgKey::Flags(name, _) => name,
+            };
+            if arg_idx > 0 {
+                json.push_str(", ");
+            }
+            json.push_str(format!("{}: ", Self::_quote_json_string(name)).as_str());
+            match self._resolve_arg_values(arg_idx) {
+                Some(values) => {
+                    if arg.multi_mode != ArgMultiMode::None || arg.nature == ArgNature::Append {
+                        let values: Vec<String> = values.iter().map(Self::_to_json_value).collect();
+                        json.push_str(format!("[{}]", values.join(", ")).as_str());
+                    } else {
+                        json.push_str(Self::_to_json_value(&values[0]).as_str());
+                    }
+                }
+                None => {
+                    if arg.nature == ArgNature::Fixed && matches!(arg.value, ArgValue::Bool(_)) {
+                        json.push_str("false");
+                    } else {
+                        json.push_str("null");
+                    }
+                }
+            }
+        }
+        json.push('}');
+        json
+    }
+    /// resolve the parsed value(s) of the argument; multi-argument and appended values are all included
+    fn _resolve_arg_values(&self, arg_idx: usize) -> Option<Vec<ArgValue>> {
+        let arg = &self.args[arg_idx];
+        if arg.nature == ArgNature::Append {
+            return self.input_append_arg_data.get(&arg_idx).cloned();
+        }
+        let value = self.input_arg_values.get(arg_idx)?.as_ref()?;
+        let arg_name = arg.key.get_a_name();
+        if arg.multi_mode == ArgMultiMode::Regular {
+            if let Some((names, values)) = &self.input_multi_arg_data {
+                if names.contains(&arg_name) {
+                    return Some(values.clone());
+                }
+            }
+        } else if arg.multi_mode == ArgMultiMode::Rest {
+            let mut values = vec![value.clone()];
+            if let Some((names, rest_values)) = &self.input_rest_arg_data {
+                if names.contains(&arg_name) {
+                    for rest_value in rest_values.iter() {
+                        values.push(ArgValue::String(rest_value.clone()));
+                    }
+                }
+            }
+            return Some(values);
+        }
+        Some(vec![value.clone()])
+    }
+    fn _quote_shell_arg(in_arg: &str) -> String {
+        let is_safe = !in_arg.is_empty()
+            && in_arg
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c));
+        if is_safe {
+            in_arg.to_string()
+        } else {
+            format!("'{}'", in_arg.replace('\'', "'\\''"))
+        }
+    }
+    fn _to_json_value(value: &ArgValue) -> String {
+        match value {
+            ArgValue::Bool(_)
+            | ArgValue::I32(_)
+            | ArgValue::I64(_)
+            | ArgValue::U32(_)
+            | ArgValue::U64(_)
+            | ArgValue::Usize(_) => value.to_string(),
+            ArgValue::F32(v) if v.is_finite() => value.to_string(),
+            ArgValue::F64(v) if v.is_finite() => value.to_string(),
+            ArgValue::F32(_) | ArgValue::F64(_) => "null".to_owned(),
+            _ => Self::_quote_json_string(&value.to_string()),
+        }
+    }
+    fn _quote_json_string(text: &str) -> String {
+        let mut quoted = String::from("\"");
+        for c in text.chars() {
+            match c {
+                '"' => quoted.push_str("\\\""),
+                '\\' => quoted.push_str("\\\\"),
+                '\n' => quoted.push_str("\\n"),
+                '\r' => quoted.push_str("\\r"),
+                '\t' => quoted.push_str("\\t"),
+                c if (c as u32) < 0x20 => quoted.push_str(format!("\\u{:04x}", c as u32).as_str()),
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
     /// get the parsed -- [`DumbArgParser::parse_args`] -- argument value (parameter) assigned to the given argument name
     /// * `arg_name` - the argument name of which the value is to be retrieved; it can be a positional argument name,
     ///                or can be a flag argument name (including `flag2`, which is just an alias to the flag argument)
@@ -383,7 +854,22 @@ impl DumbArgParser {
     /// * `arg_name` - the argument name of which the values are to be retrieved
     ///
     /// note: like [`DumbArgParser::get`], except when target type is [`String`], no implicit type conversion
+    ///
+    /// for "append" argument -- see [`DumbArgBuilder::append`] -- the values appended so far will be returned
     pub fn get_multi<T: ArgValueTrait>(&self, arg_name: &str) -> Option<Vec<T>> {
+        if let Some(arg_idx) = self.input_arg_index_map.get(arg_name) {
+            if let Some(arg_values) = self.input_append_arg_data.get(arg_idx) {
+                let mut values = Vec::new();
+                for arg_value in arg_values.iter() {
+                    let value = match T::from_arg_value(arg_value.clone()) {
+                        Ok(value) => *value,
+                        Err(err) => panic!("{}", err),
+                    };
+                    values.push(value);
+                }
+                return Some(values);
+            }
+        }
         match &self.input_multi_arg_data {
             Some(input_multi_arg_data) => {
                 let names = &input_multi_arg_data.0;
@@ -429,6 +915,10 @@ impl DumbArgParser {
             None => None,
         }
     }
+    /// get the warnings (like using deprecated arguments -- see [`DumbArgBuilder::set_deprecated`]) emitted during the last parsing
+    pub fn get_warnings(&self) -> Vec<String> {
+        self.warnings.clone()
+    }
     /// IMPORTANT: assume [`DumbArgParser::get_rest`] is able to retrieve the "rest" multi-argument values
     pub fn process_rest_args(&self, arg_name: &str, parser: &mut DumbArgParser) {
         let arg_idx = match self.input_arg_index_map.get(arg_name) {
@@ -517,26 +1007,41 @@ impl DumbArgParser {
         self.input_arg_values.clear();
         self.input_arg_index_map.clear();
         self.input_multi_arg_data = None;
+        self.input_append_arg_data.clear();
+        self.input_arg_provided.clear();
+        self.warnings.clear();
         for i in 0..self.args.len() {
             self.input_arg_values.push(None);
+            self.input_arg_provided.push(false);
         }
         let (flag_args, position_args) = self._split_args();
         for arg_idx in 0..self.args.len() {
             let arg = &self.args[arg_idx];
             let rest_arg_values = if arg.multi_mode != ArgMultiMode::None {
-                if (arg.nature == ArgNature::Fixed) {
-                    panic!("multi-argument [{}] cannot be fixed", arg.key.get_a_name());
-                }
                 Some(vec![])
             } else {
                 None
             };
-            if arg.nature == ArgNature::Optional {
+            if arg.nature == ArgNature::Optional
+                || arg.nature == ArgNature::Count
+                || arg.nature == ArgNature::Negatable
+            {
                 self._set_arg_value(arg_idx, arg.value.clone(), rest_arg_values)
                     .unwrap();
+            } else if arg.nature == ArgNature::Append {
+                self.input_append_arg_data.insert(arg_idx, Vec::new());
+                if let ArgKey::Flags(_, flags) = &arg.key {
+                    for flag in flags.iter() {
+                        self.input_arg_index_map.insert(flag.clone(), arg_idx);
+                    }
+                }
             }
         }
-        let (need_help, err_msg) = match self._scan_args(in_args) {
+        let scan_result = match self._expand_in_args(in_args) {
+            Ok(in_args) => self._scan_args(in_args.iter().map(|in_arg| in_arg.as_str()).collect()),
+            Err(err_msg) => Err(err_msg.into()),
+        };
+        let (need_help, err_msg) = match scan_result {
             Ok((show_help, err_msg)) => (show_help, err_msg),
             Err(err_msg) => (false, Some(err_msg.to_string())),
         };
@@ -553,6 +1058,143 @@ impl DumbArgParser {
             Ok(true)
         }
     }
+    fn _expand_in_args(&self, in_args: Vec<&str>) -> Result<Vec<String>, String> {
+        let in_args: Vec<String> = in_args.iter().map(|in_arg| in_arg.to_string()).collect();
+        if !self.allow_response_files {
+            return Ok(in_args);
+        }
+        let mut expanded_in_args = Vec::new();
+        DumbArgParser::_expand_response_files(
+            self._messages(),
+            in_args,
+            None,
+            &mut Vec::new(),
+            &mut expanded_in_args,
+        )?;
+        Ok(expanded_in_args)
+    }
+    fn _expand_response_files(
+        messages: &ArgMessagesType,
+        in_args: Vec<String>,
+        base_dir: Option<&Path>,
+        including_paths: &mut Vec<PathBuf>,
+        expanded_in_args: &mut Vec<String>,
+    ) -> Result<(), String> {
+        for in_arg in in_args {
+            let file_path = match in_arg.strip_prefix('@') {
+                Some(file_path) if !file_path.is_empty() => file_path,
+                _ => {
+                    expanded_in_args.push(in_arg);
+                    continue;
+                }
+            };
+            let file_path = match base_dir {
+                Some(base_dir) => base_dir.join(file_path),
+                None => PathBuf::from(file_path),
+            };
+            let canonical_path = match file_path.canonicalize() {
+                Ok(canonical_path) => canonical_path,
+                Err(err) => {
+                    return Err(messages.cannot_read_response_file(
+                        file_path.display().to_string().as_str(),
+                        err.to_string().as_str(),
+                    ))
+                }
+            };
+            if including_paths.contains(&canonical_path) {
+                return Err(messages
+                    .response_file_includes_itself(file_path.display().to_string().as_str()));
+            }
+            let content = match fs::read_to_string(&canonical_path) {
+                Ok(content) => content,
+                Err(err) => {
+                    return Err(messages.cannot_read_response_file(
+                        file_path.display().to_string().as_str(),
+                        err.to_string().as_str(),
+                    ))
+                }
+            };
+            let file_in_args = match DumbArgParser::_split_response_file_content(messages, &content)
+            {
+                Ok(file_in_args) => file_in_args,
+                Err(err_msg) => {
+                    return Err(messages.invalid_response_file(
+                        file_path.display().to_string().as_str(),
+                        err_msg.as_str(),
+                    ))
+                }
+            };
+            including_paths.push(canonical_path.clone());
+            DumbArgParser::_expand_response_files(
+                messages,
+                file_in_args,
+                canonical_path.parent(),
+                including_paths,
+                expanded_in_args,
+            )?;
+            including_paths.pop();
+        }
+        Ok(())
+    }
+    /// split the content of a response file into arguments; see [`DumbArgParser::set_allow_response_files`]
+    fn _split_response_file_content(
+        messages: &ArgMessagesType,
+        content: &str,
+    ) -> Result<Vec<String>, String> {
+        let mut in_args = Vec::new();
+        let mut in_arg: Option<String> = None;
+        let mut chars = content.chars();
+        while let Some(c) = chars.next() {
+            if c.is_whitespace() {
+                if let Some(in_arg) = in_arg.take() {
+                    in_args.push(in_arg);
+                }
+                continue;
+            }
+            if c == '#' && in_arg.is_none() {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                continue;
+            }
+            let in_arg = in_arg.get_or_insert_with(String::new);
+            match c {
+                '\'' => loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => in_arg.push(c),
+                        None => return Err(messages.unterminated_quote('\'')),
+                    }
+                },
+                '"' => loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c) if c == '"' || c == '\\' => in_arg.push(c),
+                            Some(c) => {
+                                in_arg.push('\\');
+                                in_arg.push(c);
+                            }
+                            None => return Err(messages.unterminated_quote('"')),
+                        },
+                        Some(c) => in_arg.push(c),
+                        None => return Err(messages.unterminated_quote('"')),
+                    }
+                },
+                '\\' => match chars.next() {
+                    Some(c) => in_arg.push(c),
+                    None => return Err(messages.dangling_escape()),
+                },
+                _ => in_arg.push(c),
+            }
+        }
+        if let Some(in_arg) = in_arg {
+            in_args.push(in_arg);
+        }
+        Ok(in_args)
+    }
     fn _exit_program_after_check_parse(&self, check_parse_result: Result<bool, DumbError>) {
         match check_parse_result {
             Ok(ok) => {
@@ -560,7 +1202,11 @@ impl DumbArgParser {
                     //return;
                 } else {
                     println!();
-                    println!("~~~ [{}] EXITED normally ~~~", self._get_program_name());
+                    println!(
+                        "{}",
+                        self._messages()
+                            .exited_normally(self._get_program_name().as_str())
+                    );
                     println!();
                     std::process::exit(0);
                 }
@@ -568,9 +1214,11 @@ impl DumbArgParser {
             Err(err) => {
                 println!();
                 println!(
-                    "~~~ [{}] EXITED with error \"{}\" ~~~",
-                    self._get_program_name(),
-                    err
+                    "{}",
+                    self._messages().exited_with_error(
+                        self._get_program_name().as_str(),
+                        err.to_string().as_str()
+                    )
                 );
                 println!();
                 std::process::exit(-1);
@@ -582,6 +1230,9 @@ impl DumbArgParser {
         let mut pos_idx: usize = 0;
         let in_args_len = in_args.len(); // if nothing provided, show help
         let mut in_arg_idx: usize = 0;
+        // after "--", all input arguments are positional; unless "--" is itself an argument (e.g. multi-argument that captures the rest)
+        let accept_flags_end = DumbArgParser::_scan_arg_index(&self.args, "--", -1).is_none();
+        let mut flags_ended = false;
         let mut need_help = false; //in_args_len == 0;
         loop {
             if in_arg_idx >= in_args_len {
@@ -590,42 +1241,98 @@ impl DumbArgParser {
             let in_arg = in_args[in_arg_idx];
             in_arg_idx += 1;
             //println!("- {}", in_arg);
-            if in_arg == "-h" || in_arg == "--help" {
-                need_help = true;
-                break;
+            if !flags_ended {
+                if accept_flags_end && in_arg == "--" {
+                    flags_ended = true;
+                    continue;
+                }
+                if in_arg == "-h" || in_arg == "--help" {
+                    need_help = true;
+                    break;
+                }
             }
-            let (arg_idx, arg, arg_value) = if in_arg.starts_with('-') {
+            let (arg_idx, arg, arg_value) = if !flags_ended && in_arg.starts_with('-') {
+                let resolved_in_arg: String;
+                let in_arg = if self.allow_flag_prefix
+                    && in_arg.starts_with("--")
+                    && DumbArgParser::_scan_arg_index(&self.args, in_arg, -1).is_none()
+                {
+                    match DumbArgParser::_resolve_flag_prefix(self._messages(), &self.args, in_arg)
+                    {
+                        Ok(Some(flag)) => {
+                            resolved_in_arg = flag;
+                            resolved_in_arg.as_str()
+                        }
+                        Ok(None) => in_arg,
+                        Err(err) => {
+                            err_msg = Some(err);
+                            break;
+                        }
+                    }
+                } else {
+                    in_arg
+                };
                 let arg_idx = DumbArgParser::_scan_arg_index(&self.args, in_arg, -1);
                 if arg_idx.is_none() {
-                    err_msg = Some(format!("unknown input argument [{}]", in_arg));
+                    if let Some(count_arg_indexes) =
+                        DumbArgParser::_scan_count_arg_indexes(&self.args, in_arg)
+                    {
+                        for arg_idx in count_arg_indexes {
+                            self._note_deprecated_use(arg_idx, in_arg);
+                            self._increment_arg_count(arg_idx)?;
+                        }
+                        continue;
+                    }
+                    if let Some(arg_idx) =
+                        DumbArgParser::_scan_negated_arg_index(&self.args, in_arg)
+                    {
+                        self._note_deprecated_use(arg_idx, format!("--{}", &in_arg[5..]).as_str());
+                        self._set_arg_value(arg_idx, ArgValue::Bool(false), None)?;
+                        self.input_arg_provided[arg_idx] = true;
+                        continue;
+                    }
+                    let flags = DumbArgParser::_collect_flags(&self.args);
+                    let suggestion = _suggest_similar(in_arg, flags.iter());
+                    err_msg = Some(
+                        self._messages()
+                            .unknown_input_argument(in_arg, suggestion.as_deref()),
+                    );
                     //return Err(format!("unknown argument [{}]", in_arg));
                     break;
                 }
                 let arg_idx = arg_idx.unwrap();
+                self._note_deprecated_use(arg_idx, in_arg);
+                if self.args[arg_idx].nature == ArgNature::Count {
+                    self._increment_arg_count(arg_idx)?;
+                    continue;
+                }
                 let arg = &self.args[arg_idx];
                 let arg_value;
                 if arg.nature == ArgNature::Fixed {
                     arg_value = Ok(arg.value.clone());
+                } else if arg.nature == ArgNature::Negatable {
+                    arg_value = Ok(ArgValue::Bool(true));
                 } else {
                     if in_arg_idx >= in_args_len {
-                        err_msg = Some(format!("missing input argument after [{}]", in_arg));
+                        err_msg = Some(self._messages().missing_input_argument_after(in_arg));
                         //return Err(format!("unknown argument [{}]", in_arg));
                         break;
                     }
-                    arg_value = arg.convert_in(in_args[in_arg_idx]);
+                    arg_value = arg.convert_in(self._messages(), in_args[in_arg_idx]);
                     in_arg_idx += 1;
                 };
                 (arg_idx, arg, arg_value)
             } else {
                 let arg_idx = DumbArgParser::_scan_arg_index(&self.args, in_arg, pos_idx as i32);
                 if arg_idx.is_none() {
-                    err_msg = Some(format!("unacceptable input argument [{}]", in_arg));
+                    err_msg = Some(self._messages().unacceptable_input_argument(in_arg));
                     //return Err(format!("unacceptable argument [{}]", in_arg));
                     break;
                 }
                 let arg_idx = arg_idx.unwrap();
+                self._note_deprecated_use(arg_idx, in_arg);
                 let arg = &self.args[arg_idx];
-                let arg_value = arg.convert_in(in_arg);
+                let arg_value = arg.convert_in(self._messages(), in_arg);
                 pos_idx += 1;
                 (arg_idx, arg, arg_value)
             };
@@ -652,6 +1359,7 @@ impl DumbArgParser {
                         }
                     }
                     self._set_arg_value(arg_idx, arg_value, Some(in_rest_args))?;
+                    self.input_arg_provided[arg_idx] = true;
                 }
                 Err(err) => {
                     err_msg = Some(err);
@@ -660,24 +1368,247 @@ impl DumbArgParser {
             }
         }
         if !need_help && err_msg.is_none() {
-            for (index, arg) in self.args.iter().enumerate() {
-                if arg.nature == ArgNature::Fixed {
+            for index in 0..self.args.len() {
+                let arg = &self.args[index];
+                if arg.nature == ArgNature::Fixed || arg.nature == ArgNature::Append {
+                    continue;
+                }
+                if self._is_arg_relation_governed(index) {
                     continue;
                 }
                 let value = &self.input_arg_values[index];
-                if value.is_none() {
-                    if !self.allow_none
-                    /* && arg.multi_mode == ArgMultiMode::None*/
-                    {
-                        let msg = format!("argument [{}] not provided", arg.key.get_a_name());
+                // with missing arguments allowed, every argument is optional, and not prompted for
+                if value.is_none() && !self.allow_none {
+                    if self.prompt_reader.is_some() {
+                        if let Err(err) = self._prompt_arg_value(index) {
+                            err_msg = Some(err);
+                            break;
+                        }
+                    } else {
+                        let msg = self
+                            ._messages()
+                            .argument_not_provided(arg.key.get_a_name().as_str());
                         err_msg = Some(msg);
                         break;
                     }
                 }
             }
         }
+        if !need_help && err_msg.is_none() {
+            err_msg = self._verify_arg_relations().err();
+        }
+        if !need_help && err_msg.is_none() {
+            err_msg = self._run_validators().err();
+        }
         Ok((need_help, err_msg))
     }
+    fn _run_validators(&self) -> Result<(), String> {
+        for arg_idx in 0..self.args.len() {
+            if self.input_arg_provided[arg_idx] {
+                self._run_arg_validators(arg_idx)?;
+            }
+        }
+        for validator in self.validators.iter() {
+            (validator.0)(self)?;
+        }
+        Ok(())
+    }
+    fn _run_arg_validators(&self, arg_idx: usize) -> Result<(), String> {
+        let validators = &self.args[arg_idx].validators;
+        if validators.is_empty() {
+            return Ok(());
+        }
+        if let Some(arg_values) = self._resolve_arg_values(arg_idx) {
+            for arg_value in arg_values.iter() {
+                for validator in validators.iter() {
+                    (validator.0)(arg_value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    /// prompt for the value of the missing argument; see [`DumbArgParser::set_prompt_missing_arguments`]
+    fn _prompt_arg_value(&mut self, arg_idx: usize) -> Result<(), String> {
+        let reader = self.prompt_reader.clone().unwrap();
+        let arg = self.args[arg_idx].clone();
+        let arg_name = arg.key.get_a_name();
+        let mut prompt = match &arg.description {
+            Some(description) => format!("{} -- {}", arg_name, description),
+            None => arg_name.clone(),
+        };
+        if let ArgConstraint::Range(min, max) = &arg.constraint {
+            prompt.push_str(format!(" [{}, {}]", min, max).as_str());
+        }
+        let menu_values: Vec<&ArgEnum> = match &arg.constraint {
+            ArgConstraint::Enums(enum_values)
+                if enum_values
+                    .iter()
+                    .any(|enum_value| enum_value.description.is_some()) =>
+            {
+                enum_values.iter().collect()
+            }
+            _ => Vec::new(),
+        };
+        loop {
+            println!("? {}", prompt);
+            for (index, enum_value) in menu_values.iter().enumerate() {
+                match &enum_value.description {
+                    Some(description) => {
+                        println!(
+                            "  {}) {} : {}",
+                            index + 1,
+                            enum_value.to_string(),
+                            description
+                        )
+                    }
+                    None => println!("  {}) {}", index + 1, enum_value.to_string()),
+                }
+            }
+            if menu_values.is_empty() {
+                print!("> ");
+            } else {
+                print!("> [1-{}] ", menu_values.len());
+            }
+            let _ = io::stdout().flush();
+            let mut line = String::new();
+            let read_count = match reader.0.lock().unwrap().read_line(&mut line) {
+                Ok(read_count) => read_count,
+                Err(err) => {
+                    return Err(self
+                        ._messages()
+                        .failed_to_read_argument(&arg_name, err.to_string().as_str()))
+                }
+            };
+            if read_count == 0 {
+                println!();
+                return Err(self._messages().argument_not_provided(&arg_name));
+            }
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (in_arg, in_rest_args) = if arg.multi_mode != ArgMultiMode::None {
+                let mut in_args = line.split_whitespace().map(|in_arg| in_arg.to_string());
+                (in_args.next().unwrap(), Some(in_args.collect()))
+            } else {
+                (line.to_string(), None)
+            };
+            // an enum value is matched first; the number of the menu is only a fallback
+            let is_enum_value = menu_values
+                .iter()
+                .any(|enum_value| enum_value.matches(&in_arg, arg.enums_ignore_case));
+            let in_arg = match in_arg.parse::<usize>() {
+                Ok(number) if !is_enum_value && number >= 1 && number <= menu_values.len() => {
+                    menu_values[number - 1].to_string()
+                }
+                _ => in_arg,
+            };
+            let set_result = match arg.convert_in(self._messages(), &in_arg) {
+                Ok(arg_value) => self._set_arg_value(arg_idx, arg_value, in_rest_args),
+                Err(err) => Err(err),
+            };
+            let set_result = set_result.and_then(|_| self._run_arg_validators(arg_idx));
+            match set_result {
+                Ok(_) => {
+                    self.input_arg_provided[arg_idx] = true;
+                    return Ok(());
+                }
+                Err(err) => println!("! {}", err),
+            }
+        }
+    }
+    /// emit a warning if the argument (or the flag used) is deprecated; see [`DumbArgBuilder::set_deprecated`] and [`DumbArgBuilder::add_deprecated_alias`]
+    fn _note_deprecated_use(&mut self, arg_idx: usize, in_arg: &str) {
+        let arg = &self.args[arg_idx];
+        let warning = if let Some(hint) = &arg.deprecated {
+            let arg_name = match &arg.key {
+                ArgKey::Name(name) => name.clone(),
+                ArgKey::Flags(_, _) => in_arg.to_string(),
+            };
+            self._messages().deprecated_argument(&arg_name, hint)
+        } else if arg.deprecated_flags.iter().any(|flag| flag == in_arg) {
+            let flags = match &arg.key {
+                ArgKey::Flags(_, flags) => flags,
+                ArgKey::Name(_) => return,
+            };
+            let replacement = flags
+                .iter()
+                .find(|flag| !arg.deprecated_flags.contains(flag));
+            self._messages()
+                .deprecated_flag(in_arg, replacement.map(|flag| flag.as_str()))
+        } else {
+            return;
+        };
+        if !self.warnings.contains(&warning) {
+            eprintln!("{}: {}", self._messages().warning_label(), warning);
+            self.warnings.push(warning);
+        }
+    }
+    fn _is_arg_relation_governed(&self, arg_idx: usize) -> bool {
+        for group in self.groups.iter() {
+            if group.rule != ArgGroupRule::None && group.arg_indexes.contains(&arg_idx) {
+                return true;
+            }
+        }
+        for requires in self.requires.iter() {
+            if requires.required_arg_indexes.contains(&arg_idx) {
+                return true;
+            }
+        }
+        false
+    }
+    fn _verify_arg_relations(&self) -> Result<(), String> {
+        for group in self.groups.iter() {
+            let provided_arg_indexes: Vec<usize> = group
+                .arg_indexes
+                .iter()
+                .filter(|arg_idx| self.input_arg_provided[**arg_idx])
+                .cloned()
+                .collect();
+            let too_many = provided_arg_indexes.len() > 1
+                && (group.rule == ArgGroupRule::AtMostOne
+                    || group.rule == ArgGroupRule::ExactlyOne);
+            if too_many {
+                return Err(self._messages().mutually_exclusive(
+                    self.args[provided_arg_indexes[0]].key.get_a_name().as_str(),
+                    self.args[provided_arg_indexes[1]].key.get_a_name().as_str(),
+                ));
+            }
+            let too_few = provided_arg_indexes.is_empty()
+                && (group.rule == ArgGroupRule::ExactlyOne
+                    || group.rule == ArgGroupRule::AtLeastOne);
+            if too_few {
+                return Err(self._messages().group_not_provided(
+                    self._compose_arg_names(&group.arg_indexes).as_str(),
+                    &group.name,
+                ));
+            }
+        }
+        for requires in self.requires.iter() {
+            if !self.input_arg_provided[requires.arg_idx] {
+                continue;
+            }
+            for required_arg_idx in requires.required_arg_indexes.iter() {
+                if !self.input_arg_provided[*required_arg_idx] {
+                    return Err(self._messages().requires_argument(
+                        self.args[requires.arg_idx].key.get_a_name().as_str(),
+                        self.args[*required_arg_idx].key.get_a_name().as_str(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+    fn _compose_arg_names(&self, arg_indexes: &[usize]) -> String {
+        let mut names = String::new();
+        for arg_idx in arg_indexes.iter() {
+            if !names.is_empty() {
+                names.push_str(", ");
+            }
+            names.push_str(self.args[*arg_idx].key.get_a_name().as_str());
+        }
+        names
+    }
     fn _scan_arg_index(args: &[Arg], flag: &str, pos_idx: i32) -> Option<usize> {
         let mut arg_pos_idx: usize = 0;
         for (arg_idx, arg) in args.iter().enumerate() {
@@ -699,29 +1630,139 @@ impl DumbArgParser {
         }
         None
     }
-    fn _set_arg_value(
-        &mut self,
-        arg_idx: usize,
-        arg_value: ArgValue,
-        in_rest_args: Option<Vec<String>>,
-    ) -> Result<(), String> {
-        let arg: &Arg = &self.args[arg_idx];
-        Self::_verify_arg_range(arg, &arg_value)?;
-        // match &arg.range {
-        //     ArgRange::Enum(enum_values) => {
-        //         let mut found = false;
-        //         for enum_value in enum_values.iter() {
-        //             if enum_value.compare(&arg_value) == 0 {
-        //                 found = true;
-        //                 break;
-        //             }
-        //         }
-        //         if !found {
-        //             let mut values = String::new();
-        //             for enum_value in enum_values.iter() {
-        //                 let value = enum_value.to_string();
-        //                 if values.len() > 0 {
-        //                     values.push_str(", ");
+    fn _collect_flags(args: &[Arg]) -> Vec<String> {
+        let mut flags = vec!["-h".to_owned(), "--help".to_owned()];
+        for arg in args.iter() {
+            if arg.hidden {
+                continue;
+            }
+            if let ArgKey::Flags(_, arg_flags) = &arg.key {
+                for flag in arg_flags.iter() {
+                    if arg.deprecated_flags.contains(flag) {
+                        continue;
+                    }
+                    flags.push(flag.clone());
+                    if arg.nature == ArgNature::Negatable && flag.starts_with("--") {
+                        flags.push(format!("--no-{}", &flag[2..]));
+                    }
+                }
+            }
+        }
+        flags
+    }
+    fn _resolve_flag_prefix(
+        messages: &ArgMessagesType,
+        args: &[Arg],
+        in_arg: &str,
+    ) -> Result<Option<String>, String> {
+        if in_arg.len() <= 2 {
+            return Ok(None);
+        }
+        let mut matched_flags: Vec<String> = Vec::new();
+        let mut matched_keys: Vec<(usize, bool)> = Vec::new();
+        for flag in DumbArgParser::_collect_flags(args) {
+            if !flag.starts_with("--") || !flag.starts_with(in_arg) {
+                continue;
+            }
+            // different flags of the same argument are considered the same match; but not for the negated flag
+            let key = match DumbArgParser::_scan_arg_index(args, &flag, -1) {
+                Some(arg_idx) => (arg_idx, false),
+                None => match DumbArgParser::_scan_negated_arg_index(args, &flag) {
+                    Some(arg_idx) => (arg_idx, true),
+                    None => (usize::MAX, false), // i.e. --help
+                },
+            };
+            if !matched_keys.contains(&key) {
+                matched_keys.push(key);
+                matched_flags.push(flag);
+            }
+        }
+        if matched_flags.len() > 1 {
+            return Err(
+                messages.ambiguous_input_argument(in_arg, matched_flags.join(", ").as_str())
+            );
+        }
+        Ok(matched_flags.pop())
+    }
+    fn _scan_count_arg_indexes(args: &[Arg], in_arg: &str) -> Option<Vec<usize>> {
+        // e.g. "-vvv" for "-v" counted 3 times
+        if in_arg.starts_with("--") || in_arg.len() <= 2 {
+            return None;
+        }
+        let mut arg_indexes = Vec::new();
+        for c in in_arg[1..].chars() {
+            let flag = format!("-{}", c);
+            let arg_idx = DumbArgParser::_scan_arg_index(args, &flag, -1)?;
+            if args[arg_idx].nature != ArgNature::Count {
+                return None;
+            }
+            arg_indexes.push(arg_idx);
+        }
+        Some(arg_indexes)
+    }
+    fn _scan_negated_arg_index(args: &[Arg], in_arg: &str) -> Option<usize> {
+        // e.g. "--no-color" for "--color"
+        if !in_arg.starts_with("--no-") {
+            return None;
+        }
+        let flag = format!("--{}", &in_arg[5..]);
+        let arg_idx = DumbArgParser::_scan_arg_index(args, &flag, -1)?;
+        if args[arg_idx].nature != ArgNature::Negatable {
+            return None;
+        }
+        Some(arg_idx)
+    }
+    fn _pick_negatable_flag(key: &ArgKey) -> String {
+        match key {
+            ArgKey::Flags(_, flags) => {
+                for flag in flags.iter() {
+                    if flag.starts_with("--") {
+                        return flag.clone();
+                    }
+                }
+                flags[0].clone()
+            }
+            ArgKey::Name(name) => name.clone(),
+        }
+    }
+    fn _increment_arg_count(&mut self, arg_idx: usize) -> Result<(), String> {
+        let count = match &self.input_arg_values[arg_idx] {
+            Some(ArgValue::I32(count)) => *count,
+            _ => 0,
+        };
+        self._set_arg_value(arg_idx, ArgValue::I32(count + 1), None)?;
+        self.input_arg_provided[arg_idx] = true;
+        Ok(())
+    }
+    fn _set_arg_value(
+        &mut self,
+        arg_idx: usize,
+        arg_value: ArgValue,
+        in_rest_args: Option<Vec<String>>,
+    ) -> Result<(), String> {
+        let arg: &Arg = &self.args[arg_idx];
+        Self::_verify_arg_range(self._messages(), arg, &arg_value)?;
+        if arg.nature == ArgNature::Append {
+            self.input_append_arg_data
+                .entry(arg_idx)
+                .or_default()
+                .push(arg_value.clone());
+        }
+        // match &arg.range {
+        //     ArgRange::Enum(enum_values) => {
+        //         let mut found = false;
+        //         for enum_value in enum_values.iter() {
+        //             if enum_value.compare(&arg_value) == 0 {
+        //                 found = true;
+        //                 break;
+        //             }
+        //         }
+        //         if !found {
+        //             let mut values = String::new();
+        //             for enum_value in enum_values.iter() {
+        //                 let value = enum_value.to_string();
+        //                 if values.len() > 0 {
+        //                     values.push_str(", ");
         //                 }
         //                 values.push_str(value.as_str());
         //             }
@@ -750,13 +1791,13 @@ impl DumbArgParser {
             if arg.multi_mode == ArgMultiMode::Regular {
                 let mut multi_arg_values = vec![arg_value];
                 for in_rest_arg in in_rest_args.iter() {
-                    let rest_arg_value = match arg.convert_in(in_rest_arg) {
+                    let rest_arg_value = match arg.convert_in(self._messages(), in_rest_arg) {
                         Ok(rest_arg_value) => rest_arg_value,
                         Err(err) => {
                             return Err(err /*.into()*/);
                         }
                     };
-                    Self::_verify_arg_range(arg, &rest_arg_value)?;
+                    Self::_verify_arg_range(self._messages(), arg, &rest_arg_value)?;
                     multi_arg_values.push(rest_arg_value.clone());
                 }
                 (Some(multi_arg_values), None)
@@ -792,12 +1833,16 @@ impl DumbArgParser {
         }
         Ok(())
     }
-    fn _verify_arg_range(arg: &Arg, arg_value: &ArgValue) -> Result<(), String> {
+    fn _verify_arg_range(
+        messages: &ArgMessagesType,
+        arg: &Arg,
+        arg_value: &ArgValue,
+    ) -> Result<(), String> {
         match &arg.constraint {
             ArgConstraint::Enums(enum_values) => {
                 let mut found = false;
                 for enum_value in enum_values.iter() {
-                    if enum_value.compare(arg_value) == 0 {
+                    if enum_value.compare(arg_value) == Ok(0) {
                         found = true;
                         break;
                     }
@@ -811,20 +1856,35 @@ impl DumbArgParser {
                         }
                         values.push_str(value.as_str());
                     }
-                    return Err(format!(
-                        "[{}] doesn't match any of the enum values [{}]",
-                        arg_value.to_string(),
-                        values
+                    let suggestion = if let ArgValue::String(value) = arg_value {
+                        let mut candidates = Vec::new();
+                        for enum_value in enum_values.iter() {
+                            candidates.push(enum_value.to_string());
+                            candidates.extend(enum_value.aliases.iter().cloned());
+                        }
+                        if arg.enums_ignore_case {
+                            let candidates: Vec<String> =
+                                candidates.iter().map(|c| c.to_lowercase()).collect();
+                            _suggest_similar(&value.to_lowercase(), candidates.iter())
+                        } else {
+                            _suggest_similar(value, candidates.iter())
+                        }
+                    } else {
+                        None
+                    };
+                    return Err(messages.not_match_enum(
+                        arg_value.to_string().as_str(),
+                        values.as_str(),
+                        suggestion.as_deref(),
                     ));
                 }
             }
             ArgConstraint::Range(min, max) => {
-                if arg_value.compare(min) < 0 || arg_value.compare(max) > 0 {
-                    return Err(format!(
-                        "[{}] is out of range [{}, {}]",
-                        arg_value.to_string(),
-                        min,
-                        max
+                if arg_value.compare(min)? < 0 || arg_value.compare(max)? > 0 {
+                    return Err(messages.out_of_range(
+                        arg_value.to_string().as_str(),
+                        min.to_string().as_str(),
+                        max.to_string().as_str(),
                     ));
                 }
             }
@@ -834,143 +1894,496 @@ impl DumbArgParser {
     }
     fn _show_help(&self, flag_args: &[Arg], position_args: &[Arg], err_msg: &Option<String>) {
         println!();
+        let help_width = Self::_get_help_width();
+        for line in self._compose_help(flag_args, position_args, err_msg, help_width) {
+            println!("{}", line);
+        }
+    }
+    /// compose the help message (as shown when "-h" is provided) formatted to fit the given width, mostly for illustration use;
+    /// when the help message is shown, the width is taken from the environment variable `COLUMNS` (default 80)
+    pub fn compose_help(&self, width: usize) -> String {
+        let (flag_args, position_args) = self._split_args();
+        self._compose_help(&flag_args, &position_args, &None, width)
+            .join("\n")
+    }
+    /// compose a roff man page (for `man`) of the parser, in the given manual section (normally 1);
+    /// it includes the description, the usage, the arguments and their descriptions, the subcommands (enum values of the "rest" argument)
+    /// as well as the relationships among arguments
+    pub fn compose_man_page(&self, section: u8) -> String {
+        let (flag_args, position_args) = self._split_args();
+        let program_name = self._get_program_name();
+        let mut lines = Vec::new();
+        lines.push(format!(
+            ".TH {} {}",
+            Self::_escape_roff(&program_name.to_uppercase()),
+            section
+        ));
+        lines.push(format!(".SH {}", self._messages().name_title()));
+        match &self.description {
+            Some(description) => lines.push(format!(
+                "{} \\- {}",
+                Self::_escape_roff(&program_name),
+                Self::_escape_roff(description)
+            )),
+            None => lines.push(Self::_escape_roff(&program_name)),
+        }
+        lines.push(format!(".SH {}", self._messages().synopsis_title()));
+        lines.push(Self::_escape_roff(
+            &self._compose_usage(&flag_args, &position_args),
+        ));
+        if let Some(description) = &self.description {
+            lines.push(format!(".SH {}", self._messages().description_title()));
+            lines.push(Self::_escape_roff(description));
+        }
+        for (title, entries) in self._compose_doc_sections(&flag_args, &position_args) {
+            if entries.is_empty() {
+                continue;
+            }
+            lines.push(format!(".SH {}", title));
+            for (opt, desc_items) in entries.iter() {
+                lines.push(".TP".to_owned());
+                lines.push(format!(".B {}", Self::_escape_roff(opt)));
+                for (index, desc_item) in desc_items.iter().enumerate() {
+                    if desc_item.enum_value.is_none() && desc_item.desc.is_empty() {
+                        continue;
+                    }
+                    if index > 0 {
+                        lines.push(".br".to_owned());
+                    }
+                    match &desc_item.enum_value {
+                        Some(enum_value) => lines.push(format!(
+                            "\\(bu {}: {}",
+                            Self::_escape_roff(enum_value),
+                            Self::_escape_roff(&desc_item.desc)
+                        )),
+                        None => lines.push(Self::_escape_roff(&desc_item.desc)),
+                    }
+                }
+            }
+        }
+        let mut man_page = lines.join("\n");
+        man_page.push('\n');
+        man_page
+    }
+    /// compose a Markdown reference of the parser; it has the same content as [`DumbArgParser::compose_man_page`]
+    pub fn compose_markdown(&self) -> String {
+        let (flag_args, position_args) = self._split_args();
+        let mut lines = Vec::new();
+        lines.push(format!(
+            "# {}",
+            Self::_escape_markdown(&self._get_program_name())
+        ));
+        if let Some(description) = &self.description {
+            lines.push(String::new());
+            lines.push(Self::_escape_markdown(description));
+        }
+        lines.push(String::new());
+        lines.push(format!("## {}", self._messages().usage_label()));
+        lines.push(String::new());
+        lines.push("```text".to_owned());
+        lines.push(self._compose_usage(&flag_args, &position_args));
+        lines.push("```".to_owned());
+        for (title, entries) in self._compose_doc_sections(&flag_args, &position_args) {
+            if entries.is_empty() {
+                continue;
+            }
+            lines.push(String::new());
+            lines.push(format!("## {}", title));
+            lines.push(String::new());
+            for (opt, desc_items) in entries.iter() {
+                lines.push(format!("* `{}`", opt));
+                for desc_item in desc_items.iter() {
+                    match &desc_item.enum_value {
+                        Some(enum_value) => {
+                            if desc_item.desc.is_empty() {
+                                lines.push(format!("    * `{}`", enum_value));
+                            } else {
+                                lines.push(format!(
+                                    "    * `{}`: {}",
+                                    enum_value,
+                                    Self::_escape_markdown(&desc_item.desc)
+                                ));
+                            }
+                        }
+                        None => {
+                            if !desc_item.desc.is_empty() {
+                                lines.push(format!(
+                                    "  * {}",
+                                    Self::_escape_markdown(&desc_item.desc)
+                                ))
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let mut markdown = lines.join("\n");
+        markdown.push('\n');
+        markdown
+    }
+    /// like [`DumbArgParser::_compose_help_sections`], but with the subcommands (enum values of the "rest" argument) section added
+    fn _compose_doc_sections(
+        &self,
+        flag_args: &[Arg],
+        position_args: &[Arg],
+    ) -> Vec<(String, Vec<ArgHelpEntry>)> {
+        let mut subcommand_entries = Vec::new();
+        for position_arg in position_args.iter() {
+            if position_arg.multi_mode != ArgMultiMode::Rest {
+                continue;
+            }
+            if let ArgConstraint::Enums(enum_values) = &position_arg.constraint {
+                for enum_value in enum_values.iter() {
+                    let description = self._compose_enum_value_desc(enum_value);
+                    subcommand_entries
+                        .push((enum_value.to_string(), vec![ArgHelpItem::new(description)]));
+                }
+            }
+        }
+        let mut sections = self._compose_help_sections(flag_args, position_args);
+        sections.insert(
+            1,
+            (self._messages().subcommands_title(), subcommand_entries),
+        );
+        sections
+    }
+    fn _escape_roff(text: &str) -> String {
+        let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+        if escaped.starts_with('.') || escaped.starts_with('\'') {
+            format!("\\&{}", escaped)
+        } else {
+            escaped
+        }
+    }
+    fn _escape_markdown(text: &str) -> String {
+        let mut escaped = String::new();
+        for c in text.chars() {
+            if "\\`*_<>|".contains(c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+    fn _get_help_width() -> usize {
+        match env::var("COLUMNS") {
+            Ok(columns) => columns.trim().parse::<usize>().unwrap_or(80),
+            Err(_) => 80,
+        }
+    }
+    fn _compose_help(
+        &self,
+        flag_args: &[Arg],
+        position_args: &[Arg],
+        err_msg: &Option<String>,
+        width: usize,
+    ) -> Vec<String> {
+        let width = cmp::max(width, 40);
+        let mut lines = Vec::new();
         if let Some(err_msg) = err_msg {
-            println!("| !!!");
-            println!("| !!! INVALID INPUT ARGUMENT: {}", err_msg);
-            println!("| !!!");
+            lines.push("| !!!".to_owned());
+            let err_msg = format!(
+                "{}: {}",
+                self._messages().invalid_input_argument_label(),
+                err_msg
+            );
+            for chunk in Self::_wrap_text(&err_msg, width - 6) {
+                lines.push(format!("| !!! {}", chunk));
+            }
+            lines.push("| !!!".to_owned());
         }
         let usage = self._compose_usage(flag_args, position_args);
-        print!("| USAGE: {usage}");
-        println!();
-        match &self.description {
-            Some(description) => {
-                println!("| : {description}");
+        let usage_label = self._messages().usage_label();
+        let usage_label_width = usage_label.chars().count() + 2;
+        let usage_width = cmp::max(width - 2 - usage_label_width, width / 2);
+        for (index, chunk) in Self::_wrap_text(&usage, usage_width).iter().enumerate() {
+            if index == 0 {
+                lines.push(format!("| {}: {}", usage_label, chunk));
+            } else {
+                lines.push(format!("| {}{}", " ".repeat(usage_label_width), chunk));
             }
-            None => {
-                println!();
+        }
+        if let Some(description) = &self.description {
+            for chunk in Self::_wrap_text(description, width - 4) {
+                lines.push(format!("| : {}", chunk));
+            }
+        }
+        let sections = self._compose_help_sections(flag_args, position_args);
+        let mut opt_width = 0;
+        for (_, entries) in sections.iter() {
+            for (opt, _) in entries.iter() {
+                opt_width = cmp::max(opt_width, opt.chars().count());
+            }
+        }
+        let opt_width = cmp::min(opt_width, width / 3);
+        let desc_width = width - 4 - opt_width - 2;
+        let lt_comps = dlt_comps![
+            "|   ",
+            dltc!("opt", fixed_width = opt_width as u16),
+            "  ",
+            dltc!("desc", max_width = desc_width as u16, optional = true)
+        ];
+        let ltemp = DumbLineTemplate::new(0, width as u16, &lt_comps);
+        for (title, entries) in sections.iter() {
+            if entries.is_empty() {
+                continue;
+            }
+            lines.push(format!("| {}:", title));
+            for (opt, desc_items) in entries.iter() {
+                let mut opt = opt.as_str();
+                if opt.chars().count() > opt_width {
+                    lines.push(format!("|   {}", opt));
+                    opt = "";
+                }
+                for ArgHelpItem {
+                    desc_prefix,
+                    desc: desc_item,
+                    ..
+                } in desc_items.iter()
+                {
+                    // desc_prefix is kept as is, with the wrapped lines of desc_item aligned after it;
+                    // unless desc_prefix is too wide, in which case it takes a line of its own
+                    let mut desc_prefix_width = desc_prefix.chars().count();
+                    let mut descs = Vec::new();
+                    if 2 * desc_prefix_width > desc_width {
+                        descs.push(desc_prefix.trim_end().to_string());
+                        desc_prefix_width = 0;
+                    }
+                    let desc_chunks = Self::_wrap_text(desc_item, desc_width - desc_prefix_width);
+                    for (index, desc_chunk) in desc_chunks.iter().enumerate() {
+                        if desc_chunk.is_empty() && !descs.is_empty() {
+                            continue;
+                        }
+                        if index == 0 && desc_prefix_width > 0 {
+                            descs.push(format!("{}{}", desc_prefix, desc_chunk));
+                        } else {
+                            descs.push(format!("{}{}", " ".repeat(desc_prefix_width), desc_chunk));
+                        }
+                    }
+                    for desc in descs.iter() {
+                        let line = ltemp
+                            .format_ex(|key| match key {
+                                "opt" => Some((opt.to_string(), opt.chars().count() as u16)),
+                                "desc" => Some((desc.clone(), desc.chars().count() as u16)),
+                                _ => None,
+                            })
+                            .unwrap();
+                        lines.push(line.trim_end().to_string());
+                        opt = "";
+                    }
+                }
+            }
+        }
+        lines
+    }
+    /// compose the sections [(title, entries)] of the help message; shared by the help screen and the reference docs
+    fn _compose_help_sections(
+        &self,
+        flag_args: &[Arg],
+        position_args: &[Arg],
+    ) -> Vec<(String, Vec<ArgHelpEntry>)> {
+        let mut position_entries = Vec::new();
+        for position_arg in position_args.iter() {
+            let name = match &position_arg.key {
+                ArgKey::Name(name) => name,
+                _ => panic!(),
+            };
+            let mut opt = format!("<{name}>");
+            if position_arg.multi_mode != ArgMultiMode::None {
+                opt.push_str(" ...");
             }
+            position_entries.push((opt, self._compose_help_arg_desc_items(position_arg)));
         }
-        println!("| . -h, --help : HELP");
+        let mut flag_entries = vec![(
+            "-h, --help".to_owned(),
+            vec![ArgHelpItem::new(self._messages().help_desc())],
+        )];
         for flag_arg in flag_args.iter() {
             let (name, flags) = match &flag_arg.key {
                 ArgKey::Flags(name, flags) => (name, flags),
                 _ => panic!(),
             };
-            print!("| . ");
+            let flags: Vec<&String> = flags
+                .iter()
+                .filter(|flag| !flag_arg.deprecated_flags.contains(flag))
+                .collect();
+            let mut opt = String::new();
             for (index, flag) in flags.iter().enumerate() {
                 if index > 0 {
-                    print!(", ");
+                    opt.push_str(", ");
                 }
-                let f = if flag_arg.nature == ArgNature::Fixed {
-                    flag.to_string()
+                if flag_arg.nature == ArgNature::Fixed
+                    || flag_arg.nature == ArgNature::Count
+                    || flag_arg.nature == ArgNature::Negatable
+                {
+                    opt.push_str(flag);
                 } else {
-                    format!("{flag} {name}")
-                };
-                print!("{f}");
-            }
-            self._show_help_arg_desc(flag_arg);
-            // if flag_arg.nature == ArgNature::Fixed {
-            //     println!(" : FLAG [{}]", flag_arg.value);
-            // } else if flag_arg.nature == ArgNature::Optional {
-            //     println!(" : OPTIONAL; default [{}]", flag_arg.value);
-            // } else {
-            //     println!(" : REQUIRED; e.g. [{}]", flag_arg.value);
-            // }
-            // match &flag_arg.description {
-            //     Some(description) => {
-            //         println!("|   -- {description}")
-            //     }
-            //     None => {}
-            // }
+                    opt.push_str(format!("{flag} {name}").as_str());
+                }
+            }
+            if flag_arg.nature == ArgNature::Negatable {
+                for flag in flags.iter() {
+                    if flag.starts_with("--") {
+                        opt.push_str(format!(", --no-{}", &flag[2..]).as_str());
+                    }
+                }
+            }
+            if flag_arg.multi_mode != ArgMultiMode::None {
+                opt.push_str(" ...");
+            }
+            flag_entries.push((opt, self._compose_help_arg_desc_items(flag_arg)));
         }
-        for position_arg in position_args.iter() {
-            let name = match &position_arg.key {
-                ArgKey::Name(name) => name,
-                _ => panic!(),
-            };
-            print!("| . <{name}>");
-            self._show_help_arg_desc(position_arg);
-            // print!("| . {name}");
-            // if position_arg.nature == ArgNature::Optional {
-            //     println!(" : OPTIONAL; default [{}]", position_arg.value);
-            // } else {
-            //     println!(" : REQUIRED; e.g. [{}]", position_arg.value);
-            // }
-            // match &position_arg.description {
-            //     Some(description) => {
-            //         println!("|   -- {description}")
-            //     }
-            //     None => {}
-            // }
-        }
-    }
-    fn _show_help_arg_desc(&self, arg: &Arg) {
-        if arg.multi_mode != ArgMultiMode::None {
-            print!(" ...");
-        }
-        if arg.nature == ArgNature::Fixed {
-            println!(" : FLAG [{}]", arg.value);
+        let mut relation_entries = Vec::new();
+        for group in self.groups.iter() {
+            let desc = self._messages().group_desc(
+                &group.rule,
+                self._compose_arg_names(&group.arg_indexes).as_str(),
+            );
+            relation_entries.push((
+                self._messages().group_name(&group.name),
+                vec![ArgHelpItem::new(desc)],
+            ));
+        }
+        for requires in self.requires.iter() {
+            let desc = self._messages().requires_desc(
+                self._compose_arg_names(&requires.required_arg_indexes)
+                    .as_str(),
+            );
+            relation_entries.push((
+                self.args[requires.arg_idx].key.get_a_name(),
+                vec![ArgHelpItem::new(desc)],
+            ));
+        }
+        vec![
+            (
+                self._messages().positional_arguments_title(),
+                position_entries,
+            ),
+            (self._messages().options_title(), flag_entries),
+            (self._messages().relationships_title(), relation_entries),
+        ]
+    }
+    fn _compose_help_arg_desc_items(&self, arg: &Arg) -> Vec<ArgHelpItem> {
+        let messages = self._messages();
+        let mut desc_items = Vec::new();
+        let value = arg.value.to_string();
+        let example = match &arg.value {
+            ArgValue::String(value) if value.is_empty() => None,
+            _ => Some(value.as_str()),
+        };
+        let nature = if arg.nature == ArgNature::Fixed {
+            messages.fixed_flag_desc(&value)
+        } else if arg.nature == ArgNature::Count {
+            messages.count_flag_desc()
+        } else if arg.nature == ArgNature::Negatable {
+            messages.negatable_flag_desc(&value)
+        } else if arg.nature == ArgNature::Append {
+            messages.append_flag_desc(example)
         } else if arg.nature == ArgNature::Optional {
-            println!(" : OPTIONAL; default [{}]", arg.value);
+            messages.optional_desc(&value)
         } else {
-            print!(" : REQUIRED");
-            let show_example = match &arg.value {
-                ArgValue::String(value) => !value.is_empty(),
-                _ => true,
-            };
-            if show_example {
-                print!("; e.g. {}", arg.value);
-            }
-            if arg.multi_mode != ArgMultiMode::None {
-                print!(" ...");
-            }
-            println!();
+            messages.required_desc(example, arg.multi_mode != ArgMultiMode::None)
+        };
+        desc_items.push(ArgHelpItem::new(nature));
+        if let Some(hint) = &arg.deprecated {
+            desc_items.push(ArgHelpItem::new(messages.deprecated_desc(hint)));
         }
-        match &arg.description {
-            Some(description) => {
-                println!("|   : {description}")
-            }
-            None => {}
+        if let Some(description) = &arg.description {
+            desc_items.push(ArgHelpItem::new(description.clone()));
         }
         match &arg.constraint {
             ArgConstraint::Range(min, max) => {
-                println!("|   : range: [{}, {}]", min.to_string(), max.to_string());
+                desc_items.push(ArgHelpItem::new(
+                    messages.range_desc(min.to_string().as_str(), max.to_string().as_str()),
+                ));
             }
             ArgConstraint::Enums(enum_values) => {
-                let mut single_line = true;
-                for enum_value in enum_values.iter() {
-                    if enum_value.description.is_some() {
-                        single_line = false;
-                        break;
-                    }
-                }
+                let single_line = enum_values.iter().all(|enum_value| {
+                    enum_value.description.is_none() && enum_value.aliases.is_empty()
+                });
                 if single_line {
                     let mut values = String::new();
                     for enum_value in enum_values.iter() {
-                        let value = enum_value.to_string();
                         if !values.is_empty() {
                             values.push_str(", ");
                         }
-                        values.push_str(value.as_str());
+                        values.push_str(enum_value.to_string().as_str());
                     }
-                    println!("|   : enum values: [{}]", values);
+                    desc_items.push(ArgHelpItem::new(messages.enum_values_desc(Some(&values))));
                 } else {
+                    desc_items.push(ArgHelpItem::new(messages.enum_values_desc(None)));
+                    let value_width = enum_values
+                        .iter()
+                        .map(|enum_value| enum_value.to_string().chars().count())
+                        .max()
+                        .unwrap_or(0);
                     for enum_value in enum_values.iter() {
-                        let value = enum_value.to_string();
-                        print!("|   : . [{}]", value);
-                        match &enum_value.description {
-                            Some(description) => {
-                                println!(" : {description}");
-                            }
-                            None => {
-                                println!();
-                            }
-                        }
+                        let value = format!("{:value_width$}", enum_value.to_string());
+                        let description = self._compose_enum_value_desc(enum_value);
+                        desc_items.push(ArgHelpItem {
+                            desc_prefix: format!("- {} : ", value),
+                            desc: description,
+                            enum_value: Some(enum_value.to_string()),
+                        });
                     }
                 }
             }
             ArgConstraint::None => {}
         }
+        desc_items
+    }
+    /// the description of the enum value, with the aliases (if any) appended
+    fn _compose_enum_value_desc(&self, enum_value: &ArgEnum) -> String {
+        let mut description = match &enum_value.description {
+            Some(description) => description.clone(),
+            None => String::new(),
+        };
+        if !enum_value.aliases.is_empty() {
+            if !description.is_empty() {
+                description.push(' ');
+            }
+            description.push_str(
+                self._messages()
+                    .enum_aliases_desc(enum_value.aliases.join(", ").as_str())
+                    .as_str(),
+            );
+        }
+        description
+    }
+    /// word-wrap the text into chunks fitting the width
+    fn _wrap_text(text: &str, width: usize) -> Vec<String> {
+        let mut chunks = Vec::new();
+        let mut chunk = String::new();
+        let mut chunk_width = 0;
+        for word in text.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            loop {
+                if chunk_width > 0 && chunk_width + 1 + word.len() <= width {
+                    chunk.push(' ');
+                    chunk.extend(word.iter());
+                    chunk_width += 1 + word.len();
+                    break;
+                }
+                if chunk_width > 0 {
+                    chunks.push(chunk);
+                    chunk = String::new();
+                    chunk_width = 0;
+                    continue;
+                }
+                if word.len() <= width {
+                    chunk.extend(word.iter());
+                    chunk_width = word.len();
+                    break;
+                }
+                let rest = word.split_off(width);
+                chunks.push(word.iter().collect());
+                word = rest;
+            }
+        }
+        if chunk_width > 0 || chunks.is_empty() {
+            chunks.push(chunk);
+        }
+        chunks
     }
     fn _compose_usage(&self, flag_args: &[Arg], position_args: &[Arg]) -> String {
         let mut usage = String::new();
@@ -985,11 +2398,17 @@ impl DumbArgParser {
             let flag = &flags[0];
             let f = if flag_arg.nature == ArgNature::Fixed {
                 flag.to_string()
+            } else if flag_arg.nature == ArgNature::Count {
+                format!("{flag} ...")
+            } else if flag_arg.nature == ArgNature::Append {
+                format!("{flag} {val_name} ...")
+            } else if flag_arg.nature == ArgNature::Negatable {
+                let flag = Self::_pick_negatable_flag(&flag_arg.key);
+                format!("--[no-]{}", &flag[2..])
             } else {
                 format!("{flag} {val_name}")
             };
-            let f = if flag_arg.nature == ArgNature::Fixed || flag_arg.nature == ArgNature::Optional
-            {
+            let f = if flag_arg.nature != ArgNature::Regular {
                 format!(" [{f}]")
             } else {
                 format!(" {f}")
@@ -1032,6 +2451,28 @@ impl DumbArgParser {
 //     }
 // }
 
+/// an entry of the help message: (option, [description item])
+type ArgHelpEntry = (String, Vec<ArgHelpItem>);
+
+/// a description item of an entry of the help message
+#[derive(Debug, Clone)]
+struct ArgHelpItem {
+    /// the prefix (like "- fast : ") that the wrapped lines of the description are aligned after
+    desc_prefix: String,
+    desc: String,
+    /// the enum value the item describes, if any
+    enum_value: Option<String>,
+}
+impl ArgHelpItem {
+    fn new(desc: String) -> ArgHelpItem {
+        ArgHelpItem {
+            desc_prefix: String::new(),
+            desc,
+            enum_value: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum ArgKey {
     Name(String),
@@ -1055,6 +2496,13 @@ struct Arg {
     nature: ArgNature,
     multi_mode: ArgMultiMode,
     description: Option<String>,
+    parse_fn: Option<ArgParseFn>,
+    must_exist: bool,
+    validators: Vec<ArgValidateFn>,
+    hidden: bool,
+    deprecated: Option<String>,
+    deprecated_flags: Vec<String>,
+    enums_ignore_case: bool,
 }
 impl Arg {
     fn new(
@@ -1072,50 +2520,348 @@ impl Arg {
             nature,
             multi_mode,
             description: description.clone(),
+            parse_fn: None,
+            must_exist: false,
+            validators: Vec::new(),
+            hidden: false,
+            deprecated: None,
+            deprecated_flags: Vec::new(),
+            enums_ignore_case: false,
         }
     }
-    fn convert_in(&self, val: &str) -> Result<ArgValue, String> {
+    fn convert_in(&self, messages: &ArgMessagesType, val: &str) -> Result<ArgValue, String> {
+        let canonical_val = self.canonicalize_enum_in(val);
+        let val = canonical_val.as_deref().unwrap_or(val);
+        if let Some(parse_fn) = &self.parse_fn {
+            return (parse_fn.0)(val);
+        }
         let value = match self.value {
             ArgValue::I32(_) => {
                 let v = match val.parse::<i32>() {
                     Ok(v) => v,
-                    Err(e) => return Err(format!("failed to parse \"{}\" as i32", val)),
+                    Err(e) => return Err(messages.failed_to_parse(val, "i32")),
                 };
                 ArgValue::I32(v)
             }
             ArgValue::I64(_) => {
                 let v = match val.parse::<i64>() {
                     Ok(v) => v,
-                    Err(e) => return Err(format!("failed to parse \"{}\" as i64", val)),
+                    Err(e) => return Err(messages.failed_to_parse(val, "i64")),
                 };
                 ArgValue::I64(v)
             }
             ArgValue::F32(_) => {
                 let v = match val.parse::<f32>() {
                     Ok(v) => v,
-                    Err(e) => return Err(format!("failed to parse \"{}\" as f32", val)),
+                    Err(e) => return Err(messages.failed_to_parse(val, "f32")),
                 };
                 ArgValue::F32(v)
             }
             ArgValue::F64(_) => {
                 let v = match val.parse::<f64>() {
                     Ok(v) => v,
-                    Err(e) => return Err(format!("failed to parse \"{}\" as f64", val)),
+                    Err(e) => return Err(messages.failed_to_parse(val, "f64")),
                 };
                 ArgValue::F64(v)
             }
             ArgValue::Bool(_) => {
                 let v = match val.parse::<bool>() {
                     Ok(v) => v,
-                    Err(e) => return Err(format!("failed to parse \"{}\" as bool", val)),
+                    Err(e) => return Err(messages.failed_to_parse(val, "bool")),
                 };
                 ArgValue::Bool(v)
             }
+            ArgValue::U32(_) => {
+                let v = match val.parse::<u32>() {
+                    Ok(v) => v,
+                    Err(e) => return Err(messages.failed_to_parse(val, "u32")),
+                };
+                ArgValue::U32(v)
+            }
+            ArgValue::U64(_) => {
+                let v = match val.parse::<u64>() {
+                    Ok(v) => v,
+                    Err(e) => return Err(messages.failed_to_parse(val, "u64")),
+                };
+                ArgValue::U64(v)
+            }
+            ArgValue::Usize(_) => {
+                let v = match val.parse::<usize>() {
+                    Ok(v) => v,
+                    Err(e) => return Err(messages.failed_to_parse(val, "usize")),
+                };
+                ArgValue::Usize(v)
+            }
+            ArgValue::Path(_) => {
+                let v = PathBuf::from(val);
+                if self.must_exist && !v.exists() {
+                    return Err(messages.path_not_exist(val));
+                }
+                ArgValue::Path(v)
+            }
+            ArgValue::Duration(_) => match parse_duration(val) {
+                Ok(v) => ArgValue::Duration(v),
+                Err(e) => return Err(messages.failed_to_parse(val, "duration")),
+            },
+            ArgValue::ByteSize(_) => match parse_byte_size(val) {
+                Ok(v) => ArgValue::ByteSize(v),
+                Err(e) => return Err(messages.failed_to_parse(val, "byte size")),
+            },
             ArgValue::String(_) => ArgValue::String(val.to_string()),
             //ArgValue::StaticStr(_) => ArgValue::String(val.to_string()),
         };
         Ok(value)
     }
+    /// the canonical enum value of the input, in case the input is an alias or is of different case; see [`DumbArgBuilder::set_enum_aliases`]
+    fn canonicalize_enum_in(&self, val: &str) -> Option<String> {
+        let enum_values = match &self.constraint {
+            ArgConstraint::Enums(enum_values) => enum_values,
+            _ => return None,
+        };
+        enum_values
+            .iter()
+            .find(|enum_value| enum_value.matches(val, self.enums_ignore_case))
+            .map(|enum_value| enum_value.to_string())
+    }
+}
+
+/// suggest the candidate most similar to the input (by edit distance), if it is similar enough
+fn _suggest_similar<'a, I: Iterator<Item = &'a String>>(
+    input: &str,
+    candidates: I,
+) -> Option<String> {
+    let mut suggestion: Option<(&String, usize)> = None;
+    for candidate in candidates {
+        let max_distance = cmp::min(3, candidate.trim_start_matches('-').chars().count() / 3);
+        let distance = _edit_distance(input, candidate);
+        if distance == 0 || distance > max_distance {
+            continue;
+        }
+        match suggestion {
+            Some((_, best_distance)) if best_distance <= distance => {}
+            _ => suggestion = Some((candidate, distance)),
+        }
+    }
+    suggestion.map(|(candidate, _)| candidate.clone())
+}
+
+/// the "optimal string alignment" edit distance, where transposition of adjacent characters counts as a single edit
+fn _edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = cmp::min(
+                cmp::min(d[i - 1][j] + 1, d[i][j - 1] + 1),
+                d[i - 1][j - 1] + cost,
+            );
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = cmp::min(d[i][j], d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// user-defined validator of input argument value; see [`DumbArgBuilder::add_validator`]
+#[derive(Clone)]
+struct ArgValidateFn(Arc<ArgValidateFnType>);
+type ArgValidateFnType = dyn Fn(&ArgValue) -> Result<(), String> + Send + Sync + RefUnwindSafe;
+impl fmt::Debug for ArgValidateFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ArgValidateFn")
+    }
+}
+
+/// user-defined validator of the parsed arguments as a whole; see [`DumbArgParser::add_validator`]
+#[derive(Clone)]
+struct ArgParserValidateFn(Arc<ArgParserValidateFnType>);
+type ArgParserValidateFnType =
+    dyn Fn(&DumbArgParser) -> Result<(), String> + Send + Sync + RefUnwindSafe;
+impl fmt::Debug for ArgParserValidateFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ArgParserValidateFn")
+    }
+}
+
+/// the reader for prompting missing arguments; see [`DumbArgParser::set_prompt_reader`]
+#[derive(Clone)]
+struct ArgPromptReader(Arc<Mutex<ArgPromptReaderType>>);
+type ArgPromptReaderType = dyn BufRead + Send;
+impl fmt::Debug for ArgPromptReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ArgPromptReader")
+    }
+}
+
+/// the message catalog of the parser; see [`DumbArgParser::set_message_catalog`]
+#[derive(Clone)]
+struct ArgMessages(Arc<ArgMessagesType>);
+type ArgMessagesType = dyn ArgMessageCatalogTrait + Send + Sync + RefUnwindSafe;
+impl fmt::Debug for ArgMessages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ArgMessages")
+    }
+}
+
+/// user-defined function for converting input argument to [`ArgValue`]; see [`DumbArgBuilder::set_parse_fn`]
+#[derive(Clone)]
+struct ArgParseFn(Arc<ArgParseFnType>);
+type ArgParseFnType = dyn Fn(&str) -> Result<ArgValue, String> + Send + Sync + RefUnwindSafe;
+impl fmt::Debug for ArgParseFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ArgParseFn")
+    }
+}
+
+/// parse duration like "500us", "250ms", "5s", "1m30s", "1.5h" or "2d"; plain number is taken as seconds
+fn parse_duration(val: &str) -> Result<Duration, String> {
+    let err_msg = || format!("failed to parse \"{}\" as duration", val);
+    if let Ok(secs) = val.parse::<f64>() {
+        if secs < 0.0 || !secs.is_finite() {
+            return Err(err_msg());
+        }
+        return Duration::try_from_secs_f64(secs).map_err(|_| err_msg());
+    }
+    let mut total = Duration::ZERO;
+    let mut chars = val.chars().peekable();
+    if chars.peek().is_none() {
+        return Err(err_msg());
+    }
+    while chars.peek().is_some() {
+        let mut number = String::new();
+        while let Some(c) = chars.peek() {
+            if c.is_ascii_digit() || *c == '.' {
+                number.push(*c);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let mut unit = String::new();
+        while let Some(c) = chars.peek() {
+            if c.is_ascii_alphabetic() {
+                unit.push(*c);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let unit_nanos: u64 = match unit.as_str() {
+            "ns" => 1,
+            "us" => 1_000,
+            "ms" => 1_000_000,
+            "s" => 1_000_000_000,
+            "m" => 60_000_000_000,
+            "h" => 3_600_000_000_000,
+            "d" => 86_400_000_000_000,
+            _ => return Err(err_msg()),
+        };
+        // a whole number is counted exactly in nanoseconds, so that a formatted duration is parsed back as is
+        let part = number
+            .parse::<u64>()
+            .ok()
+            .and_then(|number| number.checked_mul(unit_nanos))
+            .map(Duration::from_nanos)
+            .or_else(|| {
+                let number = number.parse::<f64>().ok()?;
+                Duration::try_from_secs_f64(number * unit_nanos as f64 / 1e9).ok()
+            });
+        total = match part.and_then(|part| total.checked_add(part)) {
+            Some(total) => total,
+            None => return Err(err_msg()),
+        };
+    }
+    Ok(total)
+}
+
+fn format_duration(duration: &Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos == 0 {
+        return "0s".to_owned();
+    }
+    if nanos % 1000 != 0 {
+        return format!("{}ns", nanos);
+    }
+    if nanos % 1_000_000 != 0 {
+        return format!("{}us", nanos / 1000);
+    }
+    let millis = duration.as_millis();
+    if millis % 1000 != 0 {
+        return format!("{}ms", millis);
+    }
+    let mut secs = duration.as_secs();
+    let mut formatted = String::new();
+    for (unit, unit_secs) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)] {
+        if secs >= unit_secs {
+            formatted.push_str(format!("{}{}", secs / unit_secs, unit).as_str());
+            secs %= unit_secs;
+        }
+    }
+    formatted
+}
+
+/// parse byte size like "512", "4k", "10MiB" or "1.5GB";
+/// "k", "M", "G" and "T" (and the "iB" forms) are multiples of 1024, while "kB", "MB", "GB" and "TB" are multiples of 1000
+fn parse_byte_size(val: &str) -> Result<ArgByteSize, String> {
+    let err_msg = || format!("failed to parse \"{}\" as byte size", val);
+    let unit_idx = val
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(val.len());
+    let (number, unit) = val.split_at(unit_idx);
+    let number = match number.parse::<f64>() {
+        Ok(number) => number,
+        Err(_) => return Err(err_msg()),
+    };
+    let multiplier: u64 = match unit.trim() {
+        "" | "B" | "b" => 1,
+        "k" | "K" | "KiB" | "kiB" => 1 << 10,
+        "M" | "MiB" => 1 << 20,
+        "G" | "GiB" => 1 << 30,
+        "T" | "TiB" => 1 << 40,
+        "kB" | "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        _ => return Err(err_msg()),
+    };
+    let bytes = number * multiplier as f64;
+    if bytes > u64::MAX as f64 {
+        return Err(err_msg());
+    }
+    Ok(ArgByteSize(bytes.round() as u64))
+}
+
+fn format_byte_size(size: &ArgByteSize) -> String {
+    let bytes = size.0;
+    for (unit, unit_bytes) in [
+        ("TiB", 1u64 << 40),
+        ("GiB", 1 << 30),
+        ("MiB", 1 << 20),
+        ("KiB", 1 << 10),
+    ] {
+        if bytes >= unit_bytes && bytes % unit_bytes == 0 {
+            return format!("{}{}", bytes / unit_bytes, unit);
+        }
+    }
+    format!("{}B", bytes)
+}
+
+/// byte size argument value, like "10MiB"; the value is the number of bytes; see [`DumbArgBuilder::value`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ArgByteSize(pub u64);
+impl fmt::Display for ArgByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", format_byte_size(self))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1123,6 +2869,9 @@ enum ArgNature {
     Regular,
     Optional,
     Fixed,
+    Count,
+    Append,
+    Negatable,
 }
 
 #[derive(Debug, Clone)]
@@ -1136,15 +2885,27 @@ enum ArgConstraint {
 struct ArgEnum {
     arg_value: ArgValue,
     description: Option<String>,
+    aliases: Vec<String>,
 }
 impl ArgEnum {
     fn new(arg_value: ArgValue, description: Option<String>) -> ArgEnum {
         ArgEnum {
             arg_value,
             description: description.clone(),
+            aliases: Vec::new(),
+        }
+    }
+    /// whether the input is the enum value or one of its aliases
+    fn matches(&self, val: &str, ignore_case: bool) -> bool {
+        let value = self.to_string();
+        let mut candidates = std::iter::once(&value).chain(self.aliases.iter());
+        if ignore_case {
+            candidates.any(|candidate| candidate.to_lowercase() == val.to_lowercase())
+        } else {
+            candidates.any(|candidate| candidate == val)
         }
     }
-    fn compare(&self, arg_value: &ArgValue) -> i32 {
+    fn compare(&self, arg_value: &ArgValue) -> Result<i32, String> {
         self.arg_value.compare(arg_value)
     }
     fn to_string(&self) -> String {
@@ -1152,6 +2913,284 @@ impl ArgEnum {
     }
 }
 
+/// the rule of a group of arguments -- see [`DumbArgParser::add_group`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgGroupRule {
+    /// no rule; just grouping the arguments
+    None,
+    /// the arguments are mutually exclusive
+    AtMostOne,
+    /// exactly one of the arguments must be provided
+    ExactlyOne,
+    /// at least one of the arguments must be provided
+    AtLeastOne,
+}
+
+/// the catalog of the user-facing messages (help screen, input errors, warnings, etc.) of [`DumbArgParser`];
+/// every message has an English default, so only the messages to be localized need to be implemented; e.g.
+/// ```
+/// use rusty_dumb_tools::prelude::*;
+/// struct FrenchMessages;
+/// impl ArgMessageCatalogTrait for FrenchMessages {
+///     fn unknown_input_argument(&self, in_arg: &str, suggestion: Option<&str>) -> String {
+///         format!("argument inconnu [{}]", in_arg)
+///     }
+///     fn required_desc(&self, example: Option<&str>, multi: bool) -> String {
+///         "OBLIGATOIRE".to_owned()
+///     }
+/// }
+/// let mut parser = DumbArgParser::new();
+/// parser.set_message_catalog(FrenchMessages);
+/// dap_arg!("name").add_to(&mut parser).unwrap();
+/// let process_res = parser.check_process_args(vec!["-x"], false);
+/// assert_eq!("argument inconnu [-x]", process_res.unwrap_err().to_string());
+/// assert!(parser.compose_help(60).contains("OBLIGATOIRE"));
+/// ```
+/// note that errors from user-defined validators / parse functions, as well as errors in specifying the arguments, are not covered
+pub trait ArgMessageCatalogTrait {
+    /// label of the usage line of the help screen
+    fn usage_label(&self) -> String {
+        "USAGE".to_owned()
+    }
+    /// label of the error shown on the help screen
+    fn invalid_input_argument_label(&self) -> String {
+        "INVALID INPUT ARGUMENT".to_owned()
+    }
+    /// title of the positional arguments section
+    fn positional_arguments_title(&self) -> String {
+        "POSITIONAL ARGUMENTS".to_owned()
+    }
+    /// title of the flag arguments section
+    fn options_title(&self) -> String {
+        "OPTIONS".to_owned()
+    }
+    /// title of the subcommands section (man page and Markdown only)
+    fn subcommands_title(&self) -> String {
+        "SUBCOMMANDS".to_owned()
+    }
+    /// title of the relationships (groups and requires) section
+    fn relationships_title(&self) -> String {
+        "RELATIONSHIPS".to_owned()
+    }
+    /// title of the name section of man page
+    fn name_title(&self) -> String {
+        "NAME".to_owned()
+    }
+    /// title of the synopsis section of man page
+    fn synopsis_title(&self) -> String {
+        "SYNOPSIS".to_owned()
+    }
+    /// title of the description section of man page
+    fn description_title(&self) -> String {
+        "DESCRIPTION".to_owned()
+    }
+    /// description of "-h, --help"
+    fn help_desc(&self) -> String {
+        "HELP".to_owned()
+    }
+    /// description of required argument; `example` is the example value, if any
+    fn required_desc(&self, example: Option<&str>, multi: bool) -> String {
+        let mut desc = "REQUIRED".to_owned();
+        if let Some(example) = example {
+            desc.push_str(format!("; e.g. {}", example).as_str());
+        }
+        if multi {
+            desc.push_str(" ...");
+        }
+        desc
+    }
+    /// description of optional argument
+    fn optional_desc(&self, default: &str) -> String {
+        format!("OPTIONAL; default [{}]", default)
+    }
+    /// description of fixed flag, see [`DumbArgBuilder::fixed`]
+    fn fixed_flag_desc(&self, value: &str) -> String {
+        format!("FLAG [{}]", value)
+    }
+    /// description of negatable flag, see [`DumbArgBuilder::negatable`]
+    fn negatable_flag_desc(&self, default: &str) -> String {
+        format!("FLAG; default [{}]", default)
+    }
+    /// description of counted flag, see [`DumbArgBuilder::count`]
+    fn count_flag_desc(&self) -> String {
+        "COUNT; repeatable".to_owned()
+    }
+    /// description of repeatable flag, see [`DumbArgBuilder::append`]
+    fn append_flag_desc(&self, example: Option<&str>) -> String {
+        match example {
+            Some(example) => format!("APPEND; repeatable; e.g. {}", example),
+            None => "APPEND; repeatable".to_owned(),
+        }
+    }
+    /// description of deprecated argument, see [`DumbArgBuilder::set_deprecated`]
+    fn deprecated_desc(&self, hint: &str) -> String {
+        format!("DEPRECATED; {}", hint)
+    }
+    /// description of the range of argument, see [`DumbArgBuilder::set_range`]
+    fn range_desc(&self, min: &str, max: &str) -> String {
+        format!("range: [{}, {}]", min, max)
+    }
+    /// description of the enum values of argument; `values` is [`None`] when the values (with descriptions) are listed one per line
+    fn enum_values_desc(&self, values: Option<&str>) -> String {
+        match values {
+            Some(values) => format!("enum values: [{}]", values),
+            None => "enum values:".to_owned(),
+        }
+    }
+    /// description of the aliases of an enum value, see [`DumbArgBuilder::set_enum_aliases`]
+    fn enum_aliases_desc(&self, aliases: &str) -> String {
+        format!("(aliases: {})", aliases)
+    }
+    /// name of group, see [`DumbArgParser::add_group`]
+    fn group_name(&self, name: &str) -> String {
+        format!("group [{}]", name)
+    }
+    /// description of group, see [`DumbArgParser::add_group`]
+    fn group_desc(&self, rule: &ArgGroupRule, arg_names: &str) -> String {
+        let rule = match rule {
+            ArgGroupRule::None => "GROUP",
+            ArgGroupRule::AtMostOne => "MUTUALLY EXCLUSIVE",
+            ArgGroupRule::ExactlyOne => "EXACTLY ONE OF",
+            ArgGroupRule::AtLeastOne => "AT LEAST ONE OF",
+        };
+        format!("{} [{}]", rule, arg_names)
+    }
+    /// description of required arguments, see [`DumbArgParser::add_requires`]
+    fn requires_desc(&self, arg_names: &str) -> String {
+        format!("REQUIRES [{}]", arg_names)
+    }
+    fn unknown_input_argument(&self, in_arg: &str, suggestion: Option<&str>) -> String {
+        match suggestion {
+            Some(suggestion) => format!(
+                "unknown input argument [{}]; did you mean [{}]?",
+                in_arg, suggestion
+            ),
+            None => format!("unknown input argument [{}]", in_arg),
+        }
+    }
+    /// `candidates` is the comma-separated flags that the prefix matches; see [`DumbArgParser::set_allow_flag_prefix`]
+    fn ambiguous_input_argument(&self, in_arg: &str, candidates: &str) -> String {
+        format!(
+            "ambiguous input argument [{}]; could be [{}]",
+            in_arg, candidates
+        )
+    }
+    fn missing_input_argument_after(&self, in_arg: &str) -> String {
+        format!("missing input argument after [{}]", in_arg)
+    }
+    fn unacceptable_input_argument(&self, in_arg: &str) -> String {
+        format!("unacceptable input argument [{}]", in_arg)
+    }
+    fn argument_not_provided(&self, arg_name: &str) -> String {
+        format!("argument [{}] not provided", arg_name)
+    }
+    /// `type_name` is like "i32", "duration" or "byte size"
+    fn failed_to_parse(&self, val: &str, type_name: &str) -> String {
+        format!("failed to parse \"{}\" as {}", val, type_name)
+    }
+    fn path_not_exist(&self, path: &str) -> String {
+        format!("path \"{}\" does not exist", path)
+    }
+    /// `values` is the comma-separated enum values
+    fn not_match_enum(&self, value: &str, values: &str, suggestion: Option<&str>) -> String {
+        match suggestion {
+            Some(suggestion) => format!(
+                "[{}] doesn't match any of the enum values [{}]; did you mean [{}]?",
+                value, values, suggestion
+            ),
+            None => format!(
+                "[{}] doesn't match any of the enum values [{}]",
+                value, values
+            ),
+        }
+    }
+    fn out_of_range(&self, value: &str, min: &str, max: &str) -> String {
+        format!("[{}] is out of range [{}, {}]", value, min, max)
+    }
+    fn mutually_exclusive(&self, arg_name1: &str, arg_name2: &str) -> String {
+        format!(
+            "arguments [{}] and [{}] are mutually exclusive",
+            arg_name1, arg_name2
+        )
+    }
+    fn group_not_provided(&self, arg_names: &str, group_name: &str) -> String {
+        format!(
+            "one of the arguments [{}] of group [{}] must be provided",
+            arg_names, group_name
+        )
+    }
+    fn requires_argument(&self, arg_name: &str, required_arg_name: &str) -> String {
+        format!("argument [{}] requires [{}]", arg_name, required_arg_name)
+    }
+    fn cannot_read_response_file(&self, path: &str, err: &str) -> String {
+        format!("cannot read response file [{}]: {}", path, err)
+    }
+    fn response_file_includes_itself(&self, path: &str) -> String {
+        format!("response file [{}] includes itself", path)
+    }
+    /// `reason` is one of [`ArgMessageCatalogTrait::unterminated_quote`] and [`ArgMessageCatalogTrait::dangling_escape`]
+    fn invalid_response_file(&self, path: &str, reason: &str) -> String {
+        format!("invalid response file [{}]: {}", path, reason)
+    }
+    /// `quote` is either `'` or `"`
+    fn unterminated_quote(&self, quote: char) -> String {
+        match quote {
+            '\'' => "unterminated single quote".to_owned(),
+            _ => "unterminated double quote".to_owned(),
+        }
+    }
+    fn dangling_escape(&self) -> String {
+        "dangling escape at the end".to_owned()
+    }
+    /// error in reading the prompted argument; see [`DumbArgParser::set_prompt_reader`]
+    fn failed_to_read_argument(&self, arg_name: &str, err: &str) -> String {
+        format!("failed to read argument [{}]: {}", arg_name, err)
+    }
+    /// warning label
+    fn warning_label(&self) -> String {
+        "WARNING".to_owned()
+    }
+    fn deprecated_argument(&self, arg_name: &str, hint: &str) -> String {
+        format!("argument [{}] is deprecated; {}", arg_name, hint)
+    }
+    /// `replacement` is a non-deprecated flag of the same argument, if any
+    fn deprecated_flag(&self, flag: &str, replacement: Option<&str>) -> String {
+        match replacement {
+            Some(replacement) => format!(
+                "flag [{}] is deprecated; use [{}] instead",
+                flag, replacement
+            ),
+            None => format!("flag [{}] is deprecated", flag),
+        }
+    }
+    /// shown when the program exits after showing the help screen
+    fn exited_normally(&self, program_name: &str) -> String {
+        format!("~~~ [{}] EXITED normally ~~~", program_name)
+    }
+    /// shown when the program exits due to invalid input arguments
+    fn exited_with_error(&self, program_name: &str, err: &str) -> String {
+        format!("~~~ [{}] EXITED with error \"{}\" ~~~", program_name, err)
+    }
+}
+
+/// the default (English) [`ArgMessageCatalogTrait`]
+#[derive(Debug, Clone, Copy)]
+pub struct ArgDefaultMessageCatalog;
+impl ArgMessageCatalogTrait for ArgDefaultMessageCatalog {}
+
+#[derive(Debug, Clone)]
+struct ArgGroup {
+    name: String,
+    arg_indexes: Vec<usize>,
+    rule: ArgGroupRule,
+}
+
+#[derive(Debug, Clone)]
+struct ArgRequires {
+    arg_idx: usize,
+    required_arg_indexes: Vec<usize>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum ArgMultiMode {
     None,
@@ -1185,6 +3224,14 @@ pub struct DumbArgBuilder {
     nature: ArgNature,
     multi_mode: ArgMultiMode,
     description: Option<String>,
+    parse_fn: Option<ArgParseFn>,
+    must_exist: bool,
+    validators: Vec<ArgValidateFn>,
+    hidden: bool,
+    deprecated: Option<String>,
+    deprecated_flags: Vec<String>,
+    enums_ignore_case: bool,
+    enum_aliases: Vec<(ArgValue, Vec<String>)>,
 }
 impl DumbArgBuilder {
     /// create an instance of [`DumbArgBuilder`], but suggested to use [`sap_arg!`] macro instead.
@@ -1196,10 +3243,20 @@ impl DumbArgBuilder {
             nature: ArgNature::Regular,
             multi_mode: ArgMultiMode::None,
             description: None,
+            parse_fn: None,
+            must_exist: false,
+            validators: Vec::new(),
+            hidden: false,
+            deprecated: None,
+            deprecated_flags: Vec::new(),
+            enums_ignore_case: false,
+            enum_aliases: Vec::new(),
         }
     }
     /// For argument that requires an argument value passed in.
     /// `value` - used to infer the type of the argument, as well as sample value to shown in help message
+    ///
+    /// besides the primitive types, [`PathBuf`], [`Duration`] (like "5s" or "1m30s") and [`ArgByteSize`] (like "10MiB") are supported
     pub fn value<T: ArgValueTrait>(&mut self, value: T) -> &mut DumbArgBuilder {
         self.value = value.to_arg_value();
         self.nature = ArgNature::Regular;
@@ -1222,6 +3279,29 @@ impl DumbArgBuilder {
         //self.multi = false;
         self
     }
+    /// For flag argument that counts the number of times the flag is present; e.g. "-v" for verbosity, where "-vvv" (or "-v -v -v") gives 3.
+    /// The argument value is of type [`i32`], and is 0 when the flag is not present.
+    pub fn count(&mut self) -> &mut DumbArgBuilder {
+        self.value = ArgValue::I32(0);
+        self.nature = ArgNature::Count;
+        self
+    }
+    /// For flag argument that can be repeated, with each argument value appended to a list; e.g. "-I path1 -I path2".
+    /// The appended values can be retrieved with [`DumbArgParser::get_multi`]; [`DumbArgParser::get`] gives the last appended value.
+    /// * `value` - used to infer the type of the argument, as well as sample value to shown in help message
+    pub fn append<T: ArgValueTrait>(&mut self, value: T) -> &mut DumbArgBuilder {
+        self.value = value.to_arg_value();
+        self.nature = ArgNature::Append;
+        self
+    }
+    /// For [`bool`] flag argument that comes with a negated pair; e.g. "--color" for `true` and "--no-color" for `false`.
+    /// The negated flag is derived from the "--" flag, which therefore must be provided.
+    /// * `default` - the value when neither of the flags is provided
+    pub fn negatable(&mut self, default: bool) -> &mut DumbArgBuilder {
+        self.value = ArgValue::Bool(default);
+        self.nature = ArgNature::Negatable;
+        self
+    }
     /// set the acceptable value range (inclusive) of the argument
     pub fn set_range<T: ArgValueTrait>(&mut self, min: T, max: T) -> &mut DumbArgBuilder {
         self.constraint = ArgConstraint::Range(min.to_arg_value(), max.to_arg_value());
@@ -1240,6 +3320,21 @@ impl DumbArgBuilder {
     /// like [`DumbArgBuilder::set_enums`], set the acceptable values for the argument;
     /// each value is assumed to be suffixed with a description separated by a colon (":"); e.g. "debug:run in debug mode"
     pub fn set_with_desc_enums<T: ArgValueTrait>(&mut self, values: Vec<T>) -> &mut DumbArgBuilder {
+        self._set_with_desc_enums(values, false)
+    }
+    /// like [`DumbArgBuilder::set_with_desc_enums`], but the value can be followed by aliases separated by "|";
+    /// e.g. "yes|y|true:go ahead"; see [`DumbArgBuilder::set_enum_aliases`]
+    pub fn set_with_desc_alias_enums<T: ArgValueTrait>(
+        &mut self,
+        values: Vec<T>,
+    ) -> &mut DumbArgBuilder {
+        self._set_with_desc_enums(values, true)
+    }
+    fn _set_with_desc_enums<T: ArgValueTrait>(
+        &mut self,
+        values: Vec<T>,
+        with_aliases: bool,
+    ) -> &mut DumbArgBuilder {
         let mut arg_enums = Vec::new();
         for value in values.iter() {
             let arg_value = value.to_arg_value();
@@ -1247,14 +3342,38 @@ impl DumbArgBuilder {
             let mut parts = value_str.split(':');
             let value = parts.next().unwrap();
             let description = parts.next().map(|s| s.to_string()).unwrap();
-            arg_enums.push(ArgEnum::new(
-                String::to_arg_value(&value.to_string()),
-                Some(description),
-            ));
+            let (value, aliases) = if with_aliases {
+                let mut value_parts = value.split('|');
+                let value = value_parts.next().unwrap();
+                (value, value_parts.map(|alias| alias.to_string()).collect())
+            } else {
+                (value, Vec::new())
+            };
+            let mut arg_enum =
+                ArgEnum::new(String::to_arg_value(&value.to_string()), Some(description));
+            arg_enum.aliases = aliases;
+            arg_enums.push(arg_enum);
         }
         self.constraint = ArgConstraint::Enums(arg_enums);
         self
     }
+    /// set aliases of an enum value (as set with [`DumbArgBuilder::set_enums`]); e.g. `set_enum_aliases("yes", vec!["y", "true"])`
+    /// * an alias input is retrieved as the enum value
+    /// * the aliases are shown in the help message
+    pub fn set_enum_aliases<T: ArgValueTrait>(
+        &mut self,
+        value: T,
+        aliases: Vec<&str>,
+    ) -> &mut DumbArgBuilder {
+        let aliases = aliases.iter().map(|alias| alias.to_string()).collect();
+        self.enum_aliases.push((value.to_arg_value(), aliases));
+        self
+    }
+    /// match the input against the enum values (and their aliases) case-insensitively; the input is retrieved as the enum value
+    pub fn set_enums_ignore_case(&mut self) -> &mut DumbArgBuilder {
+        self.enums_ignore_case = true;
+        self
+    }
     /// set it to be a multi-argument -- i.e. one that will accept one + rest of the input argument values, that can be retrieved with [`DumbArgParser::get_multi`]
     ///
     /// *** note that multi-argument cannot be [`DumbArgBuilder::fixed`] ***
@@ -1267,22 +3386,226 @@ impl DumbArgBuilder {
         self.multi_mode = ArgMultiMode::Rest;
         self
     }
+    /// for argument of type [`PathBuf`], the input path must exist
+    pub fn set_must_exist(&mut self) -> &mut DumbArgBuilder {
+        self.must_exist = true;
+        self
+    }
+    /// set a user-defined function for converting the input argument string to the argument value,
+    /// which should be of the same type as the argument (as inferred from [`DumbArgBuilder::value`] etc.);
+    /// e.g. `set_parse_fn(|s| i32::from_str_radix(s, 16).map_err(|e| e.to_string()))`
+    pub fn set_parse_fn<T, F>(&mut self, parse_fn: F) -> &mut DumbArgBuilder
+    where
+        T: ArgValueTrait,
+        F: Fn(&str) -> Result<T, String> + Send + Sync + RefUnwindSafe + 'static,
+    {
+        self.parse_fn = Some(ArgParseFn(Arc::new(move |val| {
+            parse_fn(val).map(|v| v.to_arg_value())
+        })));
+        self
+    }
+    /// add a validator of the input argument value, which should be of the same type as the argument;
+    /// e.g. `add_validator(|v: &u32| if v.is_power_of_two() { Ok(()) } else { Err(format!("[{}] is not a power of two", v)) })`
+    /// * the validator is called for each of the input values, after all the arguments are parsed; default values are not validated
+    /// * the error returned is treated like other invalid input argument errors
+    /// * more than one validators can be added
+    pub fn add_validator<T, F>(&mut self, validator: F) -> &mut DumbArgBuilder
+    where
+        T: ArgValueTrait,
+        F: Fn(&T) -> Result<(), String> + Send + Sync + RefUnwindSafe + 'static,
+    {
+        self.validators
+            .push(ArgValidateFn(Arc::new(move |arg_value| {
+                let value = T::from_arg_value(arg_value.clone())?;
+                validator(&value)
+            })));
+        self
+    }
+    /// hide the argument from the help message (as well as the usage and the reference docs); it is still accepted as usual
+    pub fn set_hidden(&mut self) -> &mut DumbArgBuilder {
+        self.hidden = true;
+        self
+    }
+    /// mark the argument as deprecated; it is still accepted, but a warning is emitted (to stderr) when it is used;
+    /// the warnings can also be retrieved with [`DumbArgParser::get_warnings`]
+    /// * `hint` - the hint shown with the warning, like "use --color instead"
+    pub fn set_deprecated(&mut self, hint: &str) -> &mut DumbArgBuilder {
+        self.deprecated = Some(hint.to_string());
+        self
+    }
+    /// add another flag (alias) for the flag argument, in addition to the ones it is created with; e.g. `add_alias("--colour")`
+    pub fn add_alias(&mut self, flag: &str) -> &mut DumbArgBuilder {
+        self.name_or_flags.push(flag.to_string());
+        self
+    }
+    /// like [`DumbArgBuilder::add_alias`], but the flag is deprecated -- e.g. the old name of a renamed flag;
+    /// it is not shown in the help message, and a warning is emitted when it is used
+    pub fn add_deprecated_alias(&mut self, flag: &str) -> &mut DumbArgBuilder {
+        self.name_or_flags.push(flag.to_string());
+        self.deprecated_flags.push(flag.to_string());
+        self
+    }
     /// set description of the argument to be shown in the help message.
     pub fn set_description(&mut self, description: &str) -> &mut DumbArgBuilder {
         self.description = Some(description.to_string());
         self
     }
+    /// the name of the argument being built -- i.e. the positional argument name, or the first flag
+    pub fn get_name(&self) -> String {
+        match self.name_or_flags.first() {
+            Some(name) => name.clone(),
+            None => String::new(),
+        }
+    }
     /// add the argument object (argument specification) to the [`DumbArgParser`].
     pub fn add_to(&self, parser: &mut DumbArgParser) -> Result<(), DumbError> {
         let key = self._to_key()?;
-        parser.add_arg(Arg::new(
+        if self.nature == ArgNature::Count
+            || self.nature == ArgNature::Append
+            || self.nature == ArgNature::Negatable
+        {
+            match &key {
+                ArgKey::Name(name) => {
+                    return Err(format!("argument [{}] must be a flag", name).into());
+                }
+                ArgKey::Flags(_, flags) => {
+                    if self.nature == ArgNature::Negatable
+                        && !flags.iter().any(|flag| flag.starts_with("--"))
+                    {
+                        return Err(format!(
+                            "negatable argument [{}] must have a \"--\" flag",
+                            flags[0]
+                        )
+                        .into());
+                    }
+                }
+            }
+        }
+        if self.multi_mode != ArgMultiMode::None
+            && (self.nature == ArgNature::Fixed
+                || self.nature == ArgNature::Count
+                || self.nature == ArgNature::Append
+                || self.nature == ArgNature::Negatable)
+        {
+            return Err(format!(
+                "multi-argument [{}] cannot be fixed, count, append or negatable",
+                key.get_a_name()
+            )
+            .into());
+        }
+        if self.must_exist && !matches!(self.value, ArgValue::Path(_)) {
+            return Err(format!(
+                "argument [{}] must be a path to be must-exist",
+                key.get_a_name()
+            )
+            .into());
+        }
+        if let ArgConstraint::Range(min, max) = &self.constraint {
+            self.value.compare(min)?;
+            self.value.compare(max)?;
+        }
+        let mut arg = Arg::new(
             key,
             self.value.clone(),
             self.constraint.clone(),
             self.nature.clone(),
             self.multi_mode.clone(),
             &self.description,
-        ));
+        );
+        arg.parse_fn = self.parse_fn.clone();
+        arg.must_exist = self.must_exist;
+        arg.validators = self.validators.clone();
+        arg.hidden = self.hidden;
+        arg.deprecated = self.deprecated.clone();
+        arg.deprecated_flags = self.deprecated_flags.clone();
+        arg.enums_ignore_case = self.enums_ignore_case;
+        self._apply_enum_aliases(&mut arg)?;
+        if arg.nature == ArgNature::Optional
+            || arg.nature == ArgNature::Count
+            || arg.nature == ArgNature::Negatable
+        {
+            // the initial value is set to the argument before parsing, therefore must be valid
+            if let Err(err) = DumbArgParser::_verify_arg_range(parser._messages(), &arg, &arg.value)
+            {
+                return Err(format!(
+                    "invalid initial value of argument [{}]: {}",
+                    arg.key.get_a_name(),
+                    err
+                )
+                .into());
+            }
+        }
+        let multi_arg = parser
+            .args
+            .iter()
+            .find(|arg| arg.multi_mode != ArgMultiMode::None);
+        if let Some(multi_arg) = multi_arg {
+            // multi-argument takes the rest of the input arguments
+            if arg.multi_mode != ArgMultiMode::None {
+                return Err(format!(
+                    "argument [{}] cannot be multi-argument, since [{}] already is",
+                    arg.key.get_a_name(),
+                    multi_arg.key.get_a_name()
+                )
+                .into());
+            }
+            if let (ArgKey::Name(name), ArgKey::Name(multi_name)) = (&arg.key, &multi_arg.key) {
+                return Err(format!(
+                    "positional argument [{}] cannot follow multi-argument [{}]",
+                    name, multi_name
+                )
+                .into());
+            }
+        }
+        parser.add_arg(arg);
+        Ok(())
+    }
+    fn _apply_enum_aliases(&self, arg: &mut Arg) -> Result<(), String> {
+        let arg_name = arg.key.get_a_name();
+        let enum_values = match &mut arg.constraint {
+            ArgConstraint::Enums(enum_values) => enum_values,
+            _ => {
+                if !self.enum_aliases.is_empty() {
+                    return Err(format!("argument [{}] has no enum values", arg_name));
+                }
+                return Ok(());
+            }
+        };
+        for (value, aliases) in self.enum_aliases.iter() {
+            match enum_values
+                .iter_mut()
+                .find(|enum_value| enum_value.compare(value) == Ok(0))
+            {
+                Some(enum_value) => enum_value.aliases.extend(aliases.iter().cloned()),
+                None => {
+                    return Err(format!(
+                        "[{}] is not an enum value of argument [{}]",
+                        value, arg_name
+                    ))
+                }
+            }
+        }
+        // an input must not match more than one (different) enum values
+        for (idx, enum_value) in enum_values.iter().enumerate() {
+            for other_enum_value in enum_values[idx + 1..].iter() {
+                if enum_value.compare(&other_enum_value.arg_value) == Ok(0) {
+                    continue;
+                }
+                let value = other_enum_value.to_string();
+                let clash = std::iter::once(&value)
+                    .chain(other_enum_value.aliases.iter())
+                    .find(|candidate| enum_value.matches(candidate, self.enums_ignore_case));
+                if let Some(clash) = clash {
+                    return Err(format!(
+                        "[{}] of argument [{}] is ambiguous among enum values [{}] and [{}]",
+                        clash,
+                        arg_name,
+                        enum_value.to_string(),
+                        other_enum_value.to_string()
+                    ));
+                }
+            }
+        }
         Ok(())
     }
     fn _to_key(&self) -> Result<ArgKey, String> {
@@ -1325,6 +3648,7 @@ impl DumbArgBuilder {
 
 /// for use by [`DumbArgParser`] internally.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum ArgValue {
     I32(i32),
     I64(i64),
@@ -1332,6 +3656,12 @@ pub enum ArgValue {
     F64(f64),
     Bool(bool),
     String(String),
+    U32(u32),
+    U64(u64),
+    Usize(usize),
+    Path(PathBuf),
+    Duration(Duration),
+    ByteSize(ArgByteSize),
     //StaticStr(&'static str),
 }
 impl ArgValue {
@@ -1346,25 +3676,51 @@ impl ArgValue {
             ArgValue::F32(v) => v.to_string(),
             ArgValue::F64(v) => v.to_string(),
             ArgValue::String(ref v) => v.to_string(),
+            ArgValue::U32(v) => v.to_string(),
+            ArgValue::U64(v) => v.to_string(),
+            ArgValue::Usize(v) => v.to_string(),
+            ArgValue::Path(ref v) => v.to_string_lossy().to_string(),
+            ArgValue::Duration(ref v) => format_duration(v),
+            ArgValue::ByteSize(ref v) => format_byte_size(v),
         }
     }
-    fn compare(&self, arg_value: &ArgValue) -> i32 {
-        match *self {
+    /// compare with the other value, interpreting the other value as the same type as this value;
+    /// error if the other value cannot be interpreted as such (e.g. a range of [`String`] for an [`i32`] argument)
+    fn compare(&self, arg_value: &ArgValue) -> Result<i32, String> {
+        match (self, arg_value) {
+            (ArgValue::Duration(this_value), ArgValue::Duration(other_value)) => {
+                return Ok(Self::_compare_ord(this_value, other_value));
+            }
+            (ArgValue::ByteSize(this_value), ArgValue::ByteSize(other_value)) => {
+                return Ok(Self::_compare_ord(this_value, other_value));
+            }
+            _ => {}
+        }
+        let incomparable_err = || format!("[{}] cannot be compared with [{}]", self, arg_value);
+        let result = match *self {
+            ArgValue::U32(_) | ArgValue::U64(_) | ArgValue::Usize(_) => {
+                let this_value = self.to_string().parse::<u64>().map_err(|_| incomparable_err())?;
+                let other_value = arg_value
+                    .to_string()
+                    .parse::<u64>()
+                    .map_err(|_| incomparable_err())?;
+                Self::_compare_ord(&this_value, &other_value)
+            }
             ArgValue::I32(_) | ArgValue::I64(_) => {
-                let this_value = self.to_string().parse::<i64>().unwrap();
-                let other_value = arg_value.to_string().parse::<i64>().unwrap();
+                let this_value = self.to_string().parse::<i64>().map_err(|_| incomparable_err())?;
+                let other_value = arg_value
+                    .to_string()
+                    .parse::<i64>()
+                    .map_err(|_| incomparable_err())?;
                 //println!("********** {} .. {}", this_value, other_value);
-                if this_value < other_value {
-                    -1
-                } else if this_value > other_value {
-                    1
-                } else {
-                    0
-                }
+                Self::_compare_ord(&this_value, &other_value)
             }
             ArgValue::F32(_) | ArgValue::F64(_) => {
-                let this_value = self.to_string().parse::<f64>().unwrap();
-                let other_value = arg_value.to_string().parse::<f64>().unwrap();
+                let this_value = self.to_string().parse::<f64>().map_err(|_| incomparable_err())?;
+                let other_value = arg_value
+                    .to_string()
+                    .parse::<f64>()
+                    .map_err(|_| incomparable_err())?;
                 if this_value < other_value {
                     -1
                 } else if this_value > other_value {
@@ -1373,18 +3729,24 @@ impl ArgValue {
                     0
                 }
             }
-            ArgValue::Bool(_) | ArgValue::String(_)/*  | ArgValue::StaticStr(_)*/ => {
+            ArgValue::Bool(_)
+            | ArgValue::String(_)
+            | ArgValue::Path(_)
+            | ArgValue::Duration(_)
+            | ArgValue::ByteSize(_) /*  | ArgValue::StaticStr(_)*/ => {
                 let this_value = self.to_string();
                 let other_value = arg_value.to_string();
                 //println!("*********** [{}] .. [{}]", this_value, other_value);
-                if this_value < other_value {
-                    -1
-                } else if this_value > other_value {
-                    1
-                } else {
-                    0
-                }
+                Self::_compare_ord(&this_value, &other_value)
             }
+        };
+        Ok(result)
+    }
+    fn _compare_ord<T: Ord>(this_value: &T, other_value: &T) -> i32 {
+        match this_value.cmp(other_value) {
+            std::cmp::Ordering::Less => -1,
+            std::cmp::Ordering::Greater => 1,
+            std::cmp::Ordering::Equal => 0,
         }
     }
 }
@@ -1402,6 +3764,7 @@ impl fmt::Display for ArgValue {
                 ArgValue::F64(v) => write!(f, "{}", v),
                 ArgValue::String(ref v) => write!(f, "{}", v),
                 //ArgValue::StaticStr(v) => write!(f, "{}", v),
+                _ => write!(f, "{}", self.to_string()),
             }
         }
     }
@@ -1430,6 +3793,7 @@ impl ArgValueTrait for i64 {
         match arg_value {
             ArgValue::I32(v) => Ok(Box::new(v as i64)),
             ArgValue::I64(v) => Ok(Box::new(v)),
+            ArgValue::U32(v) => Ok(Box::new(v as i64)),
             _ => Err(format!("value {:?} is not of type i64", arg_value)),
         }
     }
@@ -1452,6 +3816,7 @@ impl ArgValueTrait for f64 {
     fn from_arg_value(arg_value: ArgValue) -> Result<Box<f64>, String> {
         match arg_value {
             ArgValue::I32(v) => Ok(Box::new(v as f64)),
+            ArgValue::U32(v) => Ok(Box::new(v as f64)),
             ArgValue::F32(v) => Ok(Box::new(v as f64)),
             ArgValue::F64(v) => Ok(Box::new(v)),
             _ => Err(format!("value {:?} is not of type f64", arg_value)),
@@ -1481,10 +3846,131 @@ impl ArgValueTrait for String {
             ArgValue::F64(v) => Ok(Box::new(v.to_string())),
             ArgValue::Bool(v) => Ok(Box::new(v.to_string())),
             ArgValue::String(v) => Ok(Box::new(v)),
+            ArgValue::U32(_)
+            | ArgValue::U64(_)
+            | ArgValue::Usize(_)
+            | ArgValue::Path(_)
+            | ArgValue::Duration(_)
+            | ArgValue::ByteSize(_) => Ok(Box::new(arg_value.to_string())),
             //ArgValue::StaticStr(v) => Ok(Box::new(v.to_string())),
         }
     }
 }
+impl ArgValueTrait for u32 {
+    fn to_arg_value(&self) -> ArgValue {
+        ArgValue::U32(*self)
+    }
+    fn from_arg_value(arg_value: ArgValue) -> Result<Box<u32>, String> {
+        match arg_value {
+            ArgValue::U32(v) => Ok(Box::new(v)),
+            _ => Err(format!("value {:?} is not of type u32", arg_value)),
+        }
+    }
+}
+impl ArgValueTrait for u64 {
+    fn to_arg_value(&self) -> ArgValue {
+        ArgValue::U64(*self)
+    }
+    fn from_arg_value(arg_value: ArgValue) -> Result<Box<u64>, String> {
+        match arg_value {
+            ArgValue::U32(v) => Ok(Box::new(v as u64)),
+            ArgValue::U64(v) => Ok(Box::new(v)),
+            ArgValue::Usize(v) => Ok(Box::new(v as u64)),
+            ArgValue::ByteSize(v) => Ok(Box::new(v.0)),
+            _ => Err(format!("value {:?} is not of type u64", arg_value)),
+        }
+    }
+}
+impl ArgValueTrait for usize {
+    fn to_arg_value(&self) -> ArgValue {
+        ArgValue::Usize(*self)
+    }
+    fn from_arg_value(arg_value: ArgValue) -> Result<Box<usize>, String> {
+        match arg_value {
+            ArgValue::U32(v) => Ok(Box::new(v as usize)),
+            ArgValue::Usize(v) => Ok(Box::new(v)),
+            _ => Err(format!("value {:?} is not of type usize", arg_value)),
+        }
+    }
+}
+impl ArgValueTrait for PathBuf {
+    fn to_arg_value(&self) -> ArgValue {
+        ArgValue::Path(self.clone())
+    }
+    fn from_arg_value(arg_value: ArgValue) -> Result<Box<PathBuf>, String> {
+        match arg_value {
+            ArgValue::Path(v) => Ok(Box::new(v)),
+            ArgValue::String(v) => Ok(Box::new(PathBuf::from(v))),
+            _ => Err(format!("value {:?} is not of type PathBuf", arg_value)),
+        }
+    }
+}
+impl ArgValueTrait for Duration {
+    fn to_arg_value(&self) -> ArgValue {
+        ArgValue::Duration(*self)
+    }
+    fn from_arg_value(arg_value: ArgValue) -> Result<Box<Duration>, String> {
+        match arg_value {
+            ArgValue::Duration(v) => Ok(Box::new(v)),
+            _ => Err(format!("value {:?} is not of type Duration", arg_value)),
+        }
+    }
+}
+impl ArgValueTrait for ArgByteSize {
+    fn to_arg_value(&self) -> ArgValue {
+        ArgValue::ByteSize(*self)
+    }
+    fn from_arg_value(arg_value: ArgValue) -> Result<Box<ArgByteSize>, String> {
+        match arg_value {
+            ArgValue::ByteSize(v) => Ok(Box::new(v)),
+            _ => Err(format!("value {:?} is not of type ArgByteSize", arg_value)),
+        }
+    }
+}
+/// for use by [`dap_struct!`] internally, to retrieve the parsed argument value as the type of a struct field
+pub trait ArgFieldTrait: Sized {
+    fn from_parser(parser: &DumbArgParser, arg_name: &str) -> Result<Self, DumbError>;
+}
+macro_rules! impl_arg_field_trait {
+    ($($t:ty),*) => {
+        $(impl ArgFieldTrait for $t {
+            fn from_parser(parser: &DumbArgParser, arg_name: &str) -> Result<$t, DumbError> {
+                match parser.get::<$t>(arg_name) {
+                    Some(value) => Ok(value),
+                    None => Err(parser._messages().argument_not_provided(arg_name).into()),
+                }
+            }
+        })*
+    };
+}
+impl_arg_field_trait!(
+    i32,
+    i64,
+    f32,
+    f64,
+    String,
+    u32,
+    u64,
+    usize,
+    PathBuf,
+    Duration,
+    ArgByteSize
+);
+impl ArgFieldTrait for bool {
+    fn from_parser(parser: &DumbArgParser, arg_name: &str) -> Result<bool, DumbError> {
+        Ok(parser.get::<bool>(arg_name).unwrap_or(false))
+    }
+}
+impl<T: ArgValueTrait> ArgFieldTrait for Option<T> {
+    fn from_parser(parser: &DumbArgParser, arg_name: &str) -> Result<Option<T>, DumbError> {
+        Ok(parser.get::<T>(arg_name))
+    }
+}
+impl<T: ArgValueTrait> ArgFieldTrait for Vec<T> {
+    fn from_parser(parser: &DumbArgParser, arg_name: &str) -> Result<Vec<T>, DumbError> {
+        Ok(parser.get_multi::<T>(arg_name).unwrap_or_default())
+    }
+}
 impl ArgValueTrait for &'static str {
     fn to_arg_value(&self) -> ArgValue {
         ArgValue::String(self.to_string())
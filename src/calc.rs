@@ -7,7 +7,7 @@
 #![allow(clippy::match_like_matches_macro)]
 #![allow(clippy::single_match)]
 
-use std::{error::Error, fmt, num::ParseFloatError};
+use std::{collections::BTreeMap, error::Error, fmt, num::ParseFloatError, sync::Arc};
 
 use crate::shared::DumbError;
 
@@ -149,6 +149,10 @@ pub fn test_calc() {}
 /// calc.parse_and_push("+ 2.5 * 3 - 4"); // based on last calculation result, parse and push additional "calculation units"
 /// calc.eval().unwrap();  // evaluate the pushed "calculation units" and get the result
 /// assert_eq!(5.0, calc.get_result().unwrap());
+/// calc.parse_and_push("x = ans * 2").unwrap(); // assign to a variable; "ans" is the last final result
+/// assert_eq!(Some(10.0), calc.get_variable("x"));
+/// calc.parse_and_push("x + 1").unwrap();
+/// assert_eq!(11.0, calc.eval().unwrap());
 /// ```
 ///
 /// You may want to refer to [`crate::demo::run_demo`] for a demo program that uses [`DumbCalcProcessor`];
@@ -156,27 +160,45 @@ pub fn test_calc() {}
 #[derive(Debug)]
 pub struct DumbCalcProcessor {
     calc_impl: CalcImpl,
+    display_base: u32,
+    polar_display: bool,
 }
 impl DumbCalcProcessor {
     pub fn new() -> DumbCalcProcessor {
         DumbCalcProcessor {
             calc_impl: CalcImpl::new(),
+            display_base: 10,
+            polar_display: false,
         }
     }
     /// push a "calculation unit":
     /// * a bracket: "(", ")"
     /// * a number: e.g. "0", "1", "2.3", "-4", "-5.67"
-    /// * a binary operator: "+", "-", "*", "/", "^"
-    ///   <br>note that these binary operators have the usual precedence
-    /// * an unary operator: "neg", "sin", "cos", "tan", "asin", "acos", "atan", "log", "ln", "sqrt", "square", "pow10", "inv", "exp", "abs", "%"
+    /// * a number in hex, octal or binary: e.g. "0xFF", "0o17", "0b1010"
+    /// * a binary operator: "+", "-", "*", "/", "^", "mod", "and", "or", "xor", "<<", ">>"
+    ///   <br>note that these binary operators have the usual precedence; "<<" and ">>" come after "+" and "-", then "and", "xor" and "or", like in C
+    /// * an unary operator: "neg", "sin", "cos", "tan", "asin", "acos", "atan", "log", "ln", "sqrt", "square", "pow10", "inv", "exp", "abs", "%", "not"
     ///   <br>notes:
     ///   - an unary operator should come after the operand that it operates on;
     ///   - these unary operators have the same highest precedence (basically operation will be performed, with the operand that comes before it, immediately)
-    /// * a constant: "PI", "E"
+    /// * a function: "max", "min", "atan2", "round", "hypot", or one registered with [`DumbCalcProcessor::register_function`]
+    ///   <br>notes:
+    ///   - a function should be followed by "(", and the arguments are separated by ","; e.g. "max", "(", "1", ",", "2", ")"
+    ///   - "max", "min" and "hypot" accept one or more arguments; "round" accepts the value and optionally the number of decimal places
+    /// * a ",": the separator of function arguments
+    /// * a constant: "PI", "E"; and "i" in the "complex mode" -- see [`DumbCalcProcessor::use_complex_mode`]
+    /// * a variable: "ans" (the last final result), or one set with [`DumbCalcProcessor::set_variable`]
+    /// * a unit like "km" or "h", if enabled with [`DumbCalcProcessor::use_units`]; and the conversion operator "in" (or "to"), like "in", "km", "/", "h"
     /// * a "=", which will evaluate the pushed "calculation units"
     ///
     /// please use [`DumbCalcProcessor::parse_and_push`] if you want to push multiple "calculation units" in a string, like a string of a complete infix expression
-    pub fn push(&mut self, unit: &str) -> Result<(), DumbError> {
+    ///
+    /// an error is a [`CalcError`], which tells the index of the offending "calculation unit" in the sequence of "calculation units" pushed
+    pub fn push(&mut self, unit: &str) -> Result<(), CalcError> {
+        self._push_at(unit, None)
+    }
+    /// * span: the character span of the unit in the string parsed, if parsed
+    fn _push_at(&mut self, unit: &str, span: Option<(usize, usize)>) -> Result<(), CalcError> {
         let unit = unit.trim();
         if unit == "=" {
             self.evaluate();
@@ -185,64 +207,261 @@ impl DumbCalcProcessor {
             let push_unit = Self::_to_unit(unit);
             let push_unit = match push_unit {
                 Some(push_unit) => push_unit,
-                None => match unit.parse::<f64>() {
-                    Ok(operand) => Unit::Operand(operand),
-                    Err(_) => {
-                        let err_msg = format!("'{}' is not a valid unit", unit);
-                        return Err(err_msg.into());
-                    }
+                None => match self.calc_impl.parse_operand(unit) {
+                    Some(operand) => Unit::Operand(operand),
+                    None => match self.calc_impl.find_custom_function(unit) {
+                        Some(func_idx) => Unit::Function(CalcFn::Custom(func_idx)),
+                        None => match self.calc_impl.variables.get(unit) {
+                            Some(value) => Unit::Operand(value.clone()),
+                            None => match self.calc_impl.find_quantity_unit(unit) {
+                                Some(quantity) => Unit::Operand(quantity),
+                                None => {
+                                    let err_msg = format!("'{}' is not a valid unit", unit);
+                                    return Err(self._unit_error(
+                                        CalcErrorKind::UnknownToken,
+                                        err_msg,
+                                        span,
+                                    ));
+                                }
+                            },
+                        },
+                    },
                 },
             };
-            self.calc_impl.push(push_unit, unit);
-            Ok(())
+            self._push_unit(push_unit, unit, span)
+        }
+    }
+    fn _push_unit(
+        &mut self,
+        push_unit: Unit,
+        unit: &str,
+        span: Option<(usize, usize)>,
+    ) -> Result<(), CalcError> {
+        if let Some(Unit::Function(func)) = self.calc_impl.last_pushed.clone() {
+            if push_unit != Unit::OpenBracket {
+                let err_msg = format!(
+                    "'{}' should be followed by '('",
+                    self.calc_impl.get_function_name(func)
+                );
+                return Err(self._unit_error(CalcErrorKind::InvalidSyntax, err_msg, span));
+            }
+        }
+        if push_unit == Unit::Comma && !self.calc_impl.is_in_function_call() {
+            let err_msg = "',' is only acceptable between function arguments";
+            return Err(self._unit_error(CalcErrorKind::InvalidSyntax, err_msg, span));
+        }
+        if push_unit == Unit::CloseBracket
+            && self.calc_impl.nodes.is_some() // a stray ")" is ignored, unless compiling
+            && self.calc_impl.count_opened_brackets() == 0
+        {
+            let err_msg = "')' has no matching '('";
+            return Err(self._unit_error(CalcErrorKind::UnbalancedBracket, err_msg, span));
+        }
+        self.calc_impl.push(push_unit, unit, span);
+        Ok(())
+    }
+    /// an error caused by the unit to be pushed next
+    fn _unit_error<T: Into<String>>(
+        &self,
+        kind: CalcErrorKind,
+        message: T,
+        span: Option<(usize, usize)>,
+    ) -> CalcError {
+        CalcError {
+            kind,
+            message: message.into(),
+            unit_index: Some(self.calc_impl.next_unit_index()),
+            span,
         }
     }
     /// parse and push multiple "calculation units" in a string, like a string of a complete infix expression;
-    /// each parsed "calculation unit" will be pushed one by one with [`DumbCalcProcessor::push`]
+    /// each parsed "calculation unit" will be pushed one by one, like with [`DumbCalcProcessor::push`]
+    ///
+    /// unlike [`DumbCalcProcessor::push`], the parsed unary operators can also be written in the conventional prefix way:
+    /// * an unary operator (except "%") that does not come after an operand is applied to the operand that follows it, like "sin 30" or "sqrt 16 + 1";
+    ///   if followed by "(", it is applied to the bracketed expression as a whole, like "sqrt(16) ^ 2"
+    /// * a "-" that comes after an operator, "(" or "," negates the operand that follows it, like "2 * -3", "-(2 + 3)" or "2 ^ -2 ^ 2";
+    ///   note that a leading "-" is still a binary operator, that operates on the previous result
+    /// * an unary operator that comes after an operand is still applied to that operand, like "16 sqrt"
     ///
-    /// note: please consider unary operators as ***not parsable***
-    pub fn parse_and_push<T: AsRef<str>>(&mut self, units: T) -> Result<(), DumbError> {
+    /// an assignment like "x = 3 * 4" evaluates the expression and assigns the final result to the variable "x";
+    /// it is only accepted at the beginning of a new sequence of "calculation units"; if the expression fails, the error is returned and the variable is left untouched
+    ///
+    /// an error is a [`CalcError`], which also tells the character span of the offending "calculation unit" in the string; e.g.
+    /// ```
+    /// use rusty_dumb_tools::calc::{CalcErrorKind, DumbCalcProcessor};
+    /// let mut calc = DumbCalcProcessor::new();
+    /// let err = calc.parse_and_push("1 + 2 $ 3").unwrap_err();
+    /// assert_eq!(CalcErrorKind::UnknownToken, err.kind());
+    /// assert_eq!(Some((6, 7)), err.span());
+    /// calc.reset();
+    /// calc.parse_and_push("2 * sqrt(1 - 5)").unwrap();
+    /// let err = calc.eval().unwrap_err();
+    /// assert_eq!(CalcErrorKind::Domain, err.kind());
+    /// assert_eq!(Some((4, 8)), err.span());
+    /// ```
+    pub fn parse_and_push<T: AsRef<str>>(&mut self, units: T) -> Result<(), CalcError> {
         let units = _parse_units_from_str(units.as_ref())?;
-        for unit in units {
-            self.push(&unit)?;
+        if units.len() >= 2 && units[1].0 == "=" && Self::_is_valid_variable_name(&units[0].0) {
+            if units.len() == 2 {
+                let err_msg = format!("'=' should be followed by the value of '{}'", units[0].0);
+                return Err(self._unit_error(
+                    CalcErrorKind::InvalidSyntax,
+                    err_msg,
+                    Some(units[1].1),
+                ));
+            }
+            return self._assign_variable(&units[0].0, &units[2..]);
+        }
+        self._push_parsed_units(&units, false)
+    }
+    fn _push_parsed_units(&mut self, units: &[CalcToken], new_expr: bool) -> Result<(), CalcError> {
+        for (idx, (unit, span)) in units.iter().enumerate() {
+            let span = Some(*span);
+            if matches!(Self::_to_unit(unit), Some(Unit::Function(_)))
+                && (idx + 1 >= units.len() || units[idx + 1].0 != "(")
+            {
+                if let Some(quantity) = self.calc_impl.find_quantity_unit(unit) {
+                    self._push_unit(Unit::Operand(quantity), unit, span)?; // like "min", when not called
+                    continue;
+                }
+            }
+            let last_pushed = self.calc_impl.last_pushed.clone();
+            if matches!(last_pushed, Some(Unit::PrefixOperator(_)))
+                && matches!(
+                    Self::_to_unit(unit),
+                    Some(Unit::CloseBracket) | Some(Unit::Comma)
+                )
+            {
+                let err_msg = format!(
+                    "'{}' should be followed by an operand",
+                    last_pushed.unwrap_or(Unit::Comma)
+                );
+                return Err(self._unit_error(CalcErrorKind::InvalidSyntax, err_msg, span));
+            }
+            let after_operand = match last_pushed {
+                Some(Unit::Operand(_)) | Some(Unit::CloseBracket) => true,
+                Some(Unit::Operator(op)) => op.is_unary(),
+                _ => false,
+            };
+            if after_operand {
+                self._push_at(unit, span)?;
+                continue;
+            }
+            let prefix_op = match Self::_to_unit(unit) {
+                Some(Unit::Operator(Op::SUBTRACT)) => {
+                    if last_pushed.is_some() || (new_expr && idx == 0) {
+                        Some(Op::NEGATE)
+                    } else {
+                        None // a leading "-" operates on the previous result
+                    }
+                }
+                Some(Unit::Operator(op)) => {
+                    if op.is_binary() && matches!(last_pushed, Some(Unit::PrefixOperator(_))) {
+                        let err_msg = format!(
+                            "'{}' should be followed by an operand",
+                            last_pushed.unwrap_or(Unit::Comma)
+                        );
+                        return Err(self._unit_error(CalcErrorKind::InvalidSyntax, err_msg, span));
+                    }
+                    if op.is_unary() && op != Op::PERCENT {
+                        Some(op)
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            };
+            match prefix_op {
+                Some(op) => {
+                    let followed_by_bracket =
+                        op != Op::NEGATE && idx + 1 < units.len() && units[idx + 1].0 == "(";
+                    let push_unit = if followed_by_bracket {
+                        Unit::Function(CalcFn::Unary(op))
+                    } else {
+                        Unit::PrefixOperator(op)
+                    };
+                    self._push_unit(push_unit, unit, span)?;
+                }
+                None => self._push_at(unit, span)?,
+            }
         }
         Ok(())
     }
+    fn _assign_variable(&mut self, name: &str, units: &[CalcToken]) -> Result<(), CalcError> {
+        if self.calc_impl.last_pushed.is_some() {
+            let err_msg = format!("cannot assign to '{}' in the middle of an expression", name);
+            return Err(self._unit_error(CalcErrorKind::InvalidSyntax, err_msg, None));
+        }
+        self._push_parsed_units(units, true)?;
+        self.evaluate();
+        match self.get_result() {
+            CalcResult::Final(result) => {
+                self.calc_impl.variables.insert(name.to_string(), result);
+                Ok(())
+            }
+            CalcResult::Error(err) => Err(err),
+            CalcResult::Intermediate(_) => Ok(()),
+        }
+    }
     /// evaluate the pushed "calculation units";
     /// the result will also be assigned to the internal `result`, which can be used as the "initial" value of the next sequence of "calculation units";
     /// note that you can call [`DumbCalcProcessor::get_result`] to get the result
     pub fn evaluate(&mut self) {
         self.calc_impl.eval();
+        if let CalcResult::Final(result) = self.get_result() {
+            self.calc_impl
+                .variables
+                .insert(ANS_VARIABLE.to_owned(), result);
+        }
     }
     /// like [`DumbCalcProcessor::evaluate`], evaluate the pushed "calculation units" and return the result
-    pub fn eval(&mut self) -> Result<f64, DumbError> {
-        self.calc_impl.eval();
+    pub fn eval(&mut self) -> Result<f64, CalcError> {
+        self.evaluate();
         match self.get_result() {
-            CalcResult::Final(result) => Ok(result),
+            CalcResult::Final(result) => Ok(result.to_f64()),
             CalcResult::Intermediate(result) => panic!("unexpected intermediate result {}", result),
-            CalcResult::Error(err_msg) => Err(err_msg.into()),
+            CalcResult::Error(err) => Err(err),
         }
     }
     /// return the calculation result so far; call [`DumbCalcProcessor::evaluate`] to evaluate the pushed "calculation units", and assign the result to it (as final result)
     ///
     /// note that the result is a [`CalcResult`] enum, that can be one of three kinds -- final, intermediate, or error
     pub fn get_result(&self) -> CalcResult {
-        let result = self.calc_impl.result;
-        if result.is_nan() {
-            CalcResult::Error("result is NaN".to_owned())
-        } else if result.is_infinite() {
-            CalcResult::Error("result is infinity".to_owned())
+        match self._get_result() {
+            CalcResult::Final(result) => CalcResult::Final(self._to_display_value(result)),
+            CalcResult::Intermediate(result) => {
+                CalcResult::Intermediate(self._to_display_value(result))
+            }
+            result => result,
+        }
+    }
+    fn _to_display_value(&self, value: CalcValue) -> CalcValue {
+        match value {
+            CalcValue::Integer(value) => CalcValue::Integer(value.with_base(self.display_base)),
+            CalcValue::Complex(value) if self.polar_display => {
+                CalcValue::Complex(value.with_polar(self.calc_impl.angle_mode))
+            }
+            value => value,
+        }
+    }
+    fn _get_result(&self) -> CalcResult {
+        let result = self.calc_impl.result.clone();
+        if let Some(err) = &self.calc_impl.error {
+            CalcResult::Error(err.clone())
+        } else if let Some(err) = CalcError::_from_result(&result) {
+            CalcResult::Error(err) // like from a variable set to NaN
         } else {
             if true {
                 let last_pushed = &self.calc_impl.last_pushed;
                 if let Some(Unit::OpenBracket) = last_pushed {
-                    CalcResult::Intermediate(0.0)
+                    CalcResult::Intermediate(CalcValue::Float(0.0))
                 } else {
                     //println!("!!!!! {:?}", stack.last());
                     let scanned = &self.calc_impl.scanned;
                     if !scanned.is_empty() {
                         let intermediate_result = scanned.last().unwrap();
-                        CalcResult::Intermediate(*intermediate_result)
+                        CalcResult::Intermediate(intermediate_result.clone())
                     } else {
                         CalcResult::Final(result)
                     }
@@ -250,13 +469,13 @@ impl DumbCalcProcessor {
             } else {
                 let stack = &self.calc_impl.stack;
                 if let Some(Unit::OpenBracket) = stack.last() {
-                    CalcResult::Intermediate(0.0)
+                    CalcResult::Intermediate(CalcValue::Float(0.0))
                 } else {
                     //println!("!!!!! {:?}", stack.last());
                     let scanned = &self.calc_impl.scanned;
                     if !scanned.is_empty() {
                         let intermediate_result = scanned.last().unwrap();
-                        CalcResult::Intermediate(*intermediate_result)
+                        CalcResult::Intermediate(intermediate_result.clone())
                     } else {
                         CalcResult::Final(result)
                     }
@@ -282,19 +501,370 @@ impl DumbCalcProcessor {
         };
         self.calc_impl.use_angle_mode(angle_mode);
     }
-    /// reset for new input
+    /// use the "decimal mode", in which "+", "-", "*", "/" and integer powers are calculated with arbitrary-precision decimal values;
+    /// e.g. "0.1 + 0.2" is exactly 0.3, rather than 0.30000000000000004
+    /// * precision: the maximum number of significant digits of a calculated value; e.g. 28
+    /// * rounding: how to round a calculated value that has more significant digits than `precision`
+    ///
+    /// other operations like "sin" and "sqrt" fall back to be calculated with `f64`, and the values calculated will be flagged as approximate -- see [`CalcValue::is_approximate`]
+    ///
+    /// e.g.
+    /// ```
+    /// use rusty_dumb_tools::calc::{CalcRounding, DumbCalcProcessor};
+    /// let mut calc = DumbCalcProcessor::new();
+    /// calc.use_decimal_mode(28, CalcRounding::HalfEven);
+    /// calc.parse_and_push("0.1 + 0.2").unwrap();
+    /// calc.evaluate();
+    /// assert_eq!("0.3", calc.get_result().to_string());
+    /// ```
+    pub fn use_decimal_mode(&mut self, precision: usize, rounding: CalcRounding) {
+        if precision == 0 {
+            panic!("'{}' is not a valid precision", precision);
+        }
+        let context = CalcDecimalContext {
+            precision,
+            rounding,
+        };
+        self.calc_impl.number_mode = CalcNumberMode::Decimal(context);
+    }
+    /// use the "rational mode", in which "+", "-", "*", "/" and integer powers are calculated exactly with fractions;
+    /// e.g. "1/3 + 1/6" is exactly 1/2
+    ///
+    /// other operations like "sin" and "sqrt", as well as constants like "PI", fall back to be calculated with `f64`,
+    /// and so will be the calculations that involve such `f64` values
+    ///
+    /// a fraction can be displayed as a mixed number with [`CalcResult::to_mixed_string`]; e.g.
+    /// ```
+    /// use rusty_dumb_tools::calc::DumbCalcProcessor;
+    /// let mut calc = DumbCalcProcessor::new();
+    /// calc.use_rational_mode();
+    /// calc.parse_and_push("1/3 + 1/6").unwrap();
+    /// calc.evaluate();
+    /// assert_eq!("1/2", calc.get_result().to_string());
+    /// calc.parse_and_push("2 + 1/3").unwrap();
+    /// calc.evaluate();
+    /// assert_eq!("7/3", calc.get_result().to_string());
+    /// assert_eq!("2 1/3", calc.get_result().to_mixed_string());
+    /// ```
+    pub fn use_rational_mode(&mut self) {
+        self.calc_impl.number_mode = CalcNumberMode::Rational;
+    }
+    /// use the "programmer mode", in which the calculations are done with integers of a fixed word size, that wrap around on overflow;
+    /// in addition to the usual operators, there are
+    /// * the binary operators "and", "or", "xor", "<<", ">>" and "mod"; note that "/" is an integer division that truncates towards zero
+    /// * the unary operator "not", which flips all the bits of the word
+    /// * the literals like "0xFF", "0o17" and "0b1010", which can actually be used in any "number mode"
+    ///
+    /// other operations like "sqrt" fall back to be calculated with `f64`, with the results truncated to integers
+    /// * word_size: the number of bits of a word -- 8, 16, 32 or 64
+    /// * signed: whether the words are signed (two's complement) or unsigned
+    ///
+    /// call [`DumbCalcProcessor::use_display_base`] to display the results in another base; e.g.
+    /// ```
+    /// use rusty_dumb_tools::calc::DumbCalcProcessor;
+    /// let mut calc = DumbCalcProcessor::new();
+    /// calc.use_programmer_mode(8, false);
+    /// calc.use_display_base(16);
+    /// calc.parse_and_push("0xF0 or 0x0F + 1").unwrap();
+    /// calc.evaluate();
+    /// assert_eq!("0xF0", calc.get_result().to_string());
+    /// calc.parse_and_push("0xFF + 2").unwrap();
+    /// calc.evaluate();
+    /// assert_eq!("0x1", calc.get_result().to_string());
+    /// ```
+    pub fn use_programmer_mode(&mut self, word_size: u32, signed: bool) {
+        if ![8, 16, 32, 64].contains(&word_size) {
+            panic!("'{}' is not a valid word size", word_size);
+        }
+        let word = CalcWord {
+            bits: word_size,
+            signed,
+        };
+        self.calc_impl.number_mode = CalcNumberMode::Programmer(word);
+    }
+    /// the base to display the integer results calculated in the "programmer mode" -- 2, 8, 10 (default) or 16;
+    /// other than base 10, the bits of the word are displayed, with a prefix like "0x"
+    pub fn use_display_base(&mut self, base: u32) {
+        if ![2, 8, 10, 16].contains(&base) {
+            panic!("'{}' is not a valid base", base);
+        }
+        self.display_base = base;
+    }
+    /// use the "complex mode", in which the calculations are done with complex values; "i" is the imaginary unit, which can also follow a number directly, like "3 + 2i";
+    /// and "sqrt", "ln", "log", "exp", "pow10", the trigonometric functions and "^" are extended to complex values, like "sqrt(-4)" is "2i"
+    ///
+    /// other operations like "mod" and "max" are only calculated with real values; otherwise, they are undefined
+    ///
+    /// the results are displayed like "3+2i", or in the polar form like "2∠90°" with [`DumbCalcProcessor::use_polar_display`];
+    /// note that [`DumbCalcProcessor::eval`] returns the real part of the result -- see [`CalcValue::to_f64`]
+    ///
+    /// e.g.
+    /// ```
+    /// use rusty_dumb_tools::calc::DumbCalcProcessor;
+    /// let mut calc = DumbCalcProcessor::new();
+    /// calc.use_complex_mode();
+    /// calc.parse_and_push("sqrt(-4) + 1").unwrap();
+    /// calc.evaluate();
+    /// assert_eq!("1+2i", calc.get_result().to_string());
+    /// calc.parse_and_push("(1 + i) * (1 - i)").unwrap();
+    /// assert_eq!(2.0, calc.eval().unwrap());
+    /// ```
+    pub fn use_complex_mode(&mut self) {
+        self.calc_impl.number_mode = CalcNumberMode::Complex;
+    }
+    /// whether to display the complex results calculated in the "complex mode" in the polar form, like "2∠90°";
+    /// the angle is in degrees or radians according to the "angle mode" -- see [`DumbCalcProcessor::use_angle_mode`]
+    pub fn use_polar_display(&mut self, polar: bool) {
+        self.polar_display = polar;
+    }
+    /// use the default "float mode", in which the calculations are done with `f64`
+    pub fn use_float_mode(&mut self) {
+        self.calc_impl.number_mode = CalcNumberMode::Float;
+    }
+    /// enable (or disable) the units, so that a number followed by a unit is a quantity, like "5 km" or "20 min"; the units are
+    /// * length: "m", "km", "cm", "mm", "um", "nm", "inch", "ft", "yd", "mi"
+    /// * mass: "kg", "g", "mg", "t", "lb", "oz"
+    /// * time: "s", "ms", "min", "h", "day", "week"
+    /// * data size: "bit", "B", "KB", "MB", "GB", "TB", "KiB", "MiB", "GiB", "TiB"
+    /// * temperature: "K", "degC", "degF"
+    ///
+    /// quantities can be multiplied and divided, like "5 km / 20 min"; but they can only be added to (or subtracted from) quantities of the same dimension;
+    /// and a quantity can be converted to other units with the operator "in" (or "to"), which comes after all the other operators, like "5 km / 20 min in km/h"
+    ///
+    /// a calculation that mismatches the dimensions, like "1 km + 1 kg", is a [`CalcErrorKind::DimensionMismatch`] error;
+    /// note that a variable set (or a function registered) with the same name as a unit takes precedence over the unit; and quantities are always calculated with `f64`;
+    /// also note that "min" parsed with [`DumbCalcProcessor::parse_and_push`] is the unit, unless it is called like "min(1, 2)"
+    ///
+    /// e.g.
+    /// ```
+    /// use rusty_dumb_tools::calc::DumbCalcProcessor;
+    /// let mut calc = DumbCalcProcessor::new();
+    /// calc.use_units(true);
+    /// calc.parse_and_push("5 km / 20 min in km/h").unwrap();
+    /// assert_eq!(15.0, calc.eval().unwrap());
+    /// assert_eq!("15 km/h", calc.get_result().to_string());
+    /// calc.parse_and_push("1 km + 1 kg").unwrap();
+    /// assert!(calc.eval().is_err());
+    /// ```
+    pub fn use_units(&mut self, enabled: bool) {
+        self.calc_impl.units_enabled = enabled;
+    }
+    /// set the value of a variable, which can then be used as a "calculation unit";
+    /// the name should start with a letter, followed by letters or digits; e.g. "x", "rate2"
+    pub fn set_variable(&mut self, name: &str, value: f64) -> Result<(), DumbError> {
+        if !Self::_is_valid_variable_name(name)
+            || self.calc_impl.find_custom_function(name).is_some()
+        {
+            let err_msg = format!("'{}' is not a valid variable name", name);
+            return Err(err_msg.into());
+        }
+        self.calc_impl
+            .variables
+            .insert(name.to_string(), _to_variable_value(value));
+        Ok(())
+    }
+    /// get the value of a variable, including "ans", which is the last final result
+    pub fn get_variable(&self, name: &str) -> Option<f64> {
+        self.calc_impl.variables.get(name).map(CalcValue::to_f64)
+    }
+    /// list the variables (sorted by name) together with their values
+    pub fn get_variables(&self) -> Vec<(String, f64)> {
+        self.calc_impl
+            .variables
+            .iter()
+            .map(|(name, value)| (name.clone(), value.to_f64()))
+            .collect()
+    }
+    /// register a function (or replace the one registered with the same name) that can then be called like "fv(0.05, 10, 100)";
+    /// * `name` - the name of the function, which should start with a letter, followed by letters or digits
+    /// * `arg_count` - the number of arguments the function accepts
+    /// * `func` - the function, which is passed with the `arg_count` arguments
+    ///
+    /// e.g.
+    /// ```
+    /// use rusty_dumb_tools::calc::DumbCalcProcessor;
+    /// let mut calc = DumbCalcProcessor::new();
+    /// calc.register_function("fv", 3, |args| args[2] * (1.0 + args[0]).powf(args[1])).unwrap();
+    /// calc.parse_and_push("fv(0.1, 2, 100)").unwrap();
+    /// assert_eq!(121.0, calc.eval().unwrap().round());
+    /// ```
+    pub fn register_function<F>(
+        &mut self,
+        name: &str,
+        arg_count: usize,
+        func: F,
+    ) -> Result<(), DumbError>
+    where
+        F: Fn(&[f64]) -> f64 + Send + Sync + 'static,
+    {
+        if !Self::_is_valid_variable_name(name)
+            || name == ANS_VARIABLE
+            || self.calc_impl.variables.contains_key(name)
+        {
+            let err_msg = format!("'{}' is not a valid function name", name);
+            return Err(err_msg.into());
+        }
+        let custom_function = CalcCustomFunction {
+            name: name.to_string(),
+            arg_count,
+            func: CalcCustomFn(Arc::new(func)),
+        };
+        match self.calc_impl.find_custom_function(name) {
+            Some(func_idx) => self.calc_impl.custom_functions[func_idx] = custom_function,
+            None => self.calc_impl.custom_functions.push(custom_function),
+        }
+        Ok(())
+    }
+    /// remove a variable, returning its value if it was set
+    pub fn remove_variable(&mut self, name: &str) -> Option<f64> {
+        self.calc_impl
+            .variables
+            .remove(name)
+            .map(|value| value.to_f64())
+    }
+    /// clear all the variables (including "ans")
+    pub fn clear_variables(&mut self) {
+        self.calc_impl.variables.clear();
+    }
+    /// reset for new input; note that variables are kept
     pub fn reset(&mut self) {
         self.calc_impl.reset();
     }
     /// make a backup of the current state; can call [`DumbCalcProcessor::restore`] to restore the state
     pub fn backup(&self) -> CalcProcessorBackup {
         CalcProcessorBackup {
-            calc_impl: self.calc_impl.clone(),
+            calc_impl: Box::new(self.calc_impl.clone()),
         }
     }
     /// restore the state from a backup, made with [`DumbCalcProcessor::backup`]
     pub fn restore(&mut self, backup: CalcProcessorBackup) {
-        self.calc_impl = backup.calc_impl;
+        self.calc_impl = *backup.calc_impl;
+    }
+    /// compile an infix expression, like with [`DumbCalcProcessor::parse_and_push`], into a [`CalcExpr`], which can then be evaluated many times with different variable values;
+    /// * a name that is not a known variable (or function etc) is a variable to be bound when evaluating
+    /// * a leading "-" negates the operand that follows it, rather than operating on the previous result
+    ///
+    /// the current "number mode", "angle mode" and registered functions are used for the compiled expression; and the constant parts are folded
+    ///
+    /// unlike [`DumbCalcProcessor::parse_and_push`], the brackets should be balanced; an unclosed "(" or a stray ")" is a [`CalcErrorKind::UnbalancedBracket`] error
+    ///
+    /// e.g.
+    /// ```
+    /// use rusty_dumb_tools::calc::DumbCalcProcessor;
+    /// let calc = DumbCalcProcessor::new();
+    /// let expr = calc.compile("2 * (3 + 4) * x + y").unwrap();
+    /// assert_eq!("14 * x + y", expr.to_string());
+    /// assert_eq!(vec!["x", "y"], expr.get_variables());
+    /// assert_eq!(29.0, expr.evaluate(&[("x", 2.0), ("y", 1.0)]).unwrap());
+    /// assert_eq!(43.0, expr.evaluate_with(&[3.0, 1.0]).unwrap());
+    /// ```
+    pub fn compile<T: AsRef<str>>(&self, expr: T) -> Result<CalcExpr, CalcError> {
+        let units = _parse_units_from_str(expr.as_ref())?;
+        let unit_names: Vec<String> = units.iter().map(|(unit, _)| unit.clone()).collect();
+        let mut compiler = self._new_compiler(&unit_names)?;
+        compiler._push_parsed_units(&units, true)?;
+        self._to_compiled_expr(compiler)
+    }
+    /// like [`DumbCalcProcessor::compile`], but compile the "calculation units" that are pushed one by one, like with [`DumbCalcProcessor::push`]
+    pub fn compile_units<T: AsRef<str>>(&self, units: &[T]) -> Result<CalcExpr, CalcError> {
+        let units: Vec<String> = units
+            .iter()
+            .map(|unit| unit.as_ref().trim().to_string())
+            .collect();
+        let mut compiler = self._new_compiler(&units)?;
+        for unit in &units {
+            compiler.push(unit)?;
+        }
+        self._to_compiled_expr(compiler)
+    }
+    fn _new_compiler(&self, units: &[String]) -> Result<DumbCalcProcessor, CalcError> {
+        if units.is_empty() {
+            return Err(CalcError::new(
+                CalcErrorKind::InvalidSyntax,
+                "nothing to compile",
+            ));
+        }
+        if let Some(unit_index) = units.iter().position(|unit| unit == "=") {
+            let err_msg = "'=' is not acceptable in an expression to compile";
+            return Err(CalcError {
+                unit_index: Some(unit_index),
+                ..CalcError::new(CalcErrorKind::InvalidSyntax, err_msg)
+            });
+        }
+        let mut calc_impl = CalcImpl {
+            angle_mode: self.calc_impl.angle_mode,
+            number_mode: self.calc_impl.number_mode,
+            units_enabled: self.calc_impl.units_enabled,
+            variables: self.calc_impl.variables.clone(),
+            custom_functions: self.calc_impl.custom_functions.clone(),
+            nodes: Some(Vec::new()),
+            ..CalcImpl::new()
+        };
+        for unit in units {
+            if Self::_is_valid_variable_name(unit)
+                && calc_impl.find_custom_function(unit).is_none()
+                && !calc_impl.variables.contains_key(unit)
+                && calc_impl.find_quantity_unit(unit).is_none()
+                && calc_impl.parse_operand(unit).is_none()
+            {
+                // a free variable, which is bound when evaluating
+                calc_impl
+                    .variables
+                    .insert(unit.clone(), CalcValue::Float(1.0));
+            }
+        }
+        Ok(DumbCalcProcessor {
+            calc_impl,
+            display_base: self.display_base,
+            polar_display: self.polar_display,
+        })
+    }
+    fn _to_compiled_expr(&self, compiler: DumbCalcProcessor) -> Result<CalcExpr, CalcError> {
+        let mut calc_impl = compiler.calc_impl;
+        if let Some(idx) = calc_impl
+            .stack
+            .iter()
+            .rposition(|unit| *unit == Unit::OpenBracket)
+        {
+            let unit_index = calc_impl.stack_positions[idx];
+            return Err(CalcError {
+                unit_index: Some(unit_index),
+                span: calc_impl.unit_spans[unit_index],
+                ..CalcError::new(CalcErrorKind::UnbalancedBracket, "'(' is not closed")
+            });
+        }
+        calc_impl.eval();
+        match calc_impl.error.take() {
+            Some(err) if err.kind == CalcErrorKind::InvalidArguments => return Err(err),
+            _ => {} // the other errors are only due to the values of the free variables when compiling
+        }
+        let root = calc_impl
+            .nodes
+            .take()
+            .and_then(|mut nodes| nodes.pop())
+            .unwrap();
+        calc_impl.reset();
+        calc_impl.variables.clear();
+        let mut variables = Vec::new();
+        root._collect_variables(&mut variables);
+        variables.sort();
+        variables.dedup();
+        let defaults = variables
+            .iter()
+            .map(|name| {
+                self.calc_impl
+                    .variables
+                    .get(name)
+                    .map(|value| calc_impl._to_mode_value(value.clone(), true))
+            })
+            .collect();
+        let root = calc_impl._fold_node(root);
+        Ok(CalcExpr {
+            root,
+            variables,
+            defaults,
+            calc_impl: Box::new(calc_impl),
+        })
     }
 }
 impl DumbCalcProcessor {
@@ -342,11 +912,24 @@ impl DumbCalcProcessor {
             "pow10" => Some(Unit::Operator(Op::POW10)),
             "inv" => Some(Unit::Operator(Op::INVERSE)),
             "exp" => Some(Unit::Operator(Op::EXP)),
-            //"mod" => Some(Unit::Operator(Op::MOD)),
+            "mod" => Some(Unit::Operator(Op::MOD)),
+            "and" => Some(Unit::Operator(Op::AND)),
+            "or" => Some(Unit::Operator(Op::OR)),
+            "xor" => Some(Unit::Operator(Op::XOR)),
+            "<<" => Some(Unit::Operator(Op::SHL)),
+            ">>" => Some(Unit::Operator(Op::SHR)),
+            "not" => Some(Unit::Operator(Op::NOT)),
+            "in" | "to" => Some(Unit::Operator(Op::CONVERT)),
             "abs" => Some(Unit::Operator(Op::ABS)),
             "%" => Some(Unit::Operator(Op::PERCENT)),
-            "PI" => Some(Unit::Operand(std::f64::consts::PI)),
-            "E" => Some(Unit::Operand(std::f64::consts::E)),
+            "max" => Some(Unit::Function(CalcFn::Builtin(Func::MAX))),
+            "min" => Some(Unit::Function(CalcFn::Builtin(Func::MIN))),
+            "atan2" => Some(Unit::Function(CalcFn::Builtin(Func::ATAN2))),
+            "round" => Some(Unit::Function(CalcFn::Builtin(Func::ROUND))),
+            "hypot" => Some(Unit::Function(CalcFn::Builtin(Func::HYPOT))),
+            "," => Some(Unit::Comma),
+            "PI" => Some(Unit::Operand(CalcValue::Float(std::f64::consts::PI))),
+            "E" => Some(Unit::Operand(CalcValue::Float(std::f64::consts::E))),
             _ => None,
         }
     }
@@ -356,19 +939,45 @@ impl DumbCalcProcessor {
             _ => false,
         }
     }
+    fn _is_valid_variable_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(c) if c.is_ascii_alphabetic() => {}
+            _ => return false,
+        }
+        chars.all(|c| c.is_ascii_alphanumeric())
+            && Self::_to_unit(name).is_none()
+            && name.parse::<f64>().is_err()
+    }
+}
+
+/// the variable that is bound to the last final result
+const ANS_VARIABLE: &str = "ans";
+
+/// the constant of the imaginary unit in the "complex mode"
+const IMAGINARY_UNIT: &str = "i";
+
+fn _to_variable_value(value: f64) -> CalcValue {
+    match CalcDecimal::from_f64(value, false) {
+        Some(value) => CalcValue::Decimal(value), // so that it can be used exactly in the "decimal mode" or the "rational mode"
+        None => CalcValue::Float(value),
+    }
 }
 
 pub struct CalcProcessorBackup {
-    calc_impl: CalcImpl,
+    calc_impl: Box<CalcImpl>,
 }
 
-fn _parse_units_from_str(units: &str) -> Result<Vec<String>, String> {
+/// a parsed unit, with its character span
+type CalcToken = (String, (usize, usize));
+
+fn _parse_units_from_str(units: &str) -> Result<Vec<CalcToken>, CalcError> {
     let units: Vec<char> = units.chars().collect();
     _parse_units_from_chars(&units)
 }
 
-fn _parse_units_from_chars(units: &[char]) -> Result<Vec<String>, String> {
-    let mut parsed_units: Vec<String> = Vec::new();
+fn _parse_units_from_chars(units: &[char]) -> Result<Vec<CalcToken>, CalcError> {
+    let mut parsed_units: Vec<CalcToken> = Vec::new();
     let max_idx = units.len();
     let mut idx = 0;
     while idx < max_idx {
@@ -383,11 +992,15 @@ fn _parse_units_from_chars(units: &[char]) -> Result<Vec<String>, String> {
                 let unit = unit.replace('_', ""); // ignore _
                 idx = end_idx;
                 if !unit.is_empty() {
-                    parsed_units.push(unit)
+                    parsed_units.push((unit, (start_idx, end_idx)))
                 }
             }
             None => {
-                return Err("failed to extract token".to_owned());
+                return Err(CalcError {
+                    unit_index: Some(parsed_units.len()),
+                    span: Some((idx, max_idx)),
+                    ..CalcError::new(CalcErrorKind::UnknownToken, "failed to extract token")
+                });
             }
         }
     }
@@ -412,9 +1025,13 @@ fn _to_next_unit_token(mut idx: usize, s: &[char]) -> Option<(usize, usize)> {
                 || c == '%'
                 || c == '^'
                 || c == '='
+                || c == ','
             {
                 return Some((idx, idx + 1));
             }
+            if (c == '<' || c == '>') && s.get(idx + 1) == Some(&c) {
+                return Some((idx, idx + 2)); // "<<" or ">>"
+            }
             if c.is_whitespace() {
                 idx += 1;
                 continue;
@@ -438,6 +1055,9 @@ fn _to_next_unit_token(mut idx: usize, s: &[char]) -> Option<(usize, usize)> {
             || c == '^'
             || c == '%'
             || c == '='
+            || c == ','
+            || c == '<'
+            || c == '>'
         {
             end_idx = idx;
             break;
@@ -476,26 +1096,57 @@ fn _check_followed_by_digit(mut idx: usize, s: &[char]) -> bool {
 
 #[derive(Debug, Clone)]
 struct CalcImpl {
-    scanned: Vec<f64>,
-    stack: Vec<Unit>, // can only be ) or Op
+    scanned: Vec<CalcValue>,
+    stack: Vec<Unit>,            // can only be ) or Op
+    stack_positions: Vec<usize>, // the unit indexes of the units on the stack
     last_pushed: Option<Unit>,
-    result: f64,
+    result: CalcValue,
     angle_mode: AngleMode,
+    variables: BTreeMap<String, CalcValue>,
+    custom_functions: Vec<CalcCustomFunction>,
+    call_marks: Vec<usize>, // the scanned lengths when the function calls started
+    error: Option<CalcError>,
+    unit_spans: Vec<Option<(usize, usize)>>, // the character spans of the units pushed in the current sequence, if parsed
+    number_mode: CalcNumberMode,
+    units_enabled: bool,
+    nodes: Option<Vec<CalcNode>>, // the nodes of the scanned values, only when compiling
 }
 impl CalcImpl {
     fn new() -> CalcImpl {
         CalcImpl {
             scanned: Vec::new(),
             stack: Vec::new(),
+            stack_positions: Vec::new(),
             last_pushed: None,
-            result: 0.0,
+            result: CalcValue::Float(0.0),
             angle_mode: AngleMode::DEGREE,
+            variables: BTreeMap::new(),
+            custom_functions: Vec::new(),
+            call_marks: Vec::new(),
+            error: None,
+            unit_spans: Vec::new(),
+            number_mode: CalcNumberMode::Float,
+            units_enabled: false,
+            nodes: None,
         }
     }
-    fn push(&mut self, push_unit: Unit, src_unit: &str) {
+    fn push(&mut self, push_unit: Unit, src_unit: &str, span: Option<(usize, usize)>) {
         //println!("* {:?}", push_unit);
-        let last_pushed = self.last_pushed;
-        self.last_pushed = Some(push_unit);
+        let push_unit = match push_unit {
+            Unit::Operand(operand) => {
+                let approximate = DumbCalcProcessor::_is_unit_constant(src_unit)
+                    || self.variables.contains_key(src_unit);
+                Unit::Operand(self._to_mode_value(operand, approximate))
+            }
+            push_unit => push_unit,
+        };
+        let last_pushed = self.last_pushed.take();
+        self.last_pushed = Some(push_unit.clone());
+        if last_pushed.is_none() {
+            self.error = None;
+            self.unit_spans.clear();
+        }
+        self.unit_spans.push(span);
         match last_pushed {
             Some(last_pushed_unit) => match last_pushed_unit {
                 Unit::Operand(_) => {
@@ -503,22 +1154,22 @@ impl CalcImpl {
                     //     self._push(Unit::Operator(Op::MULTIPLY)); // add a * between if next is an open bracket
                     // }
                     match push_unit {
-                        Unit::OpenBracket => {
+                        Unit::OpenBracket | Unit::Function(_) => {
                             if SUPPORT_IMP_OP {
                                 self._push(Unit::Operator(Op::IMPLICIT)); // add a _imp_ between if next is an open bracket
                             } else {
                                 self._push(Unit::Operator(Op::MULTIPLY)); // add a * between if next is an open bracket
                             }
                         }
-                        Unit::Operand(operand) => {
+                        Unit::Operand(_) => {
                             if SUPPORT_IMP_OP {
-                                if DumbCalcProcessor::_is_unit_constant(src_unit) {
-                                    self._push(Unit::Operator(Op::IMPLICIT)); // add a _imp_ between if next is a constant
+                                if self.is_named_operand(src_unit) {
+                                    self._push(Unit::Operator(Op::IMPLICIT)); // add a _imp_ between if next is a constant (or variable or unit)
                                 } else {
-                                    self.scanned.pop(); // consecutive operands => replace the last one
+                                    self._pop_operand(); // consecutive operands => replace the last one
                                 }
                             } else {
-                                self.scanned.pop(); // consecutive operands => replace the last one
+                                self._pop_operand(); // consecutive operands => replace the last one
                             }
                         }
                         _ => {}
@@ -527,11 +1178,23 @@ impl CalcImpl {
                 Unit::OpenBracket => match push_unit {
                     Unit::Operator(op) => {
                         if op.is_binary() {
-                            self._push(Unit::Operand(0.0)); // add a 0 after ( if next is a binary op
+                            self._push(Unit::Operand(self._zero())); // add a 0 after ( if next is a binary op
                         }
                     }
-                    Unit::CloseBracket => {
-                        self._push(Unit::Operand(0.0)); // add a 0 after ( if next is a )
+                    Unit::CloseBracket if !self.is_in_function_call() => {
+                        self._push(Unit::Operand(self._zero())); // add a 0 after ( if next is a ), unless calling a function
+                    }
+                    Unit::Comma => {
+                        self._push(Unit::Operand(self._zero())); // add a 0 after ( if next is a ,
+                    }
+                    _ => {}
+                },
+                Unit::Comma => match push_unit {
+                    Unit::Operator(op) if op.is_binary() => {
+                        self._push(Unit::Operand(self._zero())); // add a 0 after , if next is a binary op
+                    }
+                    Unit::CloseBracket | Unit::Comma => {
+                        self._push(Unit::Operand(self._zero())); // add a 0 after , if next is a ) or ,
                     }
                     _ => {}
                 },
@@ -541,7 +1204,7 @@ impl CalcImpl {
                             self._push(Unit::Operator(Op::IMPLICIT)); // add a _imp_ between if next is an operand
                         }
                     }
-                    Unit::OpenBracket => {
+                    Unit::OpenBracket | Unit::Function(_) => {
                         if SUPPORT_IMP_OP {
                             self._push(Unit::Operator(Op::IMPLICIT)); // add a _imp_ between if next is an open bracket
                         }
@@ -551,7 +1214,7 @@ impl CalcImpl {
                 Unit::Operator(last_op) => {
                     if let Unit::Operator(op) = push_unit {
                         if last_op.is_binary() && op.is_binary() {
-                            self.stack.pop(); // consecutive binary ops => replace the last one
+                            self._pop_stack(); // consecutive binary ops => replace the last one
                         }
                     }
                 }
@@ -559,17 +1222,44 @@ impl CalcImpl {
             },
             None => {}
         }
-        self._push(push_unit)
+        match push_unit {
+            Unit::Operand(operand) if self.variables.contains_key(src_unit) => {
+                self._push_operand(operand, Some(src_unit))
+            }
+            push_unit => self._push(push_unit),
+        }
     }
     fn _push(&mut self, push_unit: Unit) {
+        let position = self.unit_spans.len().saturating_sub(1); // the unit index of the unit being pushed
         match push_unit {
             Unit::OpenBracket => {
                 // if the scanned character is a left parenthesis, push it onto the stack
-                self.stack.push(push_unit);
+                if let Some(Unit::Function(_)) = self.stack.last() {
+                    self.call_marks.push(self.scanned.len()); // the arguments of the function call start here
+                }
+                self._push_stack(push_unit, position);
+            }
+            Unit::Function(_) | Unit::PrefixOperator(_) => {
+                // a function waits on the stack for its call bracket to be closed; a prefix operator waits for its operand
+                self._push_stack(push_unit, position);
+            }
+            Unit::Comma => {
+                // pop operators from the stack until the open bracket of the function call, which is kept
+                while let Some(stack_unit) = self.stack.last() {
+                    if *stack_unit == Unit::OpenBracket {
+                        break;
+                    }
+                    let (unit, unit_position) = self._pop_stack().unwrap();
+                    self._push_to_scanned(&unit, Some(unit_position));
+                }
             }
             Unit::CloseBracket => {
                 // if the scanned character is a right parenthesis, pop operators from the stack and append them to the postfix expression until a left parenthesis is found
                 self._push_all_to_scanned(true);
+                if let Some(Unit::Function(_)) = self.stack.last() {
+                    let (unit, unit_position) = self._pop_stack().unwrap();
+                    self._push_to_scanned(&unit, Some(unit_position));
+                }
                 // while self.stack.len() > 0 {
                 //     let stack_unit = self.stack.pop().unwrap();
                 //     if stack_unit == Unit::OpenBracket {
@@ -589,23 +1279,65 @@ impl CalcImpl {
                                 break;
                             }
                         }
+                        Unit::PrefixOperator(_) => {
+                            if (OpPriority::PREFIX as u8) < order {
+                                break;
+                            }
+                        }
                         _ => {
                             break;
                         }
                     }
-                    let unit = self.stack.pop().unwrap();
-                    self._push_to_scanned(&unit);
+                    let (unit, unit_position) = self._pop_stack().unwrap();
+                    self._push_to_scanned(&unit, Some(unit_position));
                 }
                 if op.is_unary() {
-                    self._push_to_scanned(&push_unit);
+                    self._push_to_scanned(&push_unit, Some(position));
                 } else {
-                    self.stack.push(push_unit /*.clone()*/);
+                    self._push_stack(push_unit /*.clone()*/, position);
                 }
             }
-            Unit::Operand(operand) => self.scanned.push(operand),
+            Unit::Operand(operand) => self._push_operand(operand, None),
+        }
+    }
+    fn _push_stack(&mut self, unit: Unit, position: usize) {
+        self.stack.push(unit);
+        self.stack_positions.push(position);
+    }
+    fn _pop_stack(&mut self) -> Option<(Unit, usize)> {
+        let unit = self.stack.pop()?;
+        Some((unit, self.stack_positions.pop().unwrap()))
+    }
+    fn _push_operand(&mut self, operand: CalcValue, variable: Option<&str>) {
+        if let Some(nodes) = &mut self.nodes {
+            nodes.push(match variable {
+                Some(name) => CalcNode::Variable(name.to_string()),
+                None => CalcNode::Number(operand.clone()),
+            });
+        }
+        self.scanned.push(operand);
+    }
+    fn _pop_operand(&mut self) {
+        if let Some(nodes) = &mut self.nodes {
+            nodes.pop();
+        }
+        self.scanned.pop();
+    }
+    /// when compiling, replace the last nodes (as many as the scanned values taken) with the node made from them;
+    /// the missing ones are the previous result, like when calculating
+    fn _record_node<F: FnOnce(Vec<CalcNode>) -> CalcNode>(&mut self, take_count: usize, make: F) {
+        if let Some(nodes) = &mut self.nodes {
+            let mut taken = nodes.split_off(nodes.len().saturating_sub(take_count));
+            while taken.len() < take_count {
+                taken.insert(0, CalcNode::Variable(ANS_VARIABLE.to_owned()));
+            }
+            nodes.push(make(taken));
         }
     }
     fn eval(&mut self) {
+        if let Some(Unit::PrefixOperator(_)) = self.last_pushed {
+            self._pop_stack(); // a dangling prefix operator has nothing to operate on
+        }
         self.last_pushed = None;
         self._push_all_to_scanned(false);
         self.result = if !self.scanned.is_empty() {
@@ -615,7 +1347,20 @@ impl CalcImpl {
                 }
                 let mut result = self.scanned.pop().unwrap();
                 while let Some(operand) = self.scanned.pop() {
-                    result = Op::IMPLICIT.evaluate_binary(result, operand);
+                    let (value, err) = self._operate_binary(Op::IMPLICIT, result, operand);
+                    result = value;
+                    self._set_error(err, None);
+                }
+                if let Some(nodes) = &mut self.nodes {
+                    let mut node = nodes.pop().unwrap();
+                    while let Some(operand) = nodes.pop() {
+                        node = CalcNode::Binary(
+                            CalcOperator(Op::IMPLICIT),
+                            Box::new(node),
+                            Box::new(operand),
+                        );
+                    }
+                    nodes.push(node);
                 }
                 result
             } else {
@@ -625,11 +1370,16 @@ impl CalcImpl {
                 self.scanned.pop().unwrap()
             }
         } else {
-            self.result
+            self._record_node(1, |mut taken| taken.remove(0)); // the previous result
+            self._previous_result()
         };
     }
+    /// the previous result, as the implicit operand, in the current number mode
+    fn _previous_result(&self) -> CalcValue {
+        self._to_mode_value(self.result.clone(), false)
+    }
     fn get_last_operator(&self) -> Option<String> {
-        match self.last_pushed {
+        match &self.last_pushed {
             Some(Unit::Operator(op)) => Some(op.to_string()),
             _ => None,
         }
@@ -643,125 +1393,1120 @@ impl CalcImpl {
         }
         count
     }
+    fn is_in_function_call(&self) -> bool {
+        match self
+            .stack
+            .iter()
+            .rposition(|unit| *unit == Unit::OpenBracket)
+        {
+            Some(idx) => idx > 0 && matches!(self.stack[idx - 1], Unit::Function(_)),
+            None => false,
+        }
+    }
+    fn parse_operand(&self, unit: &str) -> Option<CalcValue> {
+        let radix = match unit.get(..2) {
+            Some("0x") => 16,
+            Some("0o") => 8,
+            Some("0b") => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            let value = u64::from_str_radix(&unit[2..], radix).ok()?;
+            let value = CalcDecimal::parse(&value.to_string())?;
+            return Some(self._to_mode_value(CalcValue::Decimal(value), false));
+        }
+        if let CalcNumberMode::Decimal(_)
+        | CalcNumberMode::Rational
+        | CalcNumberMode::Programmer(_) = self.number_mode
+        {
+            if let Some(value) = CalcDecimal::parse(unit) {
+                return Some(self._to_mode_value(CalcValue::Decimal(value), false));
+            }
+        }
+        if self.number_mode == CalcNumberMode::Complex {
+            if let Some(imag) = unit.strip_suffix(IMAGINARY_UNIT) {
+                let imag = if imag.is_empty() {
+                    Ok(1.0)
+                } else {
+                    imag.parse::<f64>()
+                };
+                if let Ok(imag) = imag {
+                    return Some(CalcValue::Complex(CalcComplex::new(0.0, imag)));
+                    // like "i" or "2i"
+                }
+            }
+        }
+        unit.parse::<f64>().ok().map(CalcValue::Float)
+    }
+    /// whether the operand is named, like a constant, a variable or a unit, which is implicitly multiplied with the operand before it
+    fn is_named_operand(&self, unit: &str) -> bool {
+        DumbCalcProcessor::_is_unit_constant(unit)
+            || self.variables.contains_key(unit)
+            || self.find_quantity_unit(unit).is_some()
+            || (self.number_mode == CalcNumberMode::Complex && unit == IMAGINARY_UNIT)
+    }
+    /// a quantity of 1 unit, like "km", if the units are enabled
+    fn find_quantity_unit(&self, name: &str) -> Option<CalcValue> {
+        if !self.units_enabled {
+            return None;
+        }
+        CalcQuantity::from_unit(name).map(CalcValue::Quantity)
+    }
+    fn find_custom_function(&self, name: &str) -> Option<usize> {
+        self.custom_functions
+            .iter()
+            .position(|custom_function| custom_function.name == name)
+    }
+    fn get_function_name(&self, func: CalcFn) -> String {
+        match func {
+            CalcFn::Builtin(func) => func.to_string(),
+            CalcFn::Unary(op) => op.to_string(),
+            CalcFn::Custom(func_idx) => self.custom_functions[func_idx].name.clone(),
+        }
+    }
     fn use_angle_mode(&mut self, angle_mode: AngleMode) {
         self.angle_mode = angle_mode;
     }
     fn reset(&mut self) {
         self.scanned.clear();
         self.stack.clear();
-        self.result = 0.0;
+        self.stack_positions.clear();
+        self.unit_spans.clear();
+        self.call_marks.clear();
+        self.result = CalcValue::Float(0.0);
         self.last_pushed = None;
+        self.error = None;
+        if let Some(nodes) = &mut self.nodes {
+            nodes.clear();
+        }
     }
 }
 impl CalcImpl {
     fn _push_all_to_scanned(&mut self, until_open_bracket: bool) {
-        while let Some(stack_unit) = self.stack.pop() {
+        while let Some((stack_unit, position)) = self._pop_stack() {
             if until_open_bracket && stack_unit == Unit::OpenBracket {
                 break;
             }
-            self._push_to_scanned(&stack_unit);
+            self._push_to_scanned(&stack_unit, Some(position));
         }
     }
-    fn _push_to_scanned(&mut self, unit: &Unit) {
+    /// * position: the unit index of the unit, which an error is attributed to
+    fn _push_to_scanned(&mut self, unit: &Unit, position: Option<usize>) {
         match unit {
             Unit::Operator(op) => {
                 let result = if op.is_unary() {
+                    self._record_node(1, |mut taken| {
+                        CalcNode::Unary(CalcOperator(*op), Box::new(taken.remove(0)))
+                    });
                     let operand = match self.scanned.pop() {
                         Some(o) => o,
-                        None => self.result,
+                        None => self._previous_result(),
                     };
-                    op.evaluate_unary(operand, self.angle_mode)
+                    let (result, err) = self._operate_unary(*op, operand);
+                    self._set_error(err, position);
+                    result
                 } else {
+                    if self.scanned.is_empty() {
+                        return;
+                    }
+                    if *op == Op::SUBTRACT
+                        && self.scanned.len() == 1
+                        && matches!(self.scanned[0], CalcValue::Quantity(_))
+                        && !matches!(self.result, CalcValue::Quantity(_))
+                    {
+                        // a leading "-" before a quantity, like "-3 km", negates it, rather than subtracting it from the previous number
+                        return self._push_to_scanned(&Unit::PrefixOperator(Op::NEGATE), position);
+                    }
+                    self._record_node(2, |mut taken| {
+                        let right = taken.pop().unwrap();
+                        let left = taken.pop().unwrap();
+                        CalcNode::Binary(CalcOperator(*op), Box::new(left), Box::new(right))
+                    });
                     let right = match self.scanned.pop() {
                         Some(r) => r,
                         None => return,
                     };
                     let left = match self.scanned.pop() {
                         Some(l) => l,
-                        None => self.result,
+                        None => self._previous_result(),
                     };
-                    op.evaluate_binary(left, right)
+                    let (result, err) = self._operate_binary(*op, left, right);
+                    self._set_error(err, position);
+                    result
+                };
+                self.scanned.push(result)
+            }
+            Unit::PrefixOperator(op) => {
+                self._record_node(1, |mut taken| {
+                    CalcNode::Unary(CalcOperator(*op), Box::new(taken.remove(0)))
+                });
+                let operand = match self.scanned.pop() {
+                    Some(o) => o,
+                    None => self._previous_result(),
                 };
+                let (result, err) = self._operate_unary(*op, operand);
+                self._set_error(err, position);
+                self.scanned.push(result)
+            }
+            Unit::Function(func) => {
+                let mark = self.call_marks.pop().unwrap_or(self.scanned.len());
+                if self.nodes.is_some() {
+                    let function = CalcFunction {
+                        func: *func,
+                        name: self.get_function_name(*func),
+                    };
+                    self._record_node(self.scanned.len() - mark, |taken| {
+                        CalcNode::Call(function, taken)
+                    });
+                }
+                let args = self.scanned.split_off(mark);
+                let result = self._call_function(*func, &args, position);
                 self.scanned.push(result)
             }
             Unit::OpenBracket => {} // if it an open (, ignore it
             _ => panic!("unexpected unit {:?} ... self={:?}", unit, self),
         }
     }
-}
-
-/// calculation result, which can be one of three kinds
-/// * final: final calculation result
-/// * intermediate: intermediate result during "calculation units" being pushed
-/// * error: error like calculating 1 / 0
-#[derive(Debug)]
-pub enum CalcResult {
-    Final(f64),
-    Intermediate(f64),
-    Error(String),
-}
-impl CalcResult {
-    pub fn unwrap(&self) -> f64 {
-        match *self {
-            CalcResult::Final(result) => result,
-            CalcResult::Intermediate(result) => result,
-            CalcResult::Error(ref err_msg) => panic!("Error: {}", err_msg),
+    fn _call_function(
+        &mut self,
+        func: CalcFn,
+        args: &[CalcValue],
+        position: Option<usize>,
+    ) -> CalcValue {
+        let (min_arg_count, max_arg_count) = match func {
+            CalcFn::Builtin(func) => func.get_arg_count_range(),
+            CalcFn::Unary(_) => (1, Some(1)),
+            CalcFn::Custom(func_idx) => {
+                let arg_count = self.custom_functions[func_idx].arg_count;
+                (arg_count, Some(arg_count))
+            }
+        };
+        let arg_count = args.len();
+        if arg_count < min_arg_count || max_arg_count.is_some_and(|max| arg_count > max) {
+            let expected = match max_arg_count {
+                Some(max) if max == min_arg_count => format!("{}", min_arg_count),
+                Some(max) => format!("{} to {}", min_arg_count, max),
+                None => format!("at least {}", min_arg_count),
+            };
+            let err_msg = format!(
+                "function '{}' expects {} arguments, but got {}",
+                self.get_function_name(func),
+                expected,
+                arg_count
+            );
+            let err = CalcError::new(CalcErrorKind::InvalidArguments, err_msg);
+            self._set_error(Some(err), position);
+            return CalcValue::Float(f64::NAN);
         }
+        let result = match self._apply_function(func, args) {
+            Ok(result) => result,
+            Err(err) => {
+                self._set_error(Some(err), position);
+                return CalcValue::Float(f64::NAN);
+            }
+        };
+        let err = self._check_function(func, args, &result);
+        self._set_error(err, position);
+        result
     }
-    pub fn is_final(&self) -> bool {
-        match *self {
-            CalcResult::Final(_) => true,
-            _ => false,
+    fn _check_function(
+        &self,
+        func: CalcFn,
+        args: &[CalcValue],
+        result: &CalcValue,
+    ) -> Option<CalcError> {
+        match func {
+            CalcFn::Unary(op) => CalcError::_from_operation(op, args, result),
+            _ => CalcError::_from_function(&self.get_function_name(func), args, result),
         }
     }
-    pub fn is_intermediate(&self) -> bool {
-        match *self {
-            CalcResult::Intermediate(_) => true,
-            _ => false,
+    /// set the error, with the unit that caused it, unless there is already an error
+    fn _set_error(&mut self, err: Option<CalcError>, position: Option<usize>) {
+        if let (None, Some(err)) = (&self.error, err) {
+            let span =
+                position.and_then(|position| self.unit_spans.get(position).copied().flatten());
+            self.error = Some(CalcError {
+                unit_index: position,
+                span,
+                ..err
+            });
         }
     }
-    pub fn is_ok(&self) -> bool {
-        match *self {
-            CalcResult::Error(_) => false,
-            _ => true,
+    /// the unit index of the unit to be pushed next
+    fn next_unit_index(&self) -> usize {
+        if self.last_pushed.is_none() {
+            0 // a new sequence
+        } else {
+            self.unit_spans.len()
         }
     }
-    pub fn is_err(&self) -> bool {
-        match *self {
-            CalcResult::Error(_) => true,
-            _ => false,
+    /// call a function with the arguments, which are of the expected number; an error if the arguments are of the wrong dimension
+    fn _apply_function(&self, func: CalcFn, args: &[CalcValue]) -> Result<CalcValue, CalcError> {
+        if let CalcFn::Unary(op) = func {
+            return self._evaluate_unary(op, args[0].clone());
+        }
+        if args.iter().any(|arg| matches!(arg, CalcValue::Quantity(_))) {
+            let err_msg = format!(
+                "function '{}' is not applicable to quantities",
+                self.get_function_name(func)
+            );
+            return Err(CalcError::new(CalcErrorKind::DimensionMismatch, err_msg));
+        }
+        if args
+            .iter()
+            .any(|arg| matches!(arg, CalcValue::Complex(arg) if !arg.is_real()))
+        {
+            return Ok(self._from_f64(f64::NAN)); // like the max of complex values
+        }
+        if let (CalcNumberMode::Decimal(context), CalcFn::Builtin(func)) = (self.number_mode, func)
+        {
+            let decimal_args: Option<Vec<&CalcDecimal>> = args
+                .iter()
+                .map(|arg| match arg {
+                    CalcValue::Decimal(arg) => Some(arg),
+                    _ => None,
+                })
+                .collect();
+            if let Some(result) =
+                decimal_args.and_then(|args| func.evaluate_decimal(&args, context))
+            {
+                return Ok(CalcValue::Decimal(result));
+            }
+        }
+        if let (CalcNumberMode::Rational, CalcFn::Builtin(func)) = (self.number_mode, func) {
+            let rational_args: Option<Vec<&CalcRational>> = args
+                .iter()
+                .map(|arg| match arg {
+                    CalcValue::Rational(arg) => Some(arg),
+                    _ => None,
+                })
+                .collect();
+            if let (Func::ROUND, Some(places)) =
+                (func, rational_args.as_ref().and_then(|args| args.get(1)))
+            {
+                let places = places.trunc().to_i64();
+                let places =
+                    places.filter(|places| places.unsigned_abs() <= RATIONAL_ROUND_PLACES_LIMIT);
+                if places.is_none() {
+                    let err_msg = format!(
+                        "function 'round' rounds to at most {} places in the rational mode",
+                        RATIONAL_ROUND_PLACES_LIMIT
+                    );
+                    return Err(CalcError::new(CalcErrorKind::InvalidArguments, err_msg));
+                }
+            }
+            if let Some(result) = rational_args.and_then(|args| func.evaluate_rational(&args)) {
+                return Ok(CalcValue::Rational(result));
+            }
         }
+        let args: Vec<f64> = args.iter().map(CalcValue::to_f64).collect();
+        let result = match func {
+            CalcFn::Builtin(func) => func.evaluate(&args, self.angle_mode),
+            CalcFn::Unary(op) => op.evaluate_unary(args[0], self.angle_mode),
+            CalcFn::Custom(func_idx) => (self.custom_functions[func_idx].func.0)(&args),
+        };
+        Ok(self._from_f64(result))
     }
-}
-impl fmt::Display for CalcResult {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            CalcResult::Final(result) => write!(f, "{}", result),
-            CalcResult::Intermediate(result) => write!(f, "{}", result),
-            CalcResult::Error(ref err_msg) => write!(f, "Error: {}", err_msg),
+    /// evaluate the operation, with the error of the result (if any)
+    fn _operate_binary(
+        &self,
+        op: Op,
+        left: CalcValue,
+        right: CalcValue,
+    ) -> (CalcValue, Option<CalcError>) {
+        let operands = [left.clone(), right.clone()];
+        match self._evaluate_binary(op, left, right) {
+            Ok(result) => {
+                let err = CalcError::_from_operation(op, &operands, &result);
+                (result, err)
+            }
+            Err(err) => (CalcValue::Float(f64::NAN), Some(err)),
         }
     }
-}
-
-#[derive(Debug, Clone, PartialEq, Copy)]
-enum AngleMode {
-    DEGREE,
-    RADIAN,
-}
-
-#[allow(non_camel_case_types)]
-enum OpPriority {
-    BINARY_AM = 1,
-    BINARY_MD = 2,
-    BINARY_IMP = 3,
-    BINARY_FN = 4, // 3
-    UNARY = 5,     // 4
-}
-
-#[derive(PartialEq, Copy, Clone, Debug)]
-enum Op {
-    ADD,
-    SUBTRACT,
+    /// like [`CalcImpl::_operate_binary`], but for an unary operation
+    fn _operate_unary(&self, op: Op, operand: CalcValue) -> (CalcValue, Option<CalcError>) {
+        let operands = [operand.clone()];
+        match self._evaluate_unary(op, operand) {
+            Ok(result) => {
+                let err = CalcError::_from_operation(op, &operands, &result);
+                (result, err)
+            }
+            Err(err) => (CalcValue::Float(f64::NAN), Some(err)),
+        }
+    }
+    /// evaluate the operation; an error if the operands are quantities of the wrong dimensions
+    fn _evaluate_binary(
+        &self,
+        op: Op,
+        left: CalcValue,
+        right: CalcValue,
+    ) -> Result<CalcValue, CalcError> {
+        if op == Op::CONVERT
+            || matches!(left, CalcValue::Quantity(_))
+            || matches!(right, CalcValue::Quantity(_))
+        {
+            for operand in [&left, &right] {
+                if let CalcValue::Complex(operand) = operand {
+                    if !operand.is_real() {
+                        let err_msg = format!("'{}' is undefined for {} with units", op, operand);
+                        return Err(CalcError::new(CalcErrorKind::Domain, err_msg));
+                    }
+                }
+            }
+            let left = CalcQuantity::from_value(&left);
+            let right = CalcQuantity::from_value(&right);
+            return op
+                .evaluate_quantity_binary(&left, &right)
+                .map(|result| self._from_quantity(result));
+        }
+        self._evaluate_number_binary(op, left, right)
+    }
+    /// like [`CalcImpl::_evaluate_binary`], but for an unary operation
+    fn _evaluate_unary(&self, op: Op, operand: CalcValue) -> Result<CalcValue, CalcError> {
+        if let CalcValue::Quantity(operand) = &operand {
+            return op
+                .evaluate_quantity_unary(operand)
+                .map(|result| self._from_quantity(result));
+        }
+        self._evaluate_number_unary(op, operand)
+    }
+    /// a quantity that ends up with no dimension, like "1 km / 1 m", is just a number
+    fn _from_quantity(&self, quantity: CalcQuantity) -> CalcValue {
+        if quantity.is_dimensionless() {
+            self._from_f64(quantity.to_base_value())
+        } else {
+            CalcValue::Quantity(quantity)
+        }
+    }
+    fn _evaluate_number_binary(
+        &self,
+        op: Op,
+        left: CalcValue,
+        right: CalcValue,
+    ) -> Result<CalcValue, CalcError> {
+        if matches!(left, CalcValue::Complex(_)) || matches!(right, CalcValue::Complex(_)) {
+            let left = CalcComplex::from_value(&left);
+            let right = CalcComplex::from_value(&right);
+            match op.evaluate_complex_binary(&left, &right) {
+                Some(result) => return Ok(CalcValue::Complex(result)),
+                None if !left.is_real() || !right.is_real() => {
+                    let err_msg = format!("'{}' is undefined for {} and {}", op, left, right);
+                    return Err(CalcError::new(CalcErrorKind::Domain, err_msg));
+                }
+                None => {} // calculated with the real parts
+            }
+        }
+        if let (
+            CalcNumberMode::Programmer(_),
+            CalcValue::Integer(left),
+            CalcValue::Integer(right),
+        ) = (self.number_mode, &left, &right)
+        {
+            match op.evaluate_integer_binary(left, right) {
+                Some(result) => return Ok(CalcValue::Integer(result)),
+                None if op == Op::SHL || op == Op::SHR => {
+                    let err_msg = format!(
+                        "'{}' is undefined for {} and {}",
+                        op,
+                        left.value(),
+                        right.value()
+                    );
+                    return Err(CalcError::new(CalcErrorKind::Domain, err_msg)); // a negative shift
+                }
+                None => {}
+            }
+        }
+        if let (CalcNumberMode::Rational, CalcValue::Rational(left), CalcValue::Rational(right)) =
+            (self.number_mode, &left, &right)
+        {
+            match op.evaluate_rational_binary(left, right) {
+                Some(result) => return Ok(CalcValue::Rational(result)),
+                None if matches!(op, Op::DIVIDE | Op::MOD) && right.is_zero() => {
+                    return Err(CalcError::_division_by_zero())
+                }
+                None => {} // like a non-integer power, calculated with f64
+            }
+        }
+        if let (
+            CalcNumberMode::Decimal(context),
+            CalcValue::Decimal(left),
+            CalcValue::Decimal(right),
+        ) = (self.number_mode, &left, &right)
+        {
+            match op.evaluate_decimal_binary(left, right, context) {
+                Some(result) => return Ok(CalcValue::Decimal(result)),
+                None if matches!(op, Op::DIVIDE | Op::MOD) && right.is_zero() => {
+                    return Err(CalcError::_division_by_zero())
+                }
+                None if matches!(
+                    op,
+                    Op::ADD | Op::SUBTRACT | Op::MULTIPLY | Op::IMPLICIT | Op::DIVIDE
+                ) =>
+                {
+                    return Err(CalcError::_too_large(op)); // beyond DECIMAL_EXP_LIMIT
+                }
+                None => {} // like a non-integer power, calculated with f64
+            }
+        }
+        Ok(self._from_f64(op.evaluate_binary(left.to_f64(), right.to_f64())))
+    }
+    fn _evaluate_number_unary(&self, op: Op, operand: CalcValue) -> Result<CalcValue, CalcError> {
+        if let CalcValue::Complex(operand) = &operand {
+            match op.evaluate_complex_unary(operand, self.angle_mode) {
+                Some(result) => return Ok(CalcValue::Complex(result)),
+                None if !operand.is_real() => {
+                    let err_msg = format!("'{}' is undefined for {}", op, operand);
+                    return Err(CalcError::new(CalcErrorKind::Domain, err_msg));
+                }
+                None => {} // calculated with the real part
+            }
+        }
+        if let (CalcNumberMode::Programmer(_), CalcValue::Integer(operand)) =
+            (self.number_mode, &operand)
+        {
+            if let Some(result) = op.evaluate_integer_unary(operand) {
+                return Ok(CalcValue::Integer(result));
+            }
+        }
+        if let (CalcNumberMode::Rational, CalcValue::Rational(operand)) =
+            (self.number_mode, &operand)
+        {
+            match op.evaluate_rational_unary(operand) {
+                Some(result) => return Ok(CalcValue::Rational(result)),
+                None if op == Op::INVERSE && operand.is_zero() => {
+                    return Err(CalcError::_division_by_zero())
+                }
+                None => {}
+            }
+        }
+        if let (CalcNumberMode::Decimal(context), CalcValue::Decimal(operand)) =
+            (self.number_mode, &operand)
+        {
+            match op.evaluate_decimal_unary(operand, context) {
+                Some(result) => return Ok(CalcValue::Decimal(result)),
+                None if op == Op::INVERSE && operand.is_zero() => {
+                    return Err(CalcError::_division_by_zero())
+                }
+                None if matches!(op, Op::SQUARE | Op::INVERSE | Op::PERCENT) => {
+                    return Err(CalcError::_too_large(op)); // beyond DECIMAL_EXP_LIMIT
+                }
+                None => {}
+            }
+        }
+        Ok(self._from_f64(op.evaluate_unary(operand.to_f64(), self.angle_mode)))
+    }
+    /// wrap a value calculated with f64; in the "decimal mode", it is an approximate decimal value; in the "programmer mode", it is truncated to an integer
+    fn _from_f64(&self, value: f64) -> CalcValue {
+        match self.number_mode {
+            CalcNumberMode::Float | CalcNumberMode::Rational => CalcValue::Float(value),
+            CalcNumberMode::Decimal(_) => match CalcDecimal::from_f64(value, true) {
+                Some(value) => CalcValue::Decimal(value),
+                None => CalcValue::Float(value), // like infinity
+            },
+            CalcNumberMode::Programmer(word) => match CalcInteger::from_f64(value, word) {
+                Some(value) => CalcValue::Integer(value),
+                None => CalcValue::Float(value),
+            },
+            CalcNumberMode::Complex => CalcValue::Complex(CalcComplex::new(value, 0.0)),
+        }
+    }
+    /// convert a value to the kind of the current "number mode"
+    fn _zero(&self) -> CalcValue {
+        self._to_mode_value(CalcValue::Float(0.0), false)
+    }
+    /// * approximate: whether a float value is only an approximation (e.g. PI), rather than an exact value (e.g. 0.5)
+    fn _to_mode_value(&self, value: CalcValue, approximate: bool) -> CalcValue {
+        match (self.number_mode, value) {
+            (_, CalcValue::Quantity(value)) => CalcValue::Quantity(value), // always calculated with f64
+            (CalcNumberMode::Float, value) => CalcValue::Float(value.to_f64()),
+            (CalcNumberMode::Complex, CalcValue::Complex(value)) => CalcValue::Complex(value),
+            (CalcNumberMode::Complex, value) => {
+                CalcValue::Complex(CalcComplex::new(value.to_f64(), 0.0))
+            }
+            (_, CalcValue::Complex(value)) => {
+                self._to_mode_value(CalcValue::Float(value.re), approximate) // the real part
+            }
+            (CalcNumberMode::Decimal(_), CalcValue::Float(value)) => {
+                match CalcDecimal::from_f64(value, approximate) {
+                    Some(value) => CalcValue::Decimal(value),
+                    None => CalcValue::Float(value),
+                }
+            }
+            (CalcNumberMode::Decimal(context), CalcValue::Rational(value)) => {
+                CalcValue::Decimal(value.to_decimal(context))
+            }
+            (CalcNumberMode::Rational, CalcValue::Float(value)) => {
+                match CalcRational::from_f64(value).filter(|_| !approximate) {
+                    Some(value) => CalcValue::Rational(value),
+                    None => CalcValue::Float(value), // fall back to float for irrational values
+                }
+            }
+            (CalcNumberMode::Rational, CalcValue::Decimal(value)) => {
+                if value.approximate {
+                    CalcValue::Float(value.to_f64())
+                } else {
+                    CalcValue::Rational(CalcRational::from_decimal(&value))
+                }
+            }
+            (CalcNumberMode::Decimal(_), CalcValue::Integer(value)) => {
+                CalcValue::Decimal(value.to_decimal())
+            }
+            (CalcNumberMode::Rational, CalcValue::Integer(value)) => {
+                CalcValue::Rational(CalcRational::from_decimal(&value.to_decimal()))
+            }
+            (CalcNumberMode::Programmer(word), CalcValue::Integer(value)) => {
+                CalcValue::Integer(CalcInteger::new(value.value, word))
+            }
+            (CalcNumberMode::Programmer(word), CalcValue::Decimal(value)) => {
+                match CalcInteger::from_decimal(&value, word) {
+                    Some(value) => CalcValue::Integer(value),
+                    None => self._from_f64(value.to_f64()),
+                }
+            }
+            (CalcNumberMode::Programmer(word), value) => self._from_f64(value.to_f64()),
+            (_, value) => value,
+        }
+    }
+}
+
+impl CalcImpl {
+    fn _evaluate_node(
+        &self,
+        node: &CalcNode,
+        names: &[String],
+        values: &[CalcValue],
+    ) -> Result<CalcValue, CalcError> {
+        let (result, err) = match node {
+            CalcNode::Number(value) => return Ok(value.clone()),
+            CalcNode::Variable(name) => match names.binary_search(name) {
+                Ok(idx) => return Ok(values[idx].clone()),
+                Err(_) => {
+                    let err_msg = format!("variable '{}' is not bound", name);
+                    return Err(CalcError::new(CalcErrorKind::UnboundVariable, err_msg));
+                }
+            },
+            CalcNode::Unary(op, operand) => {
+                let operand = self._evaluate_node(operand, names, values)?;
+                self._operate_unary(op.0, operand)
+            }
+            CalcNode::Binary(op, left, right) => {
+                let left = self._evaluate_node(left, names, values)?;
+                let right = self._evaluate_node(right, names, values)?;
+                self._operate_binary(op.0, left, right)
+            }
+            CalcNode::Call(function, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self._evaluate_node(arg, names, values))
+                    .collect::<Result<Vec<CalcValue>, CalcError>>()?;
+                let result = self._apply_function(function.func, &args)?;
+                let err = self._check_function(function.func, &args, &result);
+                (result, err)
+            }
+        };
+        match err {
+            Some(err) => Err(err),
+            None => Ok(result),
+        }
+    }
+    /// replace the parts without variables (nor registered functions, which may not always return the same value) with their values
+    fn _fold_node(&self, node: CalcNode) -> CalcNode {
+        let node = match node {
+            CalcNode::Unary(op, operand) => {
+                CalcNode::Unary(op, Box::new(self._fold_node(*operand)))
+            }
+            CalcNode::Binary(op, left, right) => CalcNode::Binary(
+                op,
+                Box::new(self._fold_node(*left)),
+                Box::new(self._fold_node(*right)),
+            ),
+            CalcNode::Call(function, args) => CalcNode::Call(
+                function,
+                args.into_iter().map(|arg| self._fold_node(arg)).collect(),
+            ),
+            node => return node,
+        };
+        let foldable = match &node {
+            CalcNode::Unary(_, operand) => operand.is_number(),
+            CalcNode::Binary(_, left, right) => left.is_number() && right.is_number(),
+            CalcNode::Call(function, args) => {
+                !matches!(function.func, CalcFn::Custom(_)) && args.iter().all(CalcNode::is_number)
+            }
+            _ => false,
+        };
+        if foldable {
+            match self._evaluate_node(&node, &[], &[]) {
+                Ok(value) if value.to_f64().is_finite() => return CalcNode::Number(value),
+                _ => {} // keep the node if it results in an error, which is only reported when evaluating
+            }
+        }
+        node
+    }
+    fn _from_binding(&self, value: f64) -> CalcValue {
+        match self.number_mode {
+            CalcNumberMode::Float => CalcValue::Float(value),
+            _ => self._to_mode_value(_to_variable_value(value), true),
+        }
+    }
+}
+
+/// an expression compiled with [`DumbCalcProcessor::compile`], which can be evaluated many times with different variable values
+#[derive(Debug, Clone)]
+pub struct CalcExpr {
+    root: CalcNode,
+    variables: Vec<String>,           // sorted
+    defaults: Vec<Option<CalcValue>>, // the values of the variables when compiled
+    calc_impl: Box<CalcImpl>,         // for the "number mode" etc when compiled
+}
+impl CalcExpr {
+    /// the root node of the (constant-folded) syntax tree
+    pub fn get_root(&self) -> &CalcNode {
+        &self.root
+    }
+    /// the variables (sorted by name) that the expression uses
+    pub fn get_variables(&self) -> &[String] {
+        &self.variables
+    }
+    /// evaluate the expression with the variable values bound by name;
+    /// a variable not bound takes its value when the expression was compiled (like "ans"), if any
+    pub fn evaluate(&self, bindings: &[(&str, f64)]) -> Result<f64, CalcError> {
+        self.evaluate_value(bindings).map(|value| value.to_f64())
+    }
+    /// like [`CalcExpr::evaluate`], but return the [`CalcValue`], which can be the exact value calculated in the "decimal mode" or the "rational mode"
+    pub fn evaluate_value(&self, bindings: &[(&str, f64)]) -> Result<CalcValue, CalcError> {
+        let mut values = self.defaults.clone();
+        for (name, value) in bindings {
+            if let Ok(idx) = self.variables.binary_search_by(|v| v.as_str().cmp(name)) {
+                values[idx] = Some(self.calc_impl._from_binding(*value));
+            }
+        }
+        let mut bound_values = Vec::with_capacity(values.len());
+        for (idx, value) in values.into_iter().enumerate() {
+            match value {
+                Some(value) => bound_values.push(value),
+                None => {
+                    let err_msg = format!("variable '{}' is not bound", self.variables[idx]);
+                    return Err(CalcError::new(CalcErrorKind::UnboundVariable, err_msg));
+                }
+            }
+        }
+        self._evaluate(&bound_values)
+    }
+    /// like [`CalcExpr::evaluate`], but with the values of all the variables, in the order of [`CalcExpr::get_variables`]
+    pub fn evaluate_with(&self, values: &[f64]) -> Result<f64, CalcError> {
+        if values.len() != self.variables.len() {
+            let err_msg = format!(
+                "expects {} variable values, but got {}",
+                self.variables.len(),
+                values.len()
+            );
+            return Err(CalcError::new(CalcErrorKind::InvalidArguments, err_msg));
+        }
+        let values: Vec<CalcValue> = values
+            .iter()
+            .map(|value| self.calc_impl._from_binding(*value))
+            .collect();
+        self._evaluate(&values).map(|value| value.to_f64())
+    }
+    fn _evaluate(&self, values: &[CalcValue]) -> Result<CalcValue, CalcError> {
+        let result = self
+            .calc_impl
+            ._evaluate_node(&self.root, &self.variables, values)?;
+        match CalcError::_from_result(&result) {
+            Some(err) => Err(err), // like from a variable bound to NaN
+            None => Ok(result),
+        }
+    }
+}
+impl fmt::Display for CalcExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.root)
+    }
+}
+
+/// a node of the syntax tree of a [`CalcExpr`]; it is displayed as infix, which can be compiled again
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcNode {
+    Number(CalcValue),
+    Variable(String),
+    /// an unary operator, like "sin" or "%", with its operand
+    Unary(CalcOperator, Box<CalcNode>),
+    /// a binary operator, like "+" or "and", with its left and right operands
+    Binary(CalcOperator, Box<CalcNode>, Box<CalcNode>),
+    /// a function call, like "max", with its arguments
+    Call(CalcFunction, Vec<CalcNode>),
+}
+impl CalcNode {
+    pub fn is_number(&self) -> bool {
+        matches!(self, CalcNode::Number(_))
+    }
+    fn _collect_variables(&self, variables: &mut Vec<String>) {
+        match self {
+            CalcNode::Number(_) => {}
+            CalcNode::Variable(name) => variables.push(name.clone()),
+            CalcNode::Unary(_, operand) => operand._collect_variables(variables),
+            CalcNode::Binary(_, left, right) => {
+                left._collect_variables(variables);
+                right._collect_variables(variables);
+            }
+            CalcNode::Call(_, args) => {
+                for arg in args {
+                    arg._collect_variables(variables);
+                }
+            }
+        }
+    }
+    /// the priority of the node when displayed as infix; none if displayed like with a prefix operator, like "-2"
+    fn _infix_priority(&self) -> Option<u8> {
+        match self {
+            CalcNode::Number(value) => {
+                let value = value.to_string();
+                if value.starts_with('-') {
+                    None
+                } else if value.ends_with(IMAGINARY_UNIT) && value.contains(['+', '-']) {
+                    Some(OpPriority::BINARY_AM as u8) // a complex value like "3+2i"
+                } else if value.contains('/') || value.contains(' ') {
+                    Some(OpPriority::BINARY_MD as u8) // a fraction, or a quantity like "5 km/h"
+                } else {
+                    Some(u8::MAX)
+                }
+            }
+            CalcNode::Unary(op, _) if op.0 == Op::NEGATE => None,
+            CalcNode::Unary(op, _) if op.0 == Op::PERCENT => Some(OpPriority::UNARY as u8),
+            CalcNode::Binary(op, _, _) if op.0 == Op::IMPLICIT => Some(Op::MULTIPLY.get_order()), // displayed as "*"
+            CalcNode::Binary(op, _, _) => Some(op.0.get_order()),
+            _ => Some(u8::MAX),
+        }
+    }
+    fn _fmt_operand(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        priority: u8,
+        is_right: bool,
+    ) -> fmt::Result {
+        // note that all binary operators are left-associative;
+        // and an operand like "-2" is taken by an operator that binds tighter than the "-", like "^"
+        let bracket = match self._infix_priority() {
+            Some(operand_priority) => {
+                operand_priority < priority || (is_right && operand_priority == priority)
+            }
+            None => priority >= OpPriority::PREFIX as u8,
+        };
+        if bracket {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+impl fmt::Display for CalcNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcNode::Number(value) => write!(f, "{}", value),
+            CalcNode::Variable(name) => write!(f, "{}", name),
+            CalcNode::Unary(op, operand) if op.0 == Op::NEGATE => {
+                write!(f, "-")?;
+                operand._fmt_operand(f, OpPriority::PREFIX as u8, true)
+            }
+            CalcNode::Unary(op, operand) if op.0 == Op::PERCENT => {
+                operand._fmt_operand(f, OpPriority::UNARY as u8, false)?;
+                write!(f, "%")
+            }
+            CalcNode::Unary(op, operand) => write!(f, "{}({})", op, operand),
+            CalcNode::Binary(op, left, right) => {
+                let priority = self._infix_priority().unwrap();
+                left._fmt_operand(f, priority, false)?;
+                write!(f, " {} ", op)?;
+                right._fmt_operand(f, priority, true)
+            }
+            CalcNode::Call(function, args) => {
+                write!(f, "{}(", function)?;
+                for (idx, arg) in args.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// an operator of a [`CalcNode`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalcOperator(Op);
+impl CalcOperator {
+    pub fn is_unary(&self) -> bool {
+        self.0.is_unary()
+    }
+}
+impl fmt::Display for CalcOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Op::IMPLICIT => write!(f, "*"),
+            op => write!(f, "{}", op),
+        }
+    }
+}
+
+/// a function of a [`CalcNode`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcFunction {
+    func: CalcFn,
+    name: String,
+}
+impl CalcFunction {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+impl fmt::Display for CalcFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+type CalcCustomFnType = dyn Fn(&[f64]) -> f64 + Send + Sync;
+
+#[derive(Clone)]
+struct CalcCustomFn(Arc<CalcCustomFnType>);
+impl fmt::Debug for CalcCustomFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CalcCustomFn")
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CalcCustomFunction {
+    name: String,
+    arg_count: usize,
+    func: CalcCustomFn,
+}
+
+/// calculation result, which can be one of three kinds
+/// * final: final calculation result
+/// * intermediate: intermediate result during "calculation units" being pushed
+/// * error: error like calculating 1 / 0
+#[derive(Debug)]
+pub enum CalcResult {
+    Final(CalcValue),
+    Intermediate(CalcValue),
+    Error(CalcError),
+}
+impl CalcResult {
+    pub fn unwrap(&self) -> f64 {
+        self.unwrap_value().to_f64()
+    }
+    /// like [`CalcResult::unwrap`], but return the [`CalcValue`], which can be the exact value calculated in the "decimal mode" or the "rational mode"
+    pub fn unwrap_value(&self) -> &CalcValue {
+        match *self {
+            CalcResult::Final(ref result) => result,
+            CalcResult::Intermediate(ref result) => result,
+            CalcResult::Error(ref err) => panic!("Error: {}", err),
+        }
+    }
+    /// like `to_string()`, but display a fraction as a mixed number; e.g. "2 1/3" rather than "7/3"
+    pub fn to_mixed_string(&self) -> String {
+        match *self {
+            CalcResult::Final(ref result) => result.to_mixed_string(),
+            CalcResult::Intermediate(ref result) => result.to_mixed_string(),
+            CalcResult::Error(_) => self.to_string(),
+        }
+    }
+    pub fn is_final(&self) -> bool {
+        match *self {
+            CalcResult::Final(_) => true,
+            _ => false,
+        }
+    }
+    pub fn is_intermediate(&self) -> bool {
+        match *self {
+            CalcResult::Intermediate(_) => true,
+            _ => false,
+        }
+    }
+    pub fn is_ok(&self) -> bool {
+        match *self {
+            CalcResult::Error(_) => false,
+            _ => true,
+        }
+    }
+    pub fn is_err(&self) -> bool {
+        match *self {
+            CalcResult::Error(_) => true,
+            _ => false,
+        }
+    }
+}
+impl fmt::Display for CalcResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CalcResult::Final(ref result) => write!(f, "{}", result),
+            CalcResult::Intermediate(ref result) => write!(f, "{}", result),
+            CalcResult::Error(ref err) => write!(f, "Error: {}", err),
+        }
+    }
+}
+
+/// the kind of a [`CalcError`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CalcErrorKind {
+    /// like "1 / 0" or "0 inv"
+    DivisionByZero,
+    /// an operation that is undefined for the operand, like "sqrt" of -1, "log" of 0, or "asin" of 2
+    Domain,
+    /// a result that is too large, like "exp" of 1000
+    Overflow,
+    /// a ")" without a matching "("
+    UnbalancedBracket,
+    /// a "calculation unit" that is not recognized
+    UnknownToken,
+    /// a "calculation unit" that is misplaced, like a "," outside of a function call
+    InvalidSyntax,
+    /// a function called with the wrong number of arguments, or with an argument beyond what it supports, like too many places to "round" to in the "rational mode"
+    InvalidArguments,
+    /// a variable of a [`CalcExpr`] that is not bound when evaluating
+    UnboundVariable,
+    /// a calculation with quantities of the wrong dimensions, like "1 km + 1 kg" -- see [`DumbCalcProcessor::use_units`]
+    DimensionMismatch,
+}
+
+/// an error of [`DumbCalcProcessor`], which tells the offending "calculation unit" if known:
+/// * the unit index: the index of the unit in the sequence of "calculation units" pushed, since the last evaluation
+/// * the span: the character span of the unit in the string parsed by [`DumbCalcProcessor::parse_and_push`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcError {
+    kind: CalcErrorKind,
+    message: String,
+    unit_index: Option<usize>,
+    span: Option<(usize, usize)>,
+}
+impl CalcError {
+    fn new<T: Into<String>>(kind: CalcErrorKind, message: T) -> CalcError {
+        CalcError {
+            kind,
+            message: message.into(),
+            unit_index: None,
+            span: None,
+        }
+    }
+    pub fn kind(&self) -> CalcErrorKind {
+        self.kind
+    }
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    pub fn unit_index(&self) -> Option<usize> {
+        self.unit_index
+    }
+    /// the start (inclusive) and end (exclusive) character indexes
+    pub fn span(&self) -> Option<(usize, usize)> {
+        self.span
+    }
+    fn _from_result(result: &CalcValue) -> Option<CalcError> {
+        let result = result._to_checked_f64()?;
+        if result.is_nan() {
+            Some(CalcError::new(CalcErrorKind::Domain, "result is NaN"))
+        } else if result.is_infinite() {
+            Some(CalcError::new(
+                CalcErrorKind::Overflow,
+                "result is infinity",
+            ))
+        } else {
+            None
+        }
+    }
+    /// the error of an operation calculated with f64 that results in NaN or infinity; none if it is due to an operand that is already NaN or infinity
+    fn _from_operation(op: Op, operands: &[CalcValue], result: &CalcValue) -> Option<CalcError> {
+        let result = result._to_checked_f64()?;
+        if result.is_finite() || operands.iter().any(CalcValue::_is_non_finite) {
+            return None;
+        }
+        let operand = |idx: usize| operands[idx].to_f64();
+        let by_zero = match op {
+            Op::DIVIDE | Op::MOD => operand(1) == 0.0,
+            Op::INVERSE => operand(0) == 0.0,
+            Op::TOPOW => operand(0) == 0.0 && operand(1) < 0.0,
+            _ => false,
+        };
+        if by_zero {
+            Some(CalcError::_division_by_zero())
+        } else if result.is_nan() || op == Op::LOG || op == Op::LN {
+            let operands: Vec<String> = operands.iter().map(CalcValue::to_string).collect();
+            let err_msg = format!("'{}' is undefined for {}", op, operands.join(" and "));
+            Some(CalcError::new(CalcErrorKind::Domain, err_msg))
+        } else {
+            Some(CalcError::_too_large(op))
+        }
+    }
+    fn _from_function(name: &str, args: &[CalcValue], result: &CalcValue) -> Option<CalcError> {
+        let result = result._to_checked_f64()?;
+        if result.is_finite() || args.iter().any(CalcValue::_is_non_finite) {
+            None
+        } else if result.is_nan() {
+            let err_msg = format!("function '{}' is undefined for the arguments", name);
+            Some(CalcError::new(CalcErrorKind::Domain, err_msg))
+        } else {
+            let err_msg = format!("the result of function '{}' is too large", name);
+            Some(CalcError::new(CalcErrorKind::Overflow, err_msg))
+        }
+    }
+    fn _division_by_zero() -> CalcError {
+        CalcError::new(CalcErrorKind::DivisionByZero, "division by zero")
+    }
+    fn _too_large(op: Op) -> CalcError {
+        let err_msg = format!("the result of '{}' is too large", op);
+        CalcError::new(CalcErrorKind::Overflow, err_msg)
+    }
+}
+impl Error for CalcError {}
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+impl From<CalcError> for DumbError {
+    fn from(err: CalcError) -> DumbError {
+        err.message.into()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Copy)]
+enum CalcNumberMode {
+    Float,
+    Decimal(CalcDecimalContext),
+    Rational,
+    Programmer(CalcWord),
+    Complex,
+}
+
+#[derive(Debug, Clone, PartialEq, Copy)]
+enum AngleMode {
+    DEGREE,
+    RADIAN,
+}
+
+#[allow(non_camel_case_types)]
+enum OpPriority {
+    BINARY_CONVERT = 0,
+    BINARY_OR = 1,
+    BINARY_XOR = 2,
+    BINARY_AND = 3,
+    BINARY_SHIFT = 4,
+    BINARY_AM = 5,
+    BINARY_MD = 6,
+    BINARY_IMP = 7,
+    PREFIX = 8,
+    BINARY_FN = 9, // 3
+    UNARY = 10,    // 4
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+enum Op {
+    ADD,
+    SUBTRACT,
     MULTIPLY,
     DIVIDE,
     IMPLICIT,
@@ -780,180 +2525,1988 @@ enum Op {
     POW10,
     INVERSE,
     EXP,
-    //MOD,
+    MOD,
     ABS,
     PERCENT,
+    AND,
+    OR,
+    XOR,
+    SHL,
+    SHR,
+    NOT,
+    CONVERT,
+}
+impl Op {
+    fn get_priority(&self) -> OpPriority {
+        match self {
+            Op::ADD | Op::SUBTRACT => OpPriority::BINARY_AM,
+            Op::MULTIPLY | Op::DIVIDE | Op::MOD => OpPriority::BINARY_MD,
+            Op::AND => OpPriority::BINARY_AND,
+            Op::OR => OpPriority::BINARY_OR,
+            Op::XOR => OpPriority::BINARY_XOR,
+            Op::SHL | Op::SHR => OpPriority::BINARY_SHIFT,
+            Op::IMPLICIT => OpPriority::BINARY_IMP,
+            Op::CONVERT => OpPriority::BINARY_CONVERT,
+            Op::TOPOW => OpPriority::BINARY_FN,
+            Op::NEGATE
+            | Op::SIN
+            | Op::COS
+            | Op::TAN
+            | Op::ASIN
+            | Op::ACOS
+            | Op::ATAN
+            | Op::LOG
+            | Op::LN
+            | Op::SQRT
+            | Op::SQUARE
+            | Op::POW10
+            | Op::INVERSE
+            | Op::EXP
+            | Op::ABS
+            | Op::PERCENT
+            | Op::NOT => OpPriority::UNARY,
+        }
+    }
+    fn get_order(&self) -> u8 {
+        self.get_priority() as u8
+    }
+    fn evaluate_binary(&self, left: f64, right: f64) -> f64 {
+        match *self {
+            Op::ADD => left + right,
+            Op::SUBTRACT => left - right,
+            Op::MULTIPLY => left * right,
+            Op::DIVIDE => left / right,
+            Op::IMPLICIT => left * right,
+            Op::TOPOW => left.powf(right),
+            Op::MOD => left % right,
+            Op::AND => ((left as i64) & (right as i64)) as f64,
+            Op::OR => ((left as i64) | (right as i64)) as f64,
+            Op::XOR => ((left as i64) ^ (right as i64)) as f64,
+            Op::SHL | Op::SHR => {
+                if right < 0.0 || right.fract() != 0.0 {
+                    return f64::NAN;
+                }
+                let left = left as i64;
+                if right >= 64.0 {
+                    return if *self == Op::SHR && left < 0 {
+                        -1.0
+                    } else {
+                        0.0
+                    };
+                }
+                if *self == Op::SHL {
+                    (left << right as u32) as f64
+                } else {
+                    (left >> right as u32) as f64
+                }
+            }
+            _ => panic!("{:?} non-binary operator", self),
+        }
+    }
+    fn is_unary(&self) -> bool {
+        *self == Op::NEGATE
+            || *self == Op::COS
+            || *self == Op::SIN
+            || *self == Op::TAN
+            || *self == Op::ASIN
+            || *self == Op::ACOS
+            || *self == Op::ATAN
+            || *self == Op::LOG
+            || *self == Op::LN
+            || *self == Op::SQRT
+            || *self == Op::SQUARE
+            || *self == Op::POW10
+            || *self == Op::INVERSE
+            || *self == Op::EXP
+            || *self == Op::ABS
+            || *self == Op::PERCENT
+            || *self == Op::NOT
+    }
+    fn is_binary(&self) -> bool {
+        !self.is_unary()
+    }
+    fn evaluate_unary(&self, operand: f64, angle_mode: AngleMode) -> f64 {
+        match *self {
+            Op::NEGATE => -operand,
+            Op::SIN => Op::_to_rad_angle(operand, angle_mode).sin(),
+            Op::COS => Op::_to_rad_angle(operand, angle_mode).cos(),
+            Op::TAN => Op::_to_rad_angle(operand, angle_mode).tan(),
+            Op::ASIN => Op::_from_rad_angle(operand.asin(), angle_mode),
+            Op::ACOS => Op::_from_rad_angle(operand.acos(), angle_mode),
+            Op::ATAN => Op::_from_rad_angle(operand.atan(), angle_mode),
+            Op::LOG => operand.log10(),
+            Op::LN => operand.ln(),
+            Op::SQRT => operand.sqrt(),
+            Op::SQUARE => operand * operand,
+            Op::POW10 => 10.0_f64.powf(operand),
+            Op::INVERSE => 1.0 / operand,
+            Op::EXP => operand.exp(),
+            //Op::MOD => operand % 1.0,
+            Op::ABS => operand.abs(),
+            Op::PERCENT => operand / 100.0,
+            Op::NOT => !(operand as i64) as f64,
+            _ => panic!("{:?} non-unary operator", self),
+        }
+    }
+    /// evaluate with decimal values; none if the operation is not supported for decimal values (e.g. a non-integer power)
+    fn evaluate_decimal_binary(
+        &self,
+        left: &CalcDecimal,
+        right: &CalcDecimal,
+        context: CalcDecimalContext,
+    ) -> Option<CalcDecimal> {
+        match *self {
+            Op::ADD => left.add(right, context),
+            Op::SUBTRACT => left.sub(right, context),
+            Op::MULTIPLY | Op::IMPLICIT => left.mul(right, context),
+            Op::DIVIDE => left.div(right, context),
+            Op::MOD => left.rem(right, context),
+            Op::TOPOW => left.pow(right.to_i64()?, context),
+            _ => None,
+        }
+    }
+    /// evaluate with a decimal value; none if the operation is not supported for decimal values (e.g. "sin")
+    fn evaluate_decimal_unary(
+        &self,
+        operand: &CalcDecimal,
+        context: CalcDecimalContext,
+    ) -> Option<CalcDecimal> {
+        match *self {
+            Op::NEGATE => Some(operand.neg()),
+            Op::ABS => Some(operand.abs()),
+            Op::SQUARE => operand.mul(operand, context),
+            Op::INVERSE => CalcDecimal::from_i64(1).div(operand, context),
+            Op::PERCENT => operand.div(&CalcDecimal::from_i64(100), context),
+            Op::POW10 => CalcDecimal::from_i64(10).pow(operand.to_i64()?, context),
+            _ => None,
+        }
+    }
+    /// evaluate with rational values; none if the operation is not supported for rational values (e.g. a non-integer power)
+    fn evaluate_rational_binary(
+        &self,
+        left: &CalcRational,
+        right: &CalcRational,
+    ) -> Option<CalcRational> {
+        match *self {
+            Op::ADD => Some(left.add(right)),
+            Op::SUBTRACT => Some(left.sub(right)),
+            Op::MULTIPLY | Op::IMPLICIT => Some(left.mul(right)),
+            Op::DIVIDE => left.div(right),
+            Op::MOD => left.rem(right),
+            Op::TOPOW => left.pow(right.to_i64()?),
+            _ => None,
+        }
+    }
+    /// evaluate with a rational value; none if the operation is not supported for rational values (e.g. "sqrt")
+    fn evaluate_rational_unary(&self, operand: &CalcRational) -> Option<CalcRational> {
+        match *self {
+            Op::NEGATE => Some(operand.neg()),
+            Op::ABS => Some(operand.abs()),
+            Op::SQUARE => Some(operand.mul(operand)),
+            Op::INVERSE => CalcRational::from_i64(1).div(operand),
+            Op::PERCENT => operand.div(&CalcRational::from_i64(100)),
+            Op::POW10 => CalcRational::from_i64(10).pow(operand.to_i64()?),
+            _ => None,
+        }
+    }
+    /// evaluate with integer values, wrapping around the word size of left; none if the operation is not supported for integer values (e.g. "sin"), or if dividing by zero
+    fn evaluate_integer_binary(
+        &self,
+        left: &CalcInteger,
+        right: &CalcInteger,
+    ) -> Option<CalcInteger> {
+        let (l, r) = (left.value, right.value);
+        let bits = left.word.bits as i128;
+        let value = match *self {
+            Op::ADD => l.wrapping_add(r),
+            Op::SUBTRACT => l.wrapping_sub(r),
+            Op::MULTIPLY | Op::IMPLICIT => l.wrapping_mul(r),
+            Op::DIVIDE => l.checked_div(r)?,
+            Op::MOD => l.checked_rem(r)?,
+            Op::TOPOW => {
+                if r < 0 {
+                    return None;
+                }
+                let (mut base, mut exp, mut value) = (l, r, 1i128);
+                while exp > 0 {
+                    if exp & 1 == 1 {
+                        value = value.wrapping_mul(base);
+                    }
+                    base = base.wrapping_mul(base);
+                    exp >>= 1;
+                }
+                value
+            }
+            Op::AND => l & r,
+            Op::OR => l | r,
+            Op::XOR => l ^ r,
+            Op::SHL if r >= 0 => {
+                if r >= bits {
+                    0
+                } else {
+                    l << r
+                }
+            }
+            Op::SHR if r >= 0 => {
+                if r >= bits {
+                    if l < 0 {
+                        -1
+                    } else {
+                        0
+                    }
+                } else {
+                    l >> r // arithmetic shift for a negative (signed) value
+                }
+            }
+            _ => return None,
+        };
+        Some(left.with_value(value))
+    }
+    /// evaluate with an integer value; none if the operation is not supported for integer values (e.g. "sqrt")
+    fn evaluate_integer_unary(&self, operand: &CalcInteger) -> Option<CalcInteger> {
+        let value = operand.value;
+        let value = match *self {
+            Op::NEGATE => value.wrapping_neg(),
+            Op::ABS => value.wrapping_abs(),
+            Op::SQUARE => value.wrapping_mul(value),
+            Op::NOT => !value,
+            _ => return None,
+        };
+        Some(operand.with_value(value))
+    }
+    /// evaluate with complex values; none if the operation is not supported for complex values (e.g. "mod")
+    fn evaluate_complex_binary(
+        &self,
+        left: &CalcComplex,
+        right: &CalcComplex,
+    ) -> Option<CalcComplex> {
+        match *self {
+            Op::ADD => Some(left.add(right)),
+            Op::SUBTRACT => Some(left.sub(right)),
+            Op::MULTIPLY | Op::IMPLICIT => Some(left.mul(right)),
+            Op::DIVIDE => Some(left.div(right)),
+            Op::TOPOW => Some(left.pow(right)),
+            _ => None,
+        }
+    }
+    /// evaluate with a complex value; none if the operation is not supported for complex values (e.g. "not")
+    fn evaluate_complex_unary(
+        &self,
+        operand: &CalcComplex,
+        angle_mode: AngleMode,
+    ) -> Option<CalcComplex> {
+        let to_rad = |z: &CalcComplex| z.scale(Op::_to_rad_angle(1.0, angle_mode));
+        let from_rad = |z: CalcComplex| z.scale(Op::_from_rad_angle(1.0, angle_mode));
+        let result = match *self {
+            Op::NEGATE => operand.scale(-1.0),
+            Op::SIN => to_rad(operand).sin(),
+            Op::COS => to_rad(operand).cos(),
+            Op::TAN => {
+                let operand = to_rad(operand);
+                operand.sin().div(&operand.cos())
+            }
+            Op::ASIN => from_rad(operand.asin()),
+            Op::ACOS => from_rad(
+                operand
+                    .asin()
+                    .scale(-1.0)
+                    .add_re(std::f64::consts::FRAC_PI_2),
+            ),
+            Op::ATAN => from_rad(operand.atan()),
+            Op::LOG => operand.ln().scale(1.0 / std::f64::consts::LN_10),
+            Op::LN => operand.ln(),
+            Op::SQRT => operand.sqrt(),
+            Op::SQUARE => operand.mul(operand),
+            Op::POW10 => operand.scale(std::f64::consts::LN_10).exp(),
+            Op::INVERSE => CalcComplex::new(1.0, 0.0).div(operand),
+            Op::EXP => operand.exp(),
+            Op::ABS => CalcComplex::new(operand.re.hypot(operand.im), 0.0),
+            Op::PERCENT => operand.scale(0.01),
+            _ => return None,
+        };
+        Some(result)
+    }
+    /// evaluate with quantities, where a number is a quantity without unit; an error if the operation is not applicable to the dimensions, or if the powers of the units overflow
+    fn evaluate_quantity_binary(
+        &self,
+        left: &CalcQuantity,
+        right: &CalcQuantity,
+    ) -> Result<CalcQuantity, CalcError> {
+        match *self {
+            Op::ADD | Op::SUBTRACT | Op::MOD => {
+                if left.dimensions() != right.dimensions() {
+                    return Err(Self::_dimension_error(format!(
+                        "'{}' needs quantities of the same dimension, but got {} and {}",
+                        self,
+                        left.describe(),
+                        right.describe()
+                    )));
+                }
+                let right_value = left.value_from_base(right.to_base_value()); // in the unit of the left
+                let value = match *self {
+                    Op::ADD => left.value + right_value,
+                    Op::SUBTRACT => left.value - right_value,
+                    _ => left.value % right_value,
+                };
+                Ok(left.with_value(value))
+            }
+            Op::MULTIPLY | Op::IMPLICIT | Op::DIVIDE | Op::TOPOW
+                if left.has_offset()
+                    || (right.has_offset() && !(*self == Op::IMPLICIT && right.value == 1.0)) =>
+            {
+                // only a number followed by the unit, like "10 degC", is not scaling the offset
+                Err(Self::_dimension_error(format!(
+                    "'{}' is not applicable to {} and {}, as the unit has an offset; convert it to K first",
+                    self,
+                    left.describe(),
+                    right.describe()
+                )))
+            }
+            Op::MULTIPLY | Op::IMPLICIT => left
+                .combine(right, 1, left.value * right.value)
+                .ok_or_else(|| self._power_overflow_error()),
+            Op::DIVIDE => left
+                .combine(right, -1, left.value / right.value)
+                .ok_or_else(|| self._power_overflow_error()),
+            Op::TOPOW if right.unit.is_empty() && right.value.fract() == 0.0 => {
+                let power = Some(right.value)
+                    .filter(|power| power.abs() <= i32::MAX as f64)
+                    .map(|power| power as i32);
+                power
+                    .and_then(|power| left.powi(power))
+                    .ok_or_else(|| self._power_overflow_error())
+            }
+            Op::TOPOW => Err(Self::_dimension_error(format!(
+                "the power of {} should be an integer, but got {}",
+                left.describe(),
+                right.describe()
+            ))),
+            Op::CONVERT => {
+                if right.unit.is_empty() || left.dimensions() != right.dimensions() {
+                    return Err(Self::_dimension_error(format!(
+                        "cannot convert {} to {}",
+                        left.describe(),
+                        right.describe()
+                    )));
+                }
+                if right.value != 1.0 {
+                    return Err(Self::_dimension_error(format!(
+                        "cannot convert {} to {} {}, which should be just the unit",
+                        left.describe(),
+                        right.value,
+                        right.unit()
+                    )));
+                }
+                Ok(CalcQuantity {
+                    value: right.value_from_base(left.to_base_value()),
+                    unit: right.unit.clone(),
+                })
+            }
+            _ => Err(Self::_dimension_error(format!(
+                "'{}' is not applicable to {} and {}",
+                self,
+                left.describe(),
+                right.describe()
+            ))),
+        }
+    }
+    /// like [`Op::evaluate_quantity_binary`], but for an unary operation
+    fn evaluate_quantity_unary(&self, operand: &CalcQuantity) -> Result<CalcQuantity, CalcError> {
+        let value = operand.value;
+        match *self {
+            Op::NEGATE => Ok(operand.with_value(-value)),
+            Op::ABS => Ok(operand.with_value(value.abs())),
+            Op::PERCENT => Ok(operand.with_value(value / 100.0)),
+            Op::SQUARE | Op::INVERSE if operand.has_offset() => {
+                Err(Self::_dimension_error(format!(
+                    "'{}' is not applicable to {}, as the unit has an offset; convert it to K first",
+                    self,
+                    operand.describe()
+                )))
+            }
+            Op::SQUARE => operand.powi(2).ok_or_else(|| self._power_overflow_error()),
+            Op::INVERSE => operand.powi(-1).ok_or_else(|| self._power_overflow_error()),
+            Op::SQRT if operand.unit.iter().all(|(_, power)| power % 2 == 0) => Ok(CalcQuantity {
+                value: value.sqrt(),
+                unit: operand
+                    .unit
+                    .iter()
+                    .map(|(idx, power)| (*idx, power / 2))
+                    .collect(),
+            }),
+            _ => Err(Self::_dimension_error(format!(
+                "'{}' is not applicable to {}",
+                self,
+                operand.describe()
+            ))),
+        }
+    }
+    fn _dimension_error(err_msg: String) -> CalcError {
+        CalcError::new(CalcErrorKind::DimensionMismatch, err_msg)
+    }
+    fn _power_overflow_error(&self) -> CalcError {
+        let err_msg = format!("the powers of the units of '{}' are too large", self);
+        CalcError::new(CalcErrorKind::Overflow, err_msg)
+    }
+    fn _to_rad_angle(operand: f64, angle_mode: AngleMode) -> f64 {
+        if angle_mode == AngleMode::DEGREE {
+            operand.to_radians()
+        } else {
+            operand
+        }
+    }
+    fn _from_rad_angle(operand: f64, angle_mode: AngleMode) -> f64 {
+        if angle_mode == AngleMode::DEGREE {
+            operand.to_degrees()
+        } else {
+            operand
+        }
+    }
+}
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Op::ADD => write!(f, "+"),
+            Op::SUBTRACT => write!(f, "-"),
+            Op::MULTIPLY => write!(f, "*"),
+            Op::DIVIDE => write!(f, "/"),
+            Op::IMPLICIT => write!(f, "_imp_"),
+            Op::TOPOW => write!(f, "^"),
+            Op::NEGATE => write!(f, "neg"),
+            Op::SIN => write!(f, "sin"),
+            Op::COS => write!(f, "cos"),
+            Op::TAN => write!(f, "tan"),
+            Op::ASIN => write!(f, "asin"),
+            Op::ACOS => write!(f, "acos"),
+            Op::ATAN => write!(f, "atan"),
+            Op::LOG => write!(f, "log"),
+            Op::LN => write!(f, "ln"),
+            Op::SQRT => write!(f, "sqrt"),
+            Op::SQUARE => write!(f, "square"),
+            Op::POW10 => write!(f, "pow10"),
+            Op::INVERSE => write!(f, "inv"),
+            Op::EXP => write!(f, "exp"),
+            Op::MOD => write!(f, "mod"),
+            Op::ABS => write!(f, "abs"),
+            Op::PERCENT => write!(f, "%"),
+            Op::AND => write!(f, "and"),
+            Op::OR => write!(f, "or"),
+            Op::XOR => write!(f, "xor"),
+            Op::SHL => write!(f, "<<"),
+            Op::SHR => write!(f, ">>"),
+            Op::NOT => write!(f, "not"),
+            Op::CONVERT => write!(f, "in"),
+        }
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+enum Func {
+    MAX,
+    MIN,
+    ATAN2,
+    ROUND,
+    HYPOT,
+}
+impl Func {
+    fn get_arg_count_range(&self) -> (usize, Option<usize>) {
+        match self {
+            Func::MAX | Func::MIN | Func::HYPOT => (1, None),
+            Func::ATAN2 => (2, Some(2)),
+            Func::ROUND => (1, Some(2)),
+        }
+    }
+    fn evaluate(&self, args: &[f64], angle_mode: AngleMode) -> f64 {
+        match self {
+            Func::MAX => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Func::MIN => args.iter().copied().fold(f64::INFINITY, f64::min),
+            Func::ATAN2 => Op::_from_rad_angle(args[0].atan2(args[1]), angle_mode),
+            Func::ROUND => {
+                let places = if args.len() > 1 { args[1].trunc() } else { 0.0 };
+                if places > f64::MAX_10_EXP as f64 {
+                    return args[0]; // f64 has no digits that far after the decimal point
+                }
+                if places < -f64::MAX_10_EXP as f64 {
+                    return 0.0; // rounded to a power of ten beyond any f64
+                }
+                let scale = 10.0_f64.powf(places);
+                let scaled = args[0] * scale;
+                if !scaled.is_finite() {
+                    return args[0]; // already too many digits to round
+                }
+                scaled.round() / scale
+            }
+            Func::HYPOT => args.iter().map(|arg| arg * arg).sum::<f64>().sqrt(),
+        }
+    }
+    /// evaluate with decimal values; none if the function is not supported for decimal values (e.g. "atan2")
+    fn evaluate_decimal(
+        &self,
+        args: &[&CalcDecimal],
+        context: CalcDecimalContext,
+    ) -> Option<CalcDecimal> {
+        match self {
+            Func::MAX => args.iter().copied().max_by(|a, b| a.compare(b)).cloned(),
+            Func::MIN => args.iter().copied().min_by(|a, b| a.compare(b)).cloned(),
+            Func::ROUND => {
+                let places = match args.get(1) {
+                    Some(places) => places.round_to(0, CalcRounding::Down)?.to_i64()?,
+                    None => 0,
+                };
+                args[0].round_to(places, CalcRounding::HalfUp)
+            }
+            _ => None,
+        }
+    }
+    /// evaluate with rational values; none if the function is not supported for rational values (e.g. "hypot")
+    fn evaluate_rational(&self, args: &[&CalcRational]) -> Option<CalcRational> {
+        match self {
+            Func::MAX => args.iter().copied().max_by(|a, b| a.compare(b)).cloned(),
+            Func::MIN => args.iter().copied().min_by(|a, b| a.compare(b)).cloned(),
+            Func::ROUND => {
+                let places = match args.get(1) {
+                    Some(places) => places.trunc().to_i64()?,
+                    None => 0,
+                };
+                args[0].round_to(places)
+            }
+            _ => None,
+        }
+    }
+}
+impl fmt::Display for Func {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Func::MAX => write!(f, "max"),
+            Func::MIN => write!(f, "min"),
+            Func::ATAN2 => write!(f, "atan2"),
+            Func::ROUND => write!(f, "round"),
+            Func::HYPOT => write!(f, "hypot"),
+        }
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+enum CalcFn {
+    Builtin(Func),
+    Unary(Op),
+    Custom(usize),
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Unit {
+    OpenBracket,
+    CloseBracket,
+    Operand(CalcValue),
+    Operator(Op),
+    PrefixOperator(Op),
+    Function(CalcFn),
+    Comma,
+}
+// impl Unit {
+//     fn operator(op: Op) -> Unit {
+//         Unit::Operator(op)
+//     }
+//     fn operand(operand: f64) -> Unit {
+//         Unit::Operand(operand)
+//     }
+// }
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::OpenBracket => write!(f, "("),
+            Unit::CloseBracket => write!(f, ")"),
+            Unit::Operand(operand) => write!(f, "{}", operand),
+            Unit::Operator(operator) => write!(f, "{}", operator),
+            Unit::PrefixOperator(operator) => write!(f, "{}", operator),
+            Unit::Function(CalcFn::Builtin(func)) => write!(f, "{}", func),
+            Unit::Function(CalcFn::Unary(operator)) => write!(f, "{}", operator),
+            Unit::Function(CalcFn::Custom(func_idx)) => write!(f, "fn#{}", func_idx),
+            Unit::Comma => write!(f, ","),
+        }
+    }
+}
+
+fn print_infix(infix: &[Unit]) {
+    let len = infix.len();
+    print!("[");
+    for (i, unit) in infix.iter().enumerate() {
+        if i > 0 {
+            print!(", ");
+        }
+        print!("{}", unit);
+    }
+    // for i in 0..len {
+    //     if i > 0 {
+    //         print!(", ");
+    //     }
+    //     let unit = infix[i];
+    //     print!("{}", unit);
+    // }
+    println!("]");
+}
+
+/// how to round a calculated value in the "decimal mode" -- see [`DumbCalcProcessor::use_decimal_mode`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalcRounding {
+    /// round to the nearest; away from zero if in the middle
+    HalfUp,
+    /// round to the nearest; towards zero if in the middle
+    HalfDown,
+    /// round to the nearest; to the even digit if in the middle (banker's rounding)
+    HalfEven,
+    /// round away from zero
+    Up,
+    /// round towards zero (truncate)
+    Down,
+    /// round towards positive infinity
+    Ceiling,
+    /// round towards negative infinity
+    Floor,
+}
+
+/// a calculated value -- see [`CalcResult`]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum CalcValue {
+    /// a value calculated in the default "float mode"
+    Float(f64),
+    /// a value calculated in the "decimal mode" -- see [`DumbCalcProcessor::use_decimal_mode`]
+    Decimal(CalcDecimal),
+    /// a value calculated in the "rational mode" -- see [`DumbCalcProcessor::use_rational_mode`]
+    Rational(CalcRational),
+    /// a value calculated in the "programmer mode" -- see [`DumbCalcProcessor::use_programmer_mode`]
+    Integer(CalcInteger),
+    /// a value with a unit, calculated with the units enabled -- see [`DumbCalcProcessor::use_units`]
+    Quantity(CalcQuantity),
+    /// a value calculated in the "complex mode" -- see [`DumbCalcProcessor::use_complex_mode`]
+    Complex(CalcComplex),
+}
+impl CalcValue {
+    /// the value as `f64`; the real part of a complex value
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            CalcValue::Float(value) => *value,
+            CalcValue::Decimal(value) => value.to_f64(),
+            CalcValue::Rational(value) => value.to_f64(),
+            CalcValue::Integer(value) => value.value as f64,
+            CalcValue::Quantity(value) => value.value,
+            CalcValue::Complex(value) => value.re,
+        }
+    }
+    /// like [`CalcValue::to_f64`], but NaN or infinity if the imaginary part of a complex value is; for checking the value;
+    /// none if the value is exact, like a decimal value, which is always finite however large it is
+    fn _to_checked_f64(&self) -> Option<f64> {
+        match self {
+            CalcValue::Decimal(_) | CalcValue::Rational(_) | CalcValue::Integer(_) => None,
+            CalcValue::Complex(value) if !value.im.is_finite() => Some(value.im),
+            value => Some(value.to_f64()),
+        }
+    }
+    fn _is_non_finite(&self) -> bool {
+        self._to_checked_f64()
+            .is_some_and(|value| !value.is_finite())
+    }
+    /// like `to_string()`, but display a fraction as a mixed number; e.g. "2 1/3" rather than "7/3"
+    pub fn to_mixed_string(&self) -> String {
+        match self {
+            CalcValue::Rational(value) => value.to_mixed_string(),
+            _ => self.to_string(),
+        }
+    }
+    /// whether the value is a decimal value that is (partly) calculated with `f64`, as a fallback of operations like "sin"
+    pub fn is_approximate(&self) -> bool {
+        match self {
+            CalcValue::Float(_)
+            | CalcValue::Rational(_)
+            | CalcValue::Integer(_)
+            | CalcValue::Quantity(_)
+            | CalcValue::Complex(_) => false,
+            CalcValue::Decimal(value) => value.approximate,
+        }
+    }
+}
+impl fmt::Display for CalcValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcValue::Float(value) => write!(f, "{}", value),
+            CalcValue::Decimal(value) => write!(f, "{}", value),
+            CalcValue::Rational(value) => write!(f, "{}", value),
+            CalcValue::Integer(value) => write!(f, "{}", value),
+            CalcValue::Quantity(value) => write!(f, "{}", value),
+            CalcValue::Complex(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CalcWord {
+    bits: u32,
+    signed: bool,
+}
+
+/// an integer value of a fixed word size, calculated in the "programmer mode" -- see [`DumbCalcProcessor::use_programmer_mode`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcInteger {
+    value: i128, // always wrapped around the word size
+    word: CalcWord,
+    base: u32, // the base to display the value in
+}
+impl CalcInteger {
+    fn new(value: i128, word: CalcWord) -> CalcInteger {
+        CalcInteger {
+            value: CalcInteger::_wrap(value, word),
+            word,
+            base: 10,
+        }
+    }
+    fn with_value(&self, value: i128) -> CalcInteger {
+        CalcInteger {
+            value: CalcInteger::_wrap(value, self.word),
+            ..self.clone()
+        }
+    }
+    fn with_base(&self, base: u32) -> CalcInteger {
+        CalcInteger {
+            base,
+            ..self.clone()
+        }
+    }
+    /// truncate a decimal value to an integer; none if it is too large
+    fn from_decimal(value: &CalcDecimal, word: CalcWord) -> Option<CalcInteger> {
+        let value = value.round_to(0, CalcRounding::Down)?.to_string();
+        value
+            .parse::<i128>()
+            .ok()
+            .map(|value| CalcInteger::new(value, word))
+    }
+    fn from_f64(value: f64, word: CalcWord) -> Option<CalcInteger> {
+        if value.is_finite() {
+            Some(CalcInteger::new(value.trunc() as i128, word))
+        } else {
+            None
+        }
+    }
+    fn to_decimal(&self) -> CalcDecimal {
+        CalcDecimal::parse(&self.value.to_string()).unwrap()
+    }
+    /// the value; e.g. -1 for a signed word of all 1 bits
+    pub fn value(&self) -> i128 {
+        self.value
+    }
+    /// the base that the value is displayed in -- 2, 8, 10 or 16
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+    /// the digits of the value in its base, without any prefix like "0x";
+    /// other than base 10, the bits of the word are displayed as is, like "FF" for a signed byte of value -1
+    pub fn to_digits(&self) -> String {
+        let bits = self.value & ((1i128 << self.word.bits) - 1);
+        match self.base {
+            16 => format!("{:X}", bits),
+            8 => format!("{:o}", bits),
+            2 => format!("{:b}", bits),
+            _ => self.value.to_string(),
+        }
+    }
+    fn _wrap(value: i128, word: CalcWord) -> i128 {
+        let value = value & ((1i128 << word.bits) - 1);
+        if word.signed && value >= 1i128 << (word.bits - 1) {
+            value - (1i128 << word.bits)
+        } else {
+            value
+        }
+    }
+}
+impl fmt::Display for CalcInteger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.base {
+            16 => "0x",
+            8 => "0o",
+            2 => "0b",
+            _ => "",
+        };
+        write!(f, "{}{}", prefix, self.to_digits())
+    }
+}
+
+/// the dimensions of the quantities -- see [`DumbCalcProcessor::use_units`]
+const QUANTITY_DIMENSIONS: [&str; 5] = ["length", "mass", "time", "data size", "temperature"];
+
+/// the units of the quantities: the name, the dimension, and the factor and offset to convert a value to the base unit of the dimension
+const QUANTITY_UNITS: &[(&str, usize, f64, f64)] = &[
+    ("m", 0, 1.0, 0.0),
+    ("km", 0, 1000.0, 0.0),
+    ("cm", 0, 0.01, 0.0),
+    ("mm", 0, 0.001, 0.0),
+    ("um", 0, 1e-6, 0.0),
+    ("nm", 0, 1e-9, 0.0),
+    ("inch", 0, 0.0254, 0.0),
+    ("ft", 0, 0.3048, 0.0),
+    ("yd", 0, 0.9144, 0.0),
+    ("mi", 0, 1609.344, 0.0),
+    ("kg", 1, 1.0, 0.0),
+    ("g", 1, 0.001, 0.0),
+    ("mg", 1, 1e-6, 0.0),
+    ("t", 1, 1000.0, 0.0),
+    ("lb", 1, 0.45359237, 0.0),
+    ("oz", 1, 0.028349523125, 0.0),
+    ("s", 2, 1.0, 0.0),
+    ("ms", 2, 0.001, 0.0),
+    ("min", 2, 60.0, 0.0),
+    ("h", 2, 3600.0, 0.0),
+    ("day", 2, 86400.0, 0.0),
+    ("week", 2, 604800.0, 0.0),
+    ("bit", 3, 0.125, 0.0),
+    ("B", 3, 1.0, 0.0),
+    ("KB", 3, 1e3, 0.0),
+    ("MB", 3, 1e6, 0.0),
+    ("GB", 3, 1e9, 0.0),
+    ("TB", 3, 1e12, 0.0),
+    ("KiB", 3, 1024.0, 0.0),
+    ("MiB", 3, 1048576.0, 0.0),
+    ("GiB", 3, 1073741824.0, 0.0),
+    ("TiB", 3, 1099511627776.0, 0.0),
+    ("K", 4, 1.0, 0.0),
+    ("degC", 4, 1.0, 273.15),
+    ("degF", 4, 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0),
+];
+
+/// a value with a unit, like "5 km", calculated with the units enabled -- see [`DumbCalcProcessor::use_units`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcQuantity {
+    value: f64,              // in the unit
+    unit: Vec<(usize, i32)>, // the indexes of the units in QUANTITY_UNITS, with their powers; e.g. "km/h" is [(1, 1), (19, -1)]
+}
+impl CalcQuantity {
+    fn from_unit(name: &str) -> Option<CalcQuantity> {
+        let idx = QUANTITY_UNITS
+            .iter()
+            .position(|(unit_name, ..)| *unit_name == name)?;
+        Some(CalcQuantity {
+            value: 1.0,
+            unit: vec![(idx, 1)],
+        })
+    }
+    /// a number is a quantity without unit
+    fn from_value(value: &CalcValue) -> CalcQuantity {
+        match value {
+            CalcValue::Quantity(value) => value.clone(),
+            value => CalcQuantity {
+                value: value.to_f64(),
+                unit: Vec::new(),
+            },
+        }
+    }
+    fn with_value(&self, value: f64) -> CalcQuantity {
+        CalcQuantity {
+            value,
+            unit: self.unit.clone(),
+        }
+    }
+    /// the quantity of the value, with the unit multiplied by the unit of the other quantity raised to the power (1 or -1); none if the powers overflow
+    fn combine(&self, other: &CalcQuantity, power: i32, value: f64) -> Option<CalcQuantity> {
+        let mut unit = self.unit.clone();
+        for (idx, other_power) in &other.unit {
+            let other_power = other_power.checked_mul(power)?;
+            match unit.iter().position(|(unit_idx, _)| unit_idx == idx) {
+                Some(pos) => unit[pos].1 = unit[pos].1.checked_add(other_power)?,
+                None => unit.push((*idx, other_power)),
+            }
+        }
+        unit.retain(|(_, power)| *power != 0);
+        unit.sort();
+        CalcQuantity { value, unit }._checked()
+    }
+    /// none if the powers overflow
+    fn powi(&self, power: i32) -> Option<CalcQuantity> {
+        let mut unit: Vec<(usize, i32)> = self
+            .unit
+            .iter()
+            .map(|(idx, unit_power)| Some((*idx, unit_power.checked_mul(power)?)))
+            .collect::<Option<_>>()?;
+        unit.retain(|(_, unit_power)| *unit_power != 0);
+        CalcQuantity {
+            value: self.value.powi(power),
+            unit,
+        }
+        ._checked()
+    }
+    /// none if the powers of a dimension overflow, like "m^2000000000 km^2000000000"
+    fn _checked(self) -> Option<CalcQuantity> {
+        self._checked_dimensions()?;
+        Some(self)
+    }
+    fn _checked_dimensions(&self) -> Option<[i32; QUANTITY_DIMENSIONS.len()]> {
+        let mut dimensions = [0i32; QUANTITY_DIMENSIONS.len()];
+        for (idx, power) in &self.unit {
+            let dimension = &mut dimensions[QUANTITY_UNITS[*idx].1];
+            *dimension = dimension.checked_add(*power)?;
+        }
+        Some(dimensions)
+    }
+    fn dimensions(&self) -> [i32; QUANTITY_DIMENSIONS.len()] {
+        self._checked_dimensions()
+            .expect("the powers of the dimensions are checked")
+    }
+    fn is_dimensionless(&self) -> bool {
+        self.dimensions().iter().all(|power| *power == 0)
+    }
+    fn to_base_value(&self) -> f64 {
+        self.value * self._factor() + self._offset()
+    }
+    /// convert a value in the base unit to the unit; the noise of the conversion factors is rounded away
+    fn value_from_base(&self, base_value: f64) -> f64 {
+        let value = (base_value - self._offset()) / self._factor();
+        format!("{:.14e}", value).parse().unwrap_or(value)
+    }
+    fn _factor(&self) -> f64 {
+        self.unit
+            .iter()
+            .map(|(idx, power)| QUANTITY_UNITS[*idx].2.powi(*power))
+            .product()
+    }
+    /// whether the unit is relative to an offset, like "degC", which is not to be scaled
+    fn has_offset(&self) -> bool {
+        self._offset() != 0.0
+    }
+    /// only a unit on its own, like "degC", has an offset
+    fn _offset(&self) -> f64 {
+        match self.unit[..] {
+            [(idx, 1)] => QUANTITY_UNITS[idx].3,
+            _ => 0.0,
+        }
+    }
+    /// like "km/h (length/time)"; or just the number if without unit
+    fn describe(&self) -> String {
+        if self.is_dimensionless() {
+            return self.to_base_value().to_string();
+        }
+        let dimensions = QUANTITY_DIMENSIONS
+            .iter()
+            .zip(self.dimensions())
+            .filter(|(_, power)| *power != 0)
+            .map(|(name, power)| (*name, power));
+        format!("{} ({})", self.unit(), Self::_format_powers(dimensions))
+    }
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+    /// the unit, like "km/h" or "m/s^2"
+    pub fn unit(&self) -> String {
+        let unit = self
+            .unit
+            .iter()
+            .map(|(idx, power)| (QUANTITY_UNITS[*idx].0, *power));
+        Self::_format_powers(unit)
+    }
+    fn _format_powers<'a>(powers: impl Iterator<Item = (&'a str, i32)>) -> String {
+        let powers: Vec<(&str, i32)> = powers.collect();
+        let format = |name: &str, power: i32| {
+            if power == 1 {
+                name.to_string()
+            } else {
+                format!("{}^{}", name, power)
+            }
+        };
+        let numer: Vec<String> = powers
+            .iter()
+            .filter(|(_, power)| *power > 0)
+            .map(|(name, power)| format(name, *power))
+            .collect();
+        let denom: Vec<String> = powers
+            .iter()
+            .filter(|(_, power)| *power < 0)
+            .map(|(name, power)| format(name, -power))
+            .collect();
+        if numer.is_empty() {
+            let all: Vec<String> = powers
+                .iter()
+                .map(|(name, power)| format(name, *power))
+                .collect();
+            all.join("*") // like "s^-1"
+        } else if denom.is_empty() {
+            numer.join("*")
+        } else if denom.len() == 1 {
+            format!("{}/{}", numer.join("*"), denom[0])
+        } else {
+            format!("{}/({})", numer.join("*"), denom.join("*"))
+        }
+    }
+}
+impl fmt::Display for CalcQuantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit())
+    }
+}
+
+/// a complex value, calculated in the "complex mode" -- see [`DumbCalcProcessor::use_complex_mode`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcComplex {
+    re: f64,
+    im: f64,
+    polar: Option<AngleMode>, // to display in the polar form, with the angle in the "angle mode"
+}
+impl CalcComplex {
+    fn new(re: f64, im: f64) -> CalcComplex {
+        CalcComplex {
+            re: re + 0.0, // no -0, like for "ln(-1)" to be on the branch with the positive angle
+            im: im + 0.0,
+            polar: None,
+        }
+    }
+    /// a number is a complex value without imaginary part
+    fn from_value(value: &CalcValue) -> CalcComplex {
+        match value {
+            CalcValue::Complex(value) => value.clone(),
+            value => CalcComplex::new(value.to_f64(), 0.0),
+        }
+    }
+    fn with_polar(&self, angle_mode: AngleMode) -> CalcComplex {
+        CalcComplex {
+            polar: Some(angle_mode),
+            ..self.clone()
+        }
+    }
+    /// the real part
+    pub fn re(&self) -> f64 {
+        self.re
+    }
+    /// the imaginary part
+    pub fn im(&self) -> f64 {
+        self.im
+    }
+    fn is_real(&self) -> bool {
+        self.im == 0.0
+    }
+    fn scale(&self, factor: f64) -> CalcComplex {
+        CalcComplex::new(self.re * factor, self.im * factor)
+    }
+    fn add_re(&self, re: f64) -> CalcComplex {
+        CalcComplex::new(self.re + re, self.im)
+    }
+    fn add(&self, other: &CalcComplex) -> CalcComplex {
+        CalcComplex::new(self.re + other.re, self.im + other.im)
+    }
+    fn sub(&self, other: &CalcComplex) -> CalcComplex {
+        CalcComplex::new(self.re - other.re, self.im - other.im)
+    }
+    fn mul(&self, other: &CalcComplex) -> CalcComplex {
+        CalcComplex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+    fn div(&self, other: &CalcComplex) -> CalcComplex {
+        if other.is_real() {
+            return CalcComplex::new(self.re / other.re, self.im / other.re); // like 1 / 0 is infinity
+        }
+        let denom = other.re * other.re + other.im * other.im;
+        CalcComplex::new(
+            (self.re * other.re + self.im * other.im) / denom,
+            (self.im * other.re - self.re * other.im) / denom,
+        )
+    }
+    fn pow(&self, power: &CalcComplex) -> CalcComplex {
+        if power.is_real() && power.re.fract() == 0.0 && power.re.abs() < i64::MAX as f64 {
+            if self.is_real() {
+                return CalcComplex::new(self.re.powf(power.re), 0.0);
+            }
+            // an integer power is calculated by (binary) multiplications, so that "i ^ 2" is exactly -1, and "i ^ 100" exactly 1
+            let mut result = CalcComplex::new(1.0, 0.0);
+            let mut base = self.clone();
+            let mut n = (power.re as i64).unsigned_abs();
+            while n > 0 {
+                if n % 2 == 1 {
+                    result = result.mul(&base);
+                }
+                n /= 2;
+                if n > 0 {
+                    base = base.mul(&base);
+                }
+            }
+            if result.re.is_finite() && result.im.is_finite() {
+                return if power.re < 0.0 {
+                    CalcComplex::new(1.0, 0.0).div(&result)
+                } else {
+                    result
+                };
+            } // else, too large to be multiplied out, but maybe not for the polar form
+        }
+        if self.re == 0.0 && self.im == 0.0 {
+            return if power.re > 0.0 {
+                CalcComplex::new(0.0, 0.0)
+            } else {
+                CalcComplex::new(f64::INFINITY, 0.0)
+            };
+        }
+        self.ln().mul(power).exp()
+    }
+    fn exp(&self) -> CalcComplex {
+        let modulus = self.re.exp();
+        if self.is_real() {
+            return CalcComplex::new(modulus, 0.0);
+        }
+        CalcComplex::new(modulus * self.im.cos(), modulus * self.im.sin())
+    }
+    fn ln(&self) -> CalcComplex {
+        CalcComplex::new(self.re.hypot(self.im).ln(), self.im.atan2(self.re))
+    }
+    fn sqrt(&self) -> CalcComplex {
+        let modulus = self.re.hypot(self.im);
+        let re = ((modulus + self.re) / 2.0).sqrt();
+        let im = ((modulus - self.re) / 2.0).sqrt();
+        CalcComplex::new(re, if self.im < 0.0 { -im } else { im })
+    }
+    fn sin(&self) -> CalcComplex {
+        CalcComplex::new(
+            self.re.sin() * self.im.cosh(),
+            self.re.cos() * self.im.sinh(),
+        )
+    }
+    fn cos(&self) -> CalcComplex {
+        CalcComplex::new(
+            self.re.cos() * self.im.cosh(),
+            -self.re.sin() * self.im.sinh(),
+        )
+    }
+    /// -i ln(iz + sqrt(1 - z^2))
+    fn asin(&self) -> CalcComplex {
+        if self.is_real() && self.re.abs() <= 1.0 {
+            return CalcComplex::new(self.re.asin(), 0.0);
+        }
+        let i = CalcComplex::new(0.0, 1.0);
+        let root = CalcComplex::new(1.0, 0.0).sub(&self.mul(self)).sqrt();
+        let ln = i.mul(self).add(&root).ln();
+        CalcComplex::new(ln.im, -ln.re)
+    }
+    /// i/2 (ln(1 - iz) - ln(1 + iz))
+    fn atan(&self) -> CalcComplex {
+        if self.is_real() {
+            return CalcComplex::new(self.re.atan(), 0.0);
+        }
+        let iz = CalcComplex::new(-self.im, self.re);
+        let one = CalcComplex::new(1.0, 0.0);
+        let diff = one.sub(&iz).ln().sub(&one.add(&iz).ln());
+        CalcComplex::new(-diff.im / 2.0, diff.re / 2.0)
+    }
+}
+impl fmt::Display for CalcComplex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (re, im) = (self.re, self.im);
+        if let Some(angle_mode) = self.polar {
+            let modulus = re.hypot(im);
+            let angle = Op::_from_rad_angle(im.atan2(re), angle_mode);
+            return match angle_mode {
+                AngleMode::DEGREE => write!(f, "{}∠{}°", modulus, angle),
+                AngleMode::RADIAN => write!(f, "{}∠{}", modulus, angle),
+            };
+        }
+        let imag = match im {
+            1.0 => IMAGINARY_UNIT.to_string(),
+            -1.0 => format!("-{}", IMAGINARY_UNIT),
+            im => format!("{}{}", im, IMAGINARY_UNIT),
+        };
+        if im == 0.0 {
+            write!(f, "{}", re)
+        } else if re == 0.0 {
+            write!(f, "{}", imag)
+        } else if imag.starts_with('-') {
+            write!(f, "{}{}", re, imag)
+        } else {
+            write!(f, "{}+{}", re, imag)
+        }
+    }
+}
+
+/// the exponent of a decimal is kept within ± this limit, so that adding up two exponents never overflows
+const DECIMAL_EXP_LIMIT: i64 = 1_000_000_000_000_000_000;
+/// a decimal with more zeros than this to pad is displayed in scientific notation, like "1.5e60"
+const DECIMAL_DISPLAY_ZEROS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CalcDecimalContext {
+    precision: usize,
+    rounding: CalcRounding,
+}
+
+/// an arbitrary-precision decimal value, calculated in the "decimal mode" -- see [`DumbCalcProcessor::use_decimal_mode`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcDecimal {
+    coef: CalcBigInt,
+    exp: i64, // the value is coef * 10^exp; trailing zeros of coef are always moved to exp
+    approximate: bool,
+}
+impl CalcDecimal {
+    fn new(coef: CalcBigInt, exp: i64, approximate: bool) -> CalcDecimal {
+        let mut coef = coef;
+        let mut exp = exp;
+        if coef.is_zero() {
+            exp = 0;
+        } else {
+            let ten = CalcBigInt::from_u64(10);
+            let mut zeros = 0;
+            loop {
+                let (q, r) = coef.divmod(&ten).unwrap();
+                if !r.is_zero() {
+                    break;
+                }
+                coef = q;
+                zeros += 1;
+            }
+            exp = exp.saturating_add(zeros);
+        }
+        CalcDecimal {
+            coef,
+            exp,
+            approximate,
+        }
+    }
+    fn from_i64(value: i64) -> CalcDecimal {
+        CalcDecimal::new(CalcBigInt::from_i64(value), 0, false)
+    }
+    fn from_f64(value: f64, approximate: bool) -> Option<CalcDecimal> {
+        if !value.is_finite() {
+            return None;
+        }
+        let mut decimal = CalcDecimal::parse(&format!("{:e}", value))?;
+        decimal.approximate = approximate;
+        Some(decimal)
+    }
+    /// parse a decimal like "12", "-0.5" or "1.5e3"
+    fn parse(s: &str) -> Option<CalcDecimal> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (mantissa, exp) = match s.find(['e', 'E']) {
+            Some(idx) => (&s[..idx], s[idx + 1..].parse::<i64>().ok()?),
+            None => (s, 0),
+        };
+        let (int_part, frac_part) = match mantissa.find('.') {
+            Some(idx) => (&mantissa[..idx], &mantissa[idx + 1..]),
+            None => (mantissa, ""),
+        };
+        if int_part.is_empty() && frac_part.is_empty() {
+            return None;
+        }
+        let digits = format!("{}{}", int_part, frac_part);
+        let mut coef = CalcBigInt::parse_digits(&digits)?;
+        coef.negative = negative && !coef.is_zero();
+        let exp = CalcDecimal::_check_exp(exp.checked_sub(frac_part.len() as i64))?;
+        CalcDecimal::new(coef, exp, false)._checked()
+    }
+    fn to_f64(&self) -> f64 {
+        format!("{}e{}", self.coef, self.exp)
+            .parse::<f64>()
+            .unwrap_or(f64::NAN)
+    }
+    fn to_i64(&self) -> Option<i64> {
+        if self.exp < 0 {
+            return None;
+        }
+        let exp = u32::try_from(self.exp).ok().filter(|exp| *exp <= 18)?;
+        self.coef.mul_pow10(exp).to_i64()
+    }
+    fn is_zero(&self) -> bool {
+        self.coef.is_zero()
+    }
+    fn is_negative(&self) -> bool {
+        self.coef.negative
+    }
+    fn neg(&self) -> CalcDecimal {
+        CalcDecimal::new(self.coef.neg(), self.exp, self.approximate)
+    }
+    fn abs(&self) -> CalcDecimal {
+        CalcDecimal::new(self.coef.abs(), self.exp, self.approximate)
+    }
+    fn compare(&self, other: &CalcDecimal) -> std::cmp::Ordering {
+        let sign = |value: &CalcDecimal| match (value.is_zero(), value.is_negative()) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        };
+        let (left_sign, right_sign) = (sign(self), sign(other));
+        if left_sign != right_sign || left_sign == 0 {
+            return left_sign.cmp(&right_sign);
+        }
+        // the one with the higher most significant digit is larger in magnitude; only align when they are the same
+        let magnitude = match self._adjusted_exp().cmp(&other._adjusted_exp()) {
+            std::cmp::Ordering::Equal => {
+                let (left, right, _) = CalcDecimal::_align(&self.abs(), &other.abs());
+                left.cmp(&right)
+            }
+            magnitude => magnitude,
+        };
+        if left_sign < 0 {
+            magnitude.reverse()
+        } else {
+            magnitude
+        }
+    }
+    /// none if the result is too large (or too small) to be represented
+    fn add(&self, other: &CalcDecimal, context: CalcDecimalContext) -> Option<CalcDecimal> {
+        let approximate = self.approximate || other.approximate;
+        if self.is_zero() || other.is_zero() {
+            let value = if self.is_zero() { other } else { self };
+            return CalcDecimal::new(value.coef.clone(), value.exp, approximate)
+                ._round(context, false)
+                ._checked();
+        }
+        let (high, low) = if self._adjusted_exp() >= other._adjusted_exp() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        // digits of low below the precision (plus guard digits) of the result only tell which way to round;
+        // so such low is replaced by a single (sticky) digit, to avoid aligning to a huge exponent
+        let sticky_exp = high
+            .exp
+            .min(high._adjusted_exp() - context.precision as i64 - 1)
+            - 1;
+        let low = if low._adjusted_exp() <= sticky_exp {
+            let one = CalcBigInt::from_u64(1);
+            let coef = if low.is_negative() { one.neg() } else { one };
+            CalcDecimal::new(coef, sticky_exp, low.approximate)
+        } else {
+            low.clone()
+        };
+        let (left, right, exp) = CalcDecimal::_align(high, &low);
+        CalcDecimal::new(left.add(&right), exp, approximate)
+            ._round(context, false)
+            ._checked()
+    }
+    fn sub(&self, other: &CalcDecimal, context: CalcDecimalContext) -> Option<CalcDecimal> {
+        self.add(&other.neg(), context)
+    }
+    /// none if the result is too large (or too small) to be represented
+    fn mul(&self, other: &CalcDecimal, context: CalcDecimalContext) -> Option<CalcDecimal> {
+        let approximate = self.approximate || other.approximate;
+        let exp = CalcDecimal::_check_exp(self.exp.checked_add(other.exp))?;
+        CalcDecimal::new(self.coef.mul(&other.coef), exp, approximate)
+            ._round(context, false)
+            ._checked()
+    }
+    fn div(&self, other: &CalcDecimal, context: CalcDecimalContext) -> Option<CalcDecimal> {
+        if other.is_zero() {
+            return None;
+        }
+        let approximate = self.approximate || other.approximate;
+        if self.is_zero() {
+            return Some(CalcDecimal::new(CalcBigInt::zero(), 0, approximate));
+        }
+        // scale the dividend so that the quotient has at least one more digit than the precision
+        let shift = (context.precision + 1 + other.coef.count_digits())
+            .saturating_sub(self.coef.count_digits()) as u32;
+        let (coef, rem) = self.coef.mul_pow10(shift).divmod(&other.coef)?;
+        let exp = self
+            .exp
+            .checked_sub(other.exp)
+            .and_then(|exp| exp.checked_sub(shift as i64));
+        let exp = CalcDecimal::_check_exp(exp)?;
+        CalcDecimal::new(coef, exp, approximate)
+            ._round(context, !rem.is_zero())
+            ._checked()
+    }
+    /// the remainder of the truncated division, with the sign of self; none if dividing by zero,
+    /// or if the exponents of the two are too far apart to be calculated exactly
+    fn rem(&self, other: &CalcDecimal, context: CalcDecimalContext) -> Option<CalcDecimal> {
+        if other.is_zero() {
+            return None;
+        }
+        let approximate = self.approximate || other.approximate;
+        if self.is_zero() || self._adjusted_exp() < other._adjusted_exp() {
+            let value = CalcDecimal::new(self.coef.clone(), self.exp, approximate);
+            return value._round(context, false)._checked(); // smaller than other
+        }
+        if self.exp.abs_diff(other.exp) > 10_000 {
+            return None;
+        }
+        let (left, right, exp) = CalcDecimal::_align(self, other);
+        let (_, rem) = left.divmod(&right)?;
+        CalcDecimal::new(rem, exp, approximate)
+            ._round(context, false)
+            ._checked()
+    }
+    /// raise to an integer power; none if the power is too large to be calculated exactly
+    fn pow(&self, power: i64, context: CalcDecimalContext) -> Option<CalcDecimal> {
+        if power.unsigned_abs() > 1_000_000 {
+            return None;
+        }
+        let work_context = CalcDecimalContext {
+            precision: context.precision + 10,
+            rounding: CalcRounding::HalfEven,
+        };
+        let mut result = CalcDecimal::from_i64(1);
+        let mut base = self.clone();
+        let mut n = power.unsigned_abs();
+        while n > 0 {
+            if n % 2 == 1 {
+                result = result.mul(&base, work_context)?;
+            }
+            n /= 2;
+            if n > 0 {
+                base = base.mul(&base, work_context)?;
+            }
+        }
+        if power < 0 {
+            CalcDecimal::from_i64(1).div(&result, context)
+        } else {
+            Some(result._round(context, false))
+        }
+    }
+    /// round to the given number of decimal places; none if the places are too many (or too few) to be represented
+    fn round_to(&self, places: i64, rounding: CalcRounding) -> Option<CalcDecimal> {
+        let exp = CalcDecimal::_check_exp(places.checked_neg())?;
+        if exp <= self.exp {
+            return Some(self.clone());
+        }
+        let drop = usize::try_from(exp - self.exp).ok()?;
+        self._round_digits(drop, rounding, false)._checked()
+    }
+    fn _round(self, context: CalcDecimalContext, sticky: bool) -> CalcDecimal {
+        let digits = self.coef.count_digits();
+        if digits <= context.precision {
+            return self;
+        }
+        self._round_digits(digits - context.precision, context.rounding, sticky)
+    }
+    /// drop the last `drop` digits of the coefficient, rounding the rest;
+    /// `sticky` tells that there are some non-zero digits after those dropped
+    fn _round_digits(&self, drop: usize, rounding: CalcRounding, sticky: bool) -> CalcDecimal {
+        // dropping more digits than the coefficient has is like dropping one more than it has
+        let divisor_digits = drop.min(self.coef.count_digits() + 1);
+        let divisor = CalcBigInt::from_u64(1).mul_pow10(divisor_digits as u32);
+        let (quotient, rem) = self.coef.abs().divmod(&divisor).unwrap();
+        let discarded = !rem.is_zero() || sticky;
+        let half_cmp = match rem.mul(&CalcBigInt::from_u64(2)).cmp(&divisor) {
+            std::cmp::Ordering::Equal if sticky => std::cmp::Ordering::Greater,
+            half_cmp => half_cmp,
+        };
+        let negative = self.coef.negative;
+        let round_up = match rounding {
+            CalcRounding::HalfUp => half_cmp != std::cmp::Ordering::Less,
+            CalcRounding::HalfDown => half_cmp == std::cmp::Ordering::Greater,
+            CalcRounding::HalfEven => {
+                half_cmp == std::cmp::Ordering::Greater
+                    || (half_cmp == std::cmp::Ordering::Equal && !quotient.is_even())
+            }
+            CalcRounding::Up => discarded,
+            CalcRounding::Down => false,
+            CalcRounding::Ceiling => discarded && !negative,
+            CalcRounding::Floor => discarded && negative,
+        };
+        let mut coef = if round_up {
+            quotient.add(&CalcBigInt::from_u64(1))
+        } else {
+            quotient
+        };
+        if negative {
+            coef = coef.neg();
+        }
+        let exp = self.exp.saturating_add(drop as i64);
+        CalcDecimal::new(coef, exp, self.approximate)
+    }
+    /// bring the two decimals to the same (smaller) exponent, returning the two coefficients and the exponent;
+    /// the exponents are expected to be not far apart
+    fn _align(left: &CalcDecimal, right: &CalcDecimal) -> (CalcBigInt, CalcBigInt, i64) {
+        let exp = left.exp.min(right.exp);
+        let left_coef = left.coef.mul_pow10((left.exp - exp) as u32);
+        let right_coef = right.coef.mul_pow10((right.exp - exp) as u32);
+        (left_coef, right_coef, exp)
+    }
+    /// the exponent of the most significant digit; e.g. 2 for 123
+    fn _adjusted_exp(&self) -> i64 {
+        self.exp + self.coef.count_digits() as i64 - 1
+    }
+    fn _check_exp(exp: Option<i64>) -> Option<i64> {
+        exp.filter(|exp| exp.abs() <= DECIMAL_EXP_LIMIT)
+    }
+    /// none if the exponent is out of the limit
+    fn _checked(self) -> Option<CalcDecimal> {
+        CalcDecimal::_check_exp(Some(self.exp))?;
+        Some(self)
+    }
 }
-impl Op {
-    fn get_priority(&self) -> OpPriority {
-        match self {
-            Op::ADD | Op::SUBTRACT => OpPriority::BINARY_AM,
-            Op::MULTIPLY | Op::DIVIDE => OpPriority::BINARY_MD,
-            Op::IMPLICIT => OpPriority::BINARY_IMP,
-            Op::TOPOW => OpPriority::BINARY_FN,
-            Op::NEGATE
-            | Op::SIN
-            | Op::COS
-            | Op::TAN
-            | Op::ASIN
-            | Op::ACOS
-            | Op::ATAN
-            | Op::LOG
-            | Op::LN
-            | Op::SQRT
-            | Op::SQUARE
-            | Op::POW10
-            | Op::INVERSE
-            | Op::EXP
-            //| Op::MOD
-            | Op::ABS
-            | Op::PERCENT => OpPriority::UNARY,
+impl fmt::Display for CalcDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.coef.abs().to_string();
+        let sign = if self.coef.negative { "-" } else { "" };
+        let zeros = if self.exp >= 0 {
+            self.exp.unsigned_abs()
+        } else {
+            self.exp.unsigned_abs().saturating_sub(digits.len() as u64)
+        };
+        if zeros > DECIMAL_DISPLAY_ZEROS as u64 {
+            let (first, rest) = digits.split_at(1);
+            let point = if rest.is_empty() { "" } else { "." };
+            return write!(
+                f,
+                "{}{}{}{}e{}",
+                sign,
+                first,
+                point,
+                rest,
+                self._adjusted_exp()
+            );
+        }
+        if self.exp >= 0 {
+            let zeros = "0".repeat(self.exp as usize);
+            write!(f, "{}{}{}", sign, digits, zeros)
+        } else {
+            let frac_len = (-self.exp) as usize;
+            if digits.len() > frac_len {
+                let (int_part, frac_part) = digits.split_at(digits.len() - frac_len);
+                write!(f, "{}{}.{}", sign, int_part, frac_part)
+            } else {
+                let zeros = "0".repeat(frac_len - digits.len());
+                write!(f, "{}0.{}{}", sign, zeros, digits)
+            }
         }
     }
-    fn get_order(&self) -> u8 {
-        self.get_priority() as u8
+}
+
+/// a rational is rounded to at most this many decimal places (either way), as the denominator grows with the places
+const RATIONAL_ROUND_PLACES_LIMIT: u64 = 10_000;
+
+/// an exact fraction, calculated in the "rational mode" -- see [`DumbCalcProcessor::use_rational_mode`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcRational {
+    numer: CalcBigInt, // carries the sign
+    denom: CalcBigInt, // always positive, and coprime with numer
+}
+impl CalcRational {
+    /// none if the denominator is zero
+    fn new(numer: CalcBigInt, denom: CalcBigInt) -> Option<CalcRational> {
+        if denom.is_zero() {
+            return None;
+        }
+        let gcd = numer.gcd(&denom);
+        let (mut numer, _) = numer.divmod(&gcd)?;
+        let (mut denom, _) = denom.divmod(&gcd)?;
+        if denom.negative {
+            numer = numer.neg();
+            denom = denom.neg();
+        }
+        Some(CalcRational { numer, denom })
     }
-    fn evaluate_binary(&self, left: f64, right: f64) -> f64 {
-        match *self {
-            Op::ADD => left + right,
-            Op::SUBTRACT => left - right,
-            Op::MULTIPLY => left * right,
-            Op::DIVIDE => left / right,
-            Op::IMPLICIT => left * right,
-            Op::TOPOW => left.powf(right),
-            _ => panic!("{:?} non-binary operator", self),
+    fn from_i64(value: i64) -> CalcRational {
+        CalcRational {
+            numer: CalcBigInt::from_i64(value),
+            denom: CalcBigInt::from_u64(1),
         }
     }
-    fn is_unary(&self) -> bool {
-        *self == Op::NEGATE
-            || *self == Op::COS
-            || *self == Op::SIN
-            || *self == Op::TAN
-            || *self == Op::ASIN
-            || *self == Op::ACOS
-            || *self == Op::ATAN
-            || *self == Op::LOG
-            || *self == Op::LN
-            || *self == Op::SQRT
-            || *self == Op::SQUARE
-            || *self == Op::POW10
-            || *self == Op::INVERSE
-            || *self == Op::EXP
-            //|| *self == Op::MOD
-            || *self == Op::ABS
-            || *self == Op::PERCENT
+    fn from_decimal(value: &CalcDecimal) -> CalcRational {
+        let one = CalcBigInt::from_u64(1);
+        if value.exp >= 0 {
+            let numer = value.coef.mul_pow10(value.exp as u32);
+            CalcRational::new(numer, one).unwrap()
+        } else {
+            let denom = one.mul_pow10((-value.exp) as u32);
+            CalcRational::new(value.coef.clone(), denom).unwrap()
+        }
     }
-    fn is_binary(&self) -> bool {
-        !self.is_unary()
+    /// take the shortest decimal representation of the value; e.g. 0.1 is 1/10
+    fn from_f64(value: f64) -> Option<CalcRational> {
+        CalcDecimal::from_f64(value, false).map(|value| CalcRational::from_decimal(&value))
     }
-    fn evaluate_unary(&self, operand: f64, angle_mode: AngleMode) -> f64 {
-        match *self {
-            Op::NEGATE => -operand,
-            Op::SIN => Op::_to_rad_angle(operand, angle_mode).sin(),
-            Op::COS => Op::_to_rad_angle(operand, angle_mode).cos(),
-            Op::TAN => Op::_to_rad_angle(operand, angle_mode).tan(),
-            Op::ASIN => Op::_from_rad_angle(operand.asin(), angle_mode),
-            Op::ACOS => Op::_from_rad_angle(operand.acos(), angle_mode),
-            Op::ATAN => Op::_from_rad_angle(operand.atan(), angle_mode),
-            Op::LOG => operand.log10(),
-            Op::LN => operand.ln(),
-            Op::SQRT => operand.sqrt(),
-            Op::SQUARE => operand * operand,
-            Op::POW10 => 10.0_f64.powf(operand),
-            Op::INVERSE => 1.0 / operand,
-            Op::EXP => operand.exp(),
-            //Op::MOD => operand % 1.0,
-            Op::ABS => operand.abs(),
-            Op::PERCENT => operand / 100.0,
-            _ => panic!("{:?} non-unary operator", self),
+    fn to_decimal(&self, context: CalcDecimalContext) -> CalcDecimal {
+        let numer = CalcDecimal::new(self.numer.clone(), 0, false);
+        let denom = CalcDecimal::new(self.denom.clone(), 0, false);
+        numer.div(&denom, context).unwrap()
+    }
+    fn to_f64(&self) -> f64 {
+        let context = CalcDecimalContext {
+            precision: 20,
+            rounding: CalcRounding::HalfEven,
+        };
+        self.to_decimal(context).to_f64()
+    }
+    fn to_i64(&self) -> Option<i64> {
+        if self.denom == CalcBigInt::from_u64(1) {
+            self.numer.to_i64()
+        } else {
+            None
         }
     }
-    fn _to_rad_angle(operand: f64, angle_mode: AngleMode) -> f64 {
-        if angle_mode == AngleMode::DEGREE {
-            operand.to_radians()
+    /// like "2 1/3", for a fraction with the numerator larger than the denominator
+    fn to_mixed_string(&self) -> String {
+        let (whole, rem) = self.numer.divmod(&self.denom).unwrap();
+        if whole.is_zero() || rem.is_zero() {
+            return self.to_string();
+        }
+        format!("{} {}/{}", whole, rem.abs(), self.denom)
+    }
+    fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+    fn neg(&self) -> CalcRational {
+        CalcRational {
+            numer: self.numer.neg(),
+            denom: self.denom.clone(),
+        }
+    }
+    fn abs(&self) -> CalcRational {
+        CalcRational {
+            numer: self.numer.abs(),
+            denom: self.denom.clone(),
+        }
+    }
+    /// round towards zero to an integer
+    fn trunc(&self) -> CalcRational {
+        let (whole, _) = self.numer.divmod(&self.denom).unwrap();
+        CalcRational::new(whole, CalcBigInt::from_u64(1)).unwrap()
+    }
+    fn compare(&self, other: &CalcRational) -> std::cmp::Ordering {
+        self.numer
+            .mul(&other.denom)
+            .cmp(&other.numer.mul(&self.denom))
+    }
+    fn add(&self, other: &CalcRational) -> CalcRational {
+        let numer = self
+            .numer
+            .mul(&other.denom)
+            .add(&other.numer.mul(&self.denom));
+        CalcRational::new(numer, self.denom.mul(&other.denom)).unwrap()
+    }
+    fn sub(&self, other: &CalcRational) -> CalcRational {
+        self.add(&other.neg())
+    }
+    fn mul(&self, other: &CalcRational) -> CalcRational {
+        let numer = self.numer.mul(&other.numer);
+        CalcRational::new(numer, self.denom.mul(&other.denom)).unwrap()
+    }
+    /// none if dividing by zero
+    fn div(&self, other: &CalcRational) -> Option<CalcRational> {
+        let numer = self.numer.mul(&other.denom);
+        CalcRational::new(numer, self.denom.mul(&other.numer))
+    }
+    /// the remainder of the truncated division, with the sign of self; none if dividing by zero
+    fn rem(&self, other: &CalcRational) -> Option<CalcRational> {
+        let quotient = self.div(other)?.trunc();
+        Some(self.sub(&quotient.mul(other)))
+    }
+    /// raise to an integer power; none if the power is too large, or if raising zero to a negative power
+    fn pow(&self, power: i64) -> Option<CalcRational> {
+        if power.unsigned_abs() > 10_000 {
+            return None;
+        }
+        let mut result = CalcRational::from_i64(1);
+        let mut base = self.clone();
+        let mut n = power.unsigned_abs();
+        while n > 0 {
+            if n % 2 == 1 {
+                result = result.mul(&base);
+            }
+            n /= 2;
+            if n > 0 {
+                base = base.mul(&base);
+            }
+        }
+        if power < 0 {
+            CalcRational::from_i64(1).div(&result)
         } else {
-            operand
+            Some(result)
         }
     }
-    fn _from_rad_angle(operand: f64, angle_mode: AngleMode) -> f64 {
-        if angle_mode == AngleMode::DEGREE {
-            operand.to_degrees()
+    /// round to the given number of decimal places, half away from zero; negative places round to tens, hundreds, etc.;
+    /// none if the places are too many
+    fn round_to(&self, places: i64) -> Option<CalcRational> {
+        let scale_places =
+            Some(places.unsigned_abs()).filter(|places| *places <= RATIONAL_ROUND_PLACES_LIMIT)?;
+        let scale = CalcBigInt::from_u64(1).mul_pow10(scale_places as u32);
+        let (scaled, denom) = if places >= 0 {
+            (self.numer.abs().mul(&scale), self.denom.clone())
         } else {
-            operand
+            (self.numer.abs(), self.denom.mul(&scale))
+        };
+        let (quotient, rem) = scaled.divmod(&denom)?;
+        let round_up = rem.mul(&CalcBigInt::from_u64(2)) >= denom;
+        let mut numer = if round_up {
+            quotient.add(&CalcBigInt::from_u64(1))
+        } else {
+            quotient
+        };
+        if self.numer.negative {
+            numer = numer.neg();
+        }
+        if places >= 0 {
+            CalcRational::new(numer, scale)
+        } else {
+            CalcRational::new(numer.mul(&scale), CalcBigInt::from_u64(1))
         }
     }
 }
-impl fmt::Display for Op {
+impl fmt::Display for CalcRational {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            Op::ADD => write!(f, "+"),
-            Op::SUBTRACT => write!(f, "-"),
-            Op::MULTIPLY => write!(f, "*"),
-            Op::DIVIDE => write!(f, "/"),
-            Op::IMPLICIT => write!(f, "_imp_"),
-            Op::TOPOW => write!(f, "^"),
-            Op::NEGATE => write!(f, "neg"),
-            Op::SIN => write!(f, "sin"),
-            Op::COS => write!(f, "cos"),
-            Op::TAN => write!(f, "tan"),
-            Op::ASIN => write!(f, "asin"),
-            Op::ACOS => write!(f, "acos"),
-            Op::ATAN => write!(f, "atan"),
-            Op::LOG => write!(f, "log"),
-            Op::LN => write!(f, "ln"),
-            Op::SQRT => write!(f, "sqrt"),
-            Op::SQUARE => write!(f, "square"),
-            Op::POW10 => write!(f, "pow10"),
-            Op::INVERSE => write!(f, "inv"),
-            Op::EXP => write!(f, "exp"),
-            //Op::MOD => write!(f, "mod"),
-            Op::ABS => write!(f, "abs"),
-            Op::PERCENT => write!(f, "%"),
+        if self.denom == CalcBigInt::from_u64(1) {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
         }
     }
 }
 
-#[derive(PartialEq, Copy, Clone, Debug)]
-enum Unit {
-    OpenBracket,
-    CloseBracket,
-    Operand(f64),
-    Operator(Op),
+const BIG_INT_LIMB_BASE: u64 = 1_000_000_000;
+const BIG_INT_LIMB_DIGITS: usize = 9;
+
+/// an arbitrary-precision integer, stored as base 10^9 "limbs", least significant first
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CalcBigInt {
+    negative: bool,
+    limbs: Vec<u32>, // no leading zero limbs; zero has no limbs at all
 }
-// impl Unit {
-//     fn operator(op: Op) -> Unit {
-//         Unit::Operator(op)
-//     }
-//     fn operand(operand: f64) -> Unit {
-//         Unit::Operand(operand)
-//     }
-// }
-impl fmt::Display for Unit {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            Unit::OpenBracket => write!(f, "("),
-            Unit::CloseBracket => write!(f, ")"),
-            Unit::Operand(operand) => write!(f, "{}", operand),
-            Unit::Operator(operator) => write!(f, "{}", operator),
+impl CalcBigInt {
+    fn zero() -> CalcBigInt {
+        CalcBigInt {
+            negative: false,
+            limbs: Vec::new(),
+        }
+    }
+    fn from_u64(value: u64) -> CalcBigInt {
+        let mut limbs = Vec::new();
+        let mut value = value;
+        while value > 0 {
+            limbs.push((value % BIG_INT_LIMB_BASE) as u32);
+            value /= BIG_INT_LIMB_BASE;
+        }
+        CalcBigInt {
+            negative: false,
+            limbs,
+        }
+    }
+    fn from_i64(value: i64) -> CalcBigInt {
+        let mut big_int = CalcBigInt::from_u64(value.unsigned_abs());
+        big_int.negative = value < 0;
+        big_int
+    }
+    /// parse a non-empty string of decimal digits
+    fn parse_digits(digits: &str) -> Option<CalcBigInt> {
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut limbs = Vec::new();
+        let mut end = digits.len();
+        while end > 0 {
+            let start = end.saturating_sub(BIG_INT_LIMB_DIGITS);
+            limbs.push(digits[start..end].parse::<u32>().unwrap());
+            end = start;
+        }
+        Some(CalcBigInt::_new(false, limbs))
+    }
+    fn _new(negative: bool, limbs: Vec<u32>) -> CalcBigInt {
+        let mut limbs = limbs;
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        let negative = negative && !limbs.is_empty();
+        CalcBigInt { negative, limbs }
+    }
+    fn to_i64(&self) -> Option<i64> {
+        let mut value: i64 = 0;
+        for limb in self.limbs.iter().rev() {
+            value = value
+                .checked_mul(BIG_INT_LIMB_BASE as i64)?
+                .checked_add(*limb as i64)?;
+        }
+        Some(if self.negative { -value } else { value })
+    }
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+    fn is_even(&self) -> bool {
+        self.limbs.first().is_none_or(|limb| limb % 2 == 0)
+    }
+    /// the number of decimal digits; 0 for zero
+    fn count_digits(&self) -> usize {
+        match self.limbs.last() {
+            Some(last) => (self.limbs.len() - 1) * BIG_INT_LIMB_DIGITS + last.to_string().len(),
+            None => 0,
+        }
+    }
+    fn neg(&self) -> CalcBigInt {
+        CalcBigInt::_new(!self.negative, self.limbs.clone())
+    }
+    fn abs(&self) -> CalcBigInt {
+        CalcBigInt::_new(false, self.limbs.clone())
+    }
+    fn add(&self, other: &CalcBigInt) -> CalcBigInt {
+        if self.negative == other.negative {
+            return CalcBigInt::_new(self.negative, Self::_add_abs(&self.limbs, &other.limbs));
+        }
+        match Self::_cmp_abs(&self.limbs, &other.limbs) {
+            std::cmp::Ordering::Less => {
+                CalcBigInt::_new(other.negative, Self::_sub_abs(&other.limbs, &self.limbs))
+            }
+            _ => CalcBigInt::_new(self.negative, Self::_sub_abs(&self.limbs, &other.limbs)),
+        }
+    }
+    fn sub(&self, other: &CalcBigInt) -> CalcBigInt {
+        self.add(&other.neg())
+    }
+    fn mul(&self, other: &CalcBigInt) -> CalcBigInt {
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let cur = limbs[i + j] + (*a as u64) * (*b as u64) + carry;
+                limbs[i + j] = cur % BIG_INT_LIMB_BASE;
+                carry = cur / BIG_INT_LIMB_BASE;
+            }
+            let mut k = i + other.limbs.len();
+            while carry > 0 {
+                let cur = limbs[k] + carry;
+                limbs[k] = cur % BIG_INT_LIMB_BASE;
+                carry = cur / BIG_INT_LIMB_BASE;
+                k += 1;
+            }
+        }
+        let limbs = limbs.into_iter().map(|limb| limb as u32).collect();
+        CalcBigInt::_new(self.negative != other.negative, limbs)
+    }
+    fn mul_pow10(&self, n: u32) -> CalcBigInt {
+        if self.is_zero() || n == 0 {
+            return self.clone();
+        }
+        let n = n as usize;
+        let mut limbs = vec![0u32; n / BIG_INT_LIMB_DIGITS];
+        limbs.extend_from_slice(&self.limbs);
+        let shifted = CalcBigInt::_new(self.negative, limbs);
+        let rest = 10u64.pow((n % BIG_INT_LIMB_DIGITS) as u32);
+        shifted.mul(&CalcBigInt::from_u64(rest))
+    }
+    /// the truncated quotient and the remainder (with the sign of self); none if dividing by zero
+    fn divmod(&self, other: &CalcBigInt) -> Option<(CalcBigInt, CalcBigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut rem: Vec<u32> = Vec::new();
+        for i in (0..self.limbs.len()).rev() {
+            rem.insert(0, self.limbs[i]);
+            while rem.last() == Some(&0) {
+                rem.pop();
+            }
+            // binary search the largest digit q such that other * q <= rem
+            let (mut low, mut high) = (0u64, BIG_INT_LIMB_BASE - 1);
+            while low < high {
+                let mid = (low + high).div_ceil(2);
+                let product = Self::_mul_small_abs(&other.limbs, mid as u32);
+                if Self::_cmp_abs(&product, &rem) == std::cmp::Ordering::Greater {
+                    high = mid - 1;
+                } else {
+                    low = mid;
+                }
+            }
+            if low > 0 {
+                let product = Self::_mul_small_abs(&other.limbs, low as u32);
+                rem = Self::_sub_abs(&rem, &product);
+            }
+            quotient[i] = low as u32;
+        }
+        let quotient = CalcBigInt::_new(self.negative != other.negative, quotient);
+        let rem = CalcBigInt::_new(self.negative, rem);
+        Some((quotient, rem))
+    }
+    /// the (non-negative) greatest common divisor; 1 if both are zero
+    fn gcd(&self, other: &CalcBigInt) -> CalcBigInt {
+        let mut a = self.abs();
+        let mut b = other.abs();
+        while !b.is_zero() {
+            let (_, rem) = a.divmod(&b).unwrap();
+            a = b;
+            b = rem;
+        }
+        if a.is_zero() {
+            CalcBigInt::from_u64(1)
+        } else {
+            a
+        }
+    }
+    fn _cmp_abs(left: &[u32], right: &[u32]) -> std::cmp::Ordering {
+        left.len()
+            .cmp(&right.len())
+            .then_with(|| left.iter().rev().cmp(right.iter().rev()))
+    }
+    fn _add_abs(left: &[u32], right: &[u32]) -> Vec<u32> {
+        let mut limbs = Vec::with_capacity(left.len().max(right.len()) + 1);
+        let mut carry = 0u64;
+        for i in 0..left.len().max(right.len()) {
+            let cur =
+                *left.get(i).unwrap_or(&0) as u64 + *right.get(i).unwrap_or(&0) as u64 + carry;
+            limbs.push((cur % BIG_INT_LIMB_BASE) as u32);
+            carry = cur / BIG_INT_LIMB_BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+        limbs
+    }
+    /// left - right, where left >= right
+    fn _sub_abs(left: &[u32], right: &[u32]) -> Vec<u32> {
+        let mut limbs = Vec::with_capacity(left.len());
+        let mut borrow = 0i64;
+        for (i, limb) in left.iter().enumerate() {
+            let mut cur = *limb as i64 - *right.get(i).unwrap_or(&0) as i64 - borrow;
+            borrow = if cur < 0 {
+                cur += BIG_INT_LIMB_BASE as i64;
+                1
+            } else {
+                0
+            };
+            limbs.push(cur as u32);
+        }
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        limbs
+    }
+    fn _mul_small_abs(limbs: &[u32], factor: u32) -> Vec<u32> {
+        let mut result = Vec::with_capacity(limbs.len() + 1);
+        let mut carry = 0u64;
+        for limb in limbs {
+            let cur = *limb as u64 * factor as u64 + carry;
+            result.push((cur % BIG_INT_LIMB_BASE) as u32);
+            carry = cur / BIG_INT_LIMB_BASE;
+        }
+        if carry > 0 {
+            result.push(carry as u32);
         }
+        while result.last() == Some(&0) {
+            result.pop();
+        }
+        result
     }
 }
-
-fn print_infix(infix: &[Unit]) {
-    let len = infix.len();
-    print!("[");
-    for (i, unit) in infix.iter().enumerate() {
-        if i > 0 {
-            print!(", ");
+impl PartialOrd for CalcBigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for CalcBigInt {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => std::cmp::Ordering::Greater,
+            (true, false) => std::cmp::Ordering::Less,
+            (false, false) => Self::_cmp_abs(&self.limbs, &other.limbs),
+            (true, true) => Self::_cmp_abs(&other.limbs, &self.limbs),
+        }
+    }
+}
+impl fmt::Display for CalcBigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        match self.limbs.last() {
+            Some(last) => {
+                write!(f, "{}", last)?;
+                for limb in self.limbs.iter().rev().skip(1) {
+                    write!(f, "{:09}", limb)?;
+                }
+                Ok(())
+            }
+            None => write!(f, "0"),
         }
-        print!("{}", unit);
     }
-    // for i in 0..len {
-    //     if i > 0 {
-    //         print!(", ");
-    //     }
-    //     let unit = infix[i];
-    //     print!("{}", unit);
-    // }
-    println!("]");
 }
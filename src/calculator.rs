@@ -11,7 +11,7 @@ use core::panic;
 use std::{error::Error, ops::Index};
 
 use crate::{
-    calc::{self, CalcProcessorBackup, CalcResult, DumbCalcProcessor},
+    calc::{self, CalcProcessorBackup, CalcResult, CalcValue, DumbCalcProcessor},
     shared::DumbError,
 };
 
@@ -24,6 +24,7 @@ pub struct DumbCalculator {
     undo_stack: Option<Vec<UndoStep>>,
     history_stack: Option<Vec<String>>,
     memory: Option<f64>,
+    programmer_base: Option<u32>, // the base of the digits, in the "programmer mode"
 }
 
 /// A simple calculator that accepts input keys acting like a real calculator;
@@ -78,6 +79,7 @@ impl DumbCalculator {
             undo_stack: undo_stack,
             history_stack: history_stack,
             memory: None,
+            programmer_base: None,
         }
     }
     /// push a "key input":
@@ -92,14 +94,22 @@ impl DumbCalculator {
     /// * "ac": clear the calculator
     /// * "undo": undo the last "key input" done by [`DumbCalculator::push`], if undo is enabled
     /// * "mc", "mr", "ms", "m+", "m-": memory keys
+    ///
+    /// in the "programmer mode" (see [`DumbCalculator::use_programmer_mode`]):
+    /// * "A" to "F" are also digits, and a digit should be less than the current base
+    /// * "hex", "dec", "oct", "bin": switch the base of the digits to enter and display
+    /// * "." is not acceptable
     pub fn push(&mut self, key: &str) -> Result<(), DumbError> {
+        if let Some(base) = self.programmer_base {
+            return self._push_programmer_key(key, base);
+        }
         if key == "." {
             self._record_undo(key, false);
             self.entering = match &self.entering {
                 EnteringMode::Not => EnteringMode::Decimal(0, String::from("")),
                 EnteringMode::Integer(i) => EnteringMode::Decimal(*i, String::from("")),
                 EnteringMode::Decimal(i, d) => EnteringMode::Decimal(*i, d.clone()),
-                EnteringMode::Error => EnteringMode::Error,
+                EnteringMode::Error | EnteringMode::Programmer(_) => EnteringMode::Error,
             }
         } else if key >= "0" && key <= "9" {
             self._record_undo(key, false);
@@ -126,7 +136,7 @@ impl DumbCalculator {
                         EnteringMode::Decimal(*i, new_d)
                     }
                 }
-                EnteringMode::Error => EnteringMode::Error,
+                EnteringMode::Error | EnteringMode::Programmer(_) => EnteringMode::Error,
             }
         } else {
             if key == "ac" {
@@ -158,6 +168,10 @@ impl DumbCalculator {
                     self.calc.push(num.as_str()).unwrap();
                     EnteringMode::Not
                 }
+                EnteringMode::Programmer(i) => {
+                    self.calc.push(format!("0x{:X}", i).as_str()).unwrap();
+                    EnteringMode::Not
+                }
                 EnteringMode::Error => EnteringMode::Error,
             };
             match memory_key {
@@ -171,6 +185,57 @@ impl DumbCalculator {
         }
         Ok(())
     }
+    fn _push_programmer_key(&mut self, key: &str, base: u32) -> Result<(), DumbError> {
+        let new_base = match key {
+            "hex" => Some(16),
+            "dec" => Some(10),
+            "oct" => Some(8),
+            "bin" => Some(2),
+            _ => None,
+        };
+        if let Some(new_base) = new_base {
+            self.programmer_base = Some(new_base);
+            self.calc.use_display_base(new_base);
+            return Ok(());
+        }
+        if key == "." {
+            return Err("'.' is not acceptable in the programmer mode".into());
+        }
+        let digit = match key {
+            "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" | "A" | "B" | "C" | "D"
+            | "E" | "F" => key.chars().next().and_then(|c| c.to_digit(16)),
+            _ => None,
+        };
+        match digit {
+            Some(digit) if digit >= base => {
+                let err_msg = format!("'{}' is not a valid digit of base {}", key, base);
+                Err(err_msg.into())
+            }
+            Some(digit) => {
+                self._record_undo(key, false);
+                self.entering = match &self.entering {
+                    EnteringMode::Not => EnteringMode::Programmer(digit as u64),
+                    EnteringMode::Programmer(i) => {
+                        match i
+                            .checked_mul(base as u64)
+                            .and_then(|x| x.checked_add(digit as u64))
+                        {
+                            Some(new_i) => EnteringMode::Programmer(new_i),
+                            None => EnteringMode::Error,
+                        }
+                    }
+                    _ => EnteringMode::Error,
+                };
+                Ok(())
+            }
+            None => {
+                self.programmer_base = None; // so that the key will be handled in the usual way
+                let result = self.push(key);
+                self.programmer_base = Some(base);
+                result
+            }
+        }
+    }
     /// like [`DumbCalculator::push`] but each characters of the input will be pushed individually one by one
     pub fn push_chars(&mut self, keys: &str) -> Result<(), DumbError> {
         for key in keys.chars() {
@@ -228,6 +293,33 @@ impl DumbCalculator {
     pub fn use_angle_mode(&mut self, angle_mode: &str) {
         self.calc.use_angle_mode(angle_mode);
     }
+    /// use the "programmer mode" of [`crate::calc::DumbCalcProcessor::use_programmer_mode`], in which the digits are entered and displayed in the current base;
+    /// the base is 10 initially, and can be switched with the keys "hex", "dec", "oct" and "bin"
+    /// * word_size: 8, 16, 32 or 64
+    /// * signed: whether the words are signed or unsigned
+    ///
+    /// e.g.
+    /// ```
+    /// use rusty_dumb_tools::calculator::DumbCalculator;
+    /// let mut calculator = DumbCalculator::new();
+    /// calculator.use_programmer_mode(16, false);
+    /// calculator.push("hex").unwrap();
+    /// calculator.push_chars("FF+1=").unwrap();
+    /// assert_eq!(calculator.get_display(), "100");
+    /// calculator.push("bin").unwrap();
+    /// assert_eq!(calculator.get_display(), "100000000");
+    /// ```
+    pub fn use_programmer_mode(&mut self, word_size: u32, signed: bool) {
+        self.calc.use_programmer_mode(word_size, signed);
+        self.calc.use_display_base(10);
+        self.programmer_base = Some(10);
+    }
+    /// use the default "float mode" of [`crate::calc::DumbCalcProcessor::use_float_mode`]
+    pub fn use_float_mode(&mut self) {
+        self.calc.use_float_mode();
+        self.calc.use_display_base(10);
+        self.programmer_base = None;
+    }
     /// clear the calculator (but will not clear the memory)
     pub fn clear(&mut self) {
         self.entering = EnteringMode::Not;
@@ -369,7 +461,7 @@ impl DumbCalculator {
                             "*" => "×",
                             "/" => "÷",
                             "PI" => "π",
-                            "E" => "e",
+                            "E" if self.programmer_base.is_none() => "e",
                             _ => h,
                         }
                     } else {
@@ -479,8 +571,10 @@ impl DumbCalculator {
         let (mut display_result, result) = match &self.entering {
             EnteringMode::Not => {
                 let result = match self.calc.get_result() {
-                    CalcResult::Final(r) => r,
-                    CalcResult::Intermediate(r) => r,
+                    CalcResult::Final(CalcValue::Integer(r))
+                    | CalcResult::Intermediate(CalcValue::Integer(r)) => return r.to_digits(),
+                    CalcResult::Final(r) => r.to_f64(),
+                    CalcResult::Intermediate(r) => r.to_f64(),
                     CalcResult::Error(e) => return String::from("Error"),
                 };
                 let display_result = format!("{}", result);
@@ -510,6 +604,14 @@ impl DumbCalculator {
                     (display_result, result)
                 }
             }
+            EnteringMode::Programmer(i) => {
+                return match self.programmer_base {
+                    Some(16) => format!("{:X}", i),
+                    Some(8) => format!("{:o}", i),
+                    Some(2) => format!("{:b}", i),
+                    _ => i.to_string(),
+                };
+            }
             EnteringMode::Error => {
                 return String::from("Error");
             }
@@ -620,8 +722,8 @@ impl DumbCalculator {
             return Ok(());
         }
         let result = match self.calc.get_result() {
-            CalcResult::Final(r) => r,
-            CalcResult::Intermediate(r) => r,
+            CalcResult::Final(r) => r.to_f64(),
+            CalcResult::Intermediate(r) => r.to_f64(),
             CalcResult::Error(e) => return Ok(()),
         };
         let new_memory = match key {
@@ -671,4 +773,5 @@ enum EnteringMode {
     Error,
     Integer(u32),
     Decimal(u32, String),
+    Programmer(u64), // the integer entered in the "programmer mode"
 }
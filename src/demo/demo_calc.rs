@@ -46,6 +46,16 @@ pub fn handle_demo_calc_repl() {
     println!("* can split the infix expression into multiple lines; e.g. a \"unit\" a line");
     println!("* finally, enter \"=\" (or an empty line) to evaluate it");
     println!("* can then continue to enter another infix expression ...");
+    println!("* can assign to a variable like \"x = 3 * 4\", and use it later; \"ans\" is the last result");
+    println!("* can call functions like \"max(1, 2)\", \"atan2(y, x)\", \"round(x, 2)\" and \"hypot(3, 4)\"");
+    println!("* can write unary operators before the operand, like \"sqrt(16) + sin 30\"");
+    println!("* enter \"vars\" to list the variables");
+    println!(
+        "* enter \"decimal\", \"rational\", \"programmer\", \"complex\" or \"float\" to switch the number mode"
+    );
+    println!("* in the programmer mode, enter \"hex\", \"dec\", \"oct\" or \"bin\" to switch the display base");
+    println!("* in the complex mode, enter \"polar\" or \"rect\" to switch the display form");
+    println!("* enter \"units\" (or \"nounits\") to enable (or disable) the units, like \"5 km / 20 min in km/h\"");
     println!();
     let mut calc = calc::DumbCalcProcessor::new();
     let mut units = String::new();
@@ -70,6 +80,53 @@ pub fn handle_demo_calc_repl() {
             continue;
         }
 
+        if unit.to_lowercase() == "decimal" {
+            calc.use_decimal_mode(28, calc::CalcRounding::HalfEven);
+            continue;
+        }
+        if unit.to_lowercase() == "rational" {
+            calc.use_rational_mode();
+            continue;
+        }
+        if unit.to_lowercase() == "programmer" {
+            calc.use_programmer_mode(64, true);
+            continue;
+        }
+        let base = match unit.to_lowercase().as_str() {
+            "hex" => Some(16),
+            "dec" => Some(10),
+            "oct" => Some(8),
+            "bin" => Some(2),
+            _ => None,
+        };
+        if let Some(base) = base {
+            calc.use_display_base(base);
+            continue;
+        }
+        if unit.to_lowercase() == "float" {
+            calc.use_float_mode();
+            continue;
+        }
+        if unit.to_lowercase() == "complex" {
+            calc.use_complex_mode();
+            continue;
+        }
+        if unit.to_lowercase() == "polar" || unit.to_lowercase() == "rect" {
+            calc.use_polar_display(unit.to_lowercase() == "polar");
+            continue;
+        }
+        if unit.to_lowercase() == "units" || unit.to_lowercase() == "nounits" {
+            calc.use_units(unit.to_lowercase() == "units");
+            continue;
+        }
+
+        if unit.to_lowercase() == "vars" {
+            for (name, value) in calc.get_variables() {
+                println!("| {} = {}", name, value);
+            }
+            continue;
+        }
+
         if unit == "=" {
             calc.evaluate()
         } else {
@@ -77,6 +134,12 @@ pub fn handle_demo_calc_repl() {
             match push_res {
                 Ok(_) => {}
                 Err(e) => {
+                    if let Some((start, end)) = e.span() {
+                        // mark the offending unit under the input
+                        let indent = 2 + units.chars().count() + input.chars().count()
+                            - input.trim_start().chars().count();
+                        println!("{}{}", " ".repeat(indent + start), "^".repeat(end - start));
+                    }
                     println!("| Error: {}", e);
                     continue;
                 }
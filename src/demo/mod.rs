@@ -15,6 +15,8 @@ pub mod demo_progress;
 #[cfg(test)]
 pub mod test_arg;
 #[cfg(test)]
+pub mod test_arg_prop;
+#[cfg(test)]
 pub mod test_calc;
 #[cfg(test)]
 pub mod test_calculator;
@@ -373,3 +373,1058 @@ fn test_arg__() {
         parser.get_multi::<String>("str").unwrap()
     );
 }
+#[test]
+fn test_count_arg() {
+    println!("*** COUNT ARGUMENT ***");
+    let mut parser = DumbArgParser::new_with_name("pgm");
+    dap_arg!("-v", flag2 = "--verbose")
+        .count()
+        .add_to(&mut parser);
+    dap_arg!("-q").count().add_to(&mut parser);
+    parser.process_args(vec![]);
+    assert_eq!(0, parser.get::<i32>("-v").unwrap());
+    parser.process_args(vec!["-vvv", "--verbose", "-q"]);
+    assert_eq!(4, parser.get::<i32>("--verbose").unwrap());
+    assert_eq!(1, parser.get::<i32>("-q").unwrap());
+    parser.process_args(vec!["-vqv"]);
+    assert_eq!(2, parser.get::<i32>("-v").unwrap());
+    assert_eq!(1, parser.get::<i32>("-q").unwrap());
+    assert_eq!("pgm [-h] [-v ...] [-q ...]", parser.compose_usage());
+    assert_eq!("-v -v -q", parser.compose_inputs());
+    let process_res = parser.check_process_args(vec!["-vx"], true);
+    assert_eq!(
+        "unknown input argument [-vx]",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_append_arg() {
+    println!("*** APPEND ARGUMENT ***");
+    let mut parser = DumbArgParser::new_with_name("pgm");
+    dap_arg!("-I", flag2 = "--include")
+        .append("path")
+        .add_to(&mut parser);
+    dap_arg!("-n").append(0).set_range(1, 9).add_to(&mut parser);
+    parser.process_args(vec![]);
+    assert!(parser.get_multi::<String>("-I").unwrap().is_empty());
+    assert!(parser.get::<String>("-I").is_none());
+    parser.process_args(vec!["-I", "p1", "-n", "3", "--include", "p2", "-n", "5"]);
+    assert_eq!(vec!["p1", "p2"], parser.get_multi::<String>("-I").unwrap());
+    assert_eq!(vec![3, 5], parser.get_multi::<i32>("-n").unwrap());
+    assert_eq!("p2", parser.get::<String>("--include").unwrap());
+    assert_eq!(5, parser.get::<i32>("-n").unwrap());
+    assert_eq!(
+        "pgm [-h] [-I include ...] [-n n ...]",
+        parser.compose_usage()
+    );
+    assert_eq!("-I p1 -I p2 -n 3 -n 5", parser.compose_inputs());
+    let process_res = parser.check_process_args(vec!["-n", "3", "-n", "10"], true);
+    assert_eq!(
+        "[10] is out of range [1, 9]",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_negatable_arg() {
+    println!("*** NEGATABLE ARGUMENT ***");
+    let mut parser = DumbArgParser::new_with_name("pgm");
+    dap_arg!("-c", flag2 = "--color")
+        .negatable(true)
+        .add_to(&mut parser);
+    parser.process_args(vec![]);
+    assert!(parser.get::<bool>("--color").unwrap());
+    parser.process_args(vec!["--no-color"]);
+    assert!(!parser.get::<bool>("-c").unwrap());
+    assert_eq!("--no-color", parser.compose_inputs());
+    parser.process_args(vec!["--no-color", "-c"]);
+    assert!(parser.get::<bool>("--color").unwrap());
+    assert_eq!("--color", parser.compose_inputs());
+    assert_eq!("pgm [-h] [--[no-]color]", parser.compose_usage());
+    let process_res = parser.check_process_args(vec!["--no-c"], true);
+    assert_eq!(
+        "unknown input argument [--no-c]",
+        process_res.unwrap_err().to_string()
+    );
+    let mut parser = DumbArgParser::new();
+    assert!(dap_arg!("-c").negatable(false).add_to(&mut parser).is_err());
+    assert!(dap_arg!("count").count().add_to(&mut parser).is_err());
+}
+#[test]
+fn test_unsigned_arg_types() {
+    println!("*** UNSIGNED ARGUMENT TYPES ***");
+    let mut parser = DumbArgParser::new();
+    dap_arg!("u32", value = 0_u32).add_to(&mut parser);
+    dap_arg!("u64", value = 0_u64).add_to(&mut parser);
+    dap_arg!("usize", value = 0_usize)
+        .set_range(1_usize, 10_usize)
+        .add_to(&mut parser);
+    dap_arg!("-e", default = 1_u32)
+        .set_enums(vec![1_u32, 2, 4])
+        .add_to(&mut parser);
+    parser.process_args(vec!["1", "18446744073709551615", "5", "-e", "4"]);
+    assert_eq!(1, parser.get::<u32>("u32").unwrap());
+    assert_eq!(1, parser.get::<u64>("u32").unwrap()); // u32 => u64
+    assert_eq!(u64::MAX, parser.get::<u64>("u64").unwrap());
+    assert_eq!(5, parser.get::<usize>("usize").unwrap());
+    assert_eq!(4, parser.get::<u32>("-e").unwrap());
+    assert_eq!("5", parser.get::<String>("usize").unwrap());
+    let process_res = parser.check_process_args(vec!["-1", "1", "1"], true);
+    assert_eq!(
+        "unknown input argument [-1]",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["1", "1", "11"], true);
+    assert_eq!(
+        "[11] is out of range [1, 10]",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["1", "1", "2", "-e", "3"], true);
+    assert_eq!(
+        "[3] doesn't match any of the enum values [1, 2, 4]",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_path_arg() {
+    println!("*** PATH ARGUMENT ***");
+    use std::path::PathBuf;
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-o", value = PathBuf::from("out.txt")).add_to(&mut parser);
+    dap_arg!("in", value = PathBuf::new())
+        .set_must_exist()
+        .add_to(&mut parser);
+    parser.process_args(vec!["-o", "some/where.txt", "Cargo.toml"]);
+    assert_eq!(
+        PathBuf::from("some/where.txt"),
+        parser.get::<PathBuf>("-o").unwrap()
+    );
+    assert_eq!(
+        PathBuf::from("Cargo.toml"),
+        parser.get::<PathBuf>("in").unwrap()
+    );
+    assert_eq!("Cargo.toml", parser.get::<String>("in").unwrap());
+    let process_res = parser.check_process_args(vec!["-o", "x", "no-such-file.txt"], true);
+    assert_eq!(
+        "path \"no-such-file.txt\" does not exist",
+        process_res.unwrap_err().to_string()
+    );
+    let add_res = dap_arg!("-n", value = 0)
+        .set_must_exist()
+        .add_to(&mut parser);
+    assert_eq!(
+        "argument [-n] must be a path to be must-exist",
+        add_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_duration_arg() {
+    println!("*** DURATION ARGUMENT ***");
+    use std::time::Duration;
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-t", default = Duration::from_secs(5))
+        .set_range(Duration::from_millis(100), Duration::from_secs(3600))
+        .add_to(&mut parser);
+    parser.process_args(vec![]);
+    assert_eq!(
+        Duration::from_secs(5),
+        parser.get::<Duration>("-t").unwrap()
+    );
+    assert_eq!("-t 5s", parser.compose_inputs());
+    parser.process_args(vec!["-t", "1m30s"]);
+    assert_eq!(
+        Duration::from_secs(90),
+        parser.get::<Duration>("-t").unwrap()
+    );
+    assert_eq!("1m30s", parser.get::<String>("-t").unwrap());
+    parser.process_args(vec!["-t", "250ms"]);
+    assert_eq!(
+        Duration::from_millis(250),
+        parser.get::<Duration>("-t").unwrap()
+    );
+    parser.process_args(vec!["-t", "1s500us"]);
+    assert_eq!(
+        Duration::from_micros(1_000_500),
+        parser.get::<Duration>("-t").unwrap()
+    );
+    assert_eq!("1000500us", parser.get::<String>("-t").unwrap());
+    parser.process_args(vec!["-t", "0.5h"]);
+    assert_eq!(
+        Duration::from_secs(1800),
+        parser.get::<Duration>("-t").unwrap()
+    );
+    parser.process_args(vec!["-t", "2"]);
+    assert_eq!(
+        Duration::from_secs(2),
+        parser.get::<Duration>("-t").unwrap()
+    );
+    let process_res = parser.check_process_args(vec!["-t", "2y"], true);
+    assert_eq!(
+        "failed to parse \"2y\" as duration",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["-t", "1e300"], true);
+    assert_eq!(
+        "failed to parse \"1e300\" as duration",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["-t", "99999999999999999999d"], true);
+    assert_eq!(
+        "failed to parse \"99999999999999999999d\" as duration",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["-t", "2h"], true);
+    assert_eq!(
+        "[2h] is out of range [100ms, 1h]",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_byte_size_arg() {
+    println!("*** BYTE SIZE ARGUMENT ***");
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-s", default = ArgByteSize(1024))
+        .set_range(ArgByteSize(0), ArgByteSize(1 << 30))
+        .add_to(&mut parser);
+    parser.process_args(vec![]);
+    assert_eq!(ArgByteSize(1024), parser.get::<ArgByteSize>("-s").unwrap());
+    assert_eq!("1KiB", parser.get::<String>("-s").unwrap());
+    parser.process_args(vec!["-s", "10MiB"]);
+    assert_eq!(10 * 1024 * 1024, parser.get::<u64>("-s").unwrap());
+    parser.process_args(vec!["-s", "1.5kB"]);
+    assert_eq!(1500, parser.get::<u64>("-s").unwrap());
+    assert_eq!("1500B", parser.get::<String>("-s").unwrap());
+    parser.process_args(vec!["-s", "512"]);
+    assert_eq!(512, parser.get::<u64>("-s").unwrap());
+    let process_res = parser.check_process_args(vec!["-s", "10XB"], true);
+    assert_eq!(
+        "failed to parse \"10XB\" as byte size",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["-s", "2G"], true);
+    assert_eq!(
+        "[2GiB] is out of range [0B, 1GiB]",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_parse_fn_arg() {
+    println!("*** PARSE FN ARGUMENT ***");
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-x", value = 0)
+        .set_parse_fn(|s| {
+            let s = s.trim_start_matches("0x");
+            i32::from_str_radix(s, 16).map_err(|e| format!("bad hex [{}]", s))
+        })
+        .set_range(0, 255)
+        .add_to(&mut parser);
+    parser.process_args(vec!["-x", "0xff"]);
+    assert_eq!(255, parser.get::<i32>("-x").unwrap());
+    let process_res = parser.check_process_args(vec!["-x", "zz"], true);
+    assert_eq!("bad hex [zz]", process_res.unwrap_err().to_string());
+    let process_res = parser.check_process_args(vec!["-x", "100"], true);
+    assert_eq!(
+        "[256] is out of range [0, 255]",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_arg_group() {
+    println!("*** ARGUMENT GROUP ***");
+    let mut parser = DumbArgParser::new();
+    dap_arg!("--json", fixed = true).add_to(&mut parser);
+    dap_arg!("--csv", fixed = true).add_to(&mut parser);
+    dap_arg!("-a", value = "").add_to(&mut parser);
+    dap_arg!("-b", value = "").add_to(&mut parser);
+    dap_arg!("-c", default = "C").add_to(&mut parser);
+    parser
+        .add_group("format", vec!["--json", "--csv"], ArgGroupRule::AtMostOne)
+        .unwrap();
+    parser
+        .add_group("source", vec!["-a", "-b", "-c"], ArgGroupRule::ExactlyOne)
+        .unwrap();
+    assert!(parser
+        .add_group("bad", vec!["-x"], ArgGroupRule::None)
+        .is_err());
+    parser.process_args(vec!["--json", "-a", "A"]);
+    assert!(parser.get::<bool>("--json").unwrap());
+    assert_eq!("A", parser.get::<String>("-a").unwrap());
+    assert!(parser.get::<String>("-b").is_none());
+    assert_eq!("C", parser.get::<String>("-c").unwrap());
+    let process_res = parser.check_process_args(vec!["--csv", "--json", "-a", "A"], true);
+    assert_eq!(
+        "arguments [--json] and [--csv] are mutually exclusive",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["-a", "A", "-c", "C"], true);
+    assert_eq!(
+        "arguments [-a] and [-c] are mutually exclusive",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["--csv"], true);
+    assert_eq!(
+        "one of the arguments [-a, -b, -c] of group [source] must be provided",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_arg_requires() {
+    println!("*** ARGUMENT REQUIRES ***");
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-u", flag2 = "--user", default = "").add_to(&mut parser);
+    dap_arg!("-p", flag2 = "--password", value = "").add_to(&mut parser);
+    dap_arg!("-v").count().add_to(&mut parser);
+    parser.add_requires("--user", vec!["--password"]).unwrap();
+    parser
+        .add_group("verbosity", vec!["-v"], ArgGroupRule::AtLeastOne)
+        .unwrap();
+    parser.process_args(vec!["-vv"]);
+    assert!(parser.get::<String>("-p").is_none());
+    parser.process_args(vec!["-v", "--user", "me", "-p", "secret"]);
+    assert_eq!("secret", parser.get::<String>("--password").unwrap());
+    let process_res = parser.check_process_args(vec!["-v", "--user", "me"], true);
+    assert_eq!(
+        "argument [-u] requires [-p]",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec![], true);
+    assert_eq!(
+        "one of the arguments [-v] of group [verbosity] must be provided",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_arg_suggestion() {
+    println!("*** ARGUMENT SUGGESTION ***");
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-v", flag2 = "--verbose", fixed = true).add_to(&mut parser);
+    dap_arg!("--version", fixed = true).add_to(&mut parser);
+    dap_arg!("--color").negatable(true).add_to(&mut parser);
+    dap_arg!("mode", default = "calc")
+        .set_enums(vec!["calc", "json", "ltemp"])
+        .add_to(&mut parser);
+    let process_res = parser.check_process_args(vec!["--verbos"], true);
+    assert_eq!(
+        "unknown input argument [--verbos]; did you mean [--verbose]?",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["--no-colour"], true);
+    assert_eq!(
+        "unknown input argument [--no-colour]; did you mean [--no-color]?",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["--xyz"], true);
+    assert_eq!(
+        "unknown input argument [--xyz]",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["calcc"], true);
+    assert!(process_res
+        .unwrap_err()
+        .to_string()
+        .ends_with("; did you mean [calc]?"));
+    let process_res = parser.check_process_args(vec!["--verb"], true);
+    assert!(process_res.is_err());
+    parser.set_allow_flag_prefix();
+    parser.process_args(vec!["--verb", "--no-c", "json"]);
+    assert!(parser.get::<bool>("-v").unwrap());
+    assert!(!parser.get::<bool>("--color").unwrap());
+    assert_eq!("json", parser.get::<String>("mode").unwrap());
+    let process_res = parser.check_process_args(vec!["--ver"], true);
+    assert_eq!(
+        "ambiguous input argument [--ver]; could be [--verbose, --version]",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_response_files() {
+    println!("*** RESPONSE FILES ***");
+    let dir = std::env::temp_dir().join(format!(
+        "rusty_dumb_tools_test_response_files_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    std::fs::write(
+        dir.join("args.txt"),
+        "# the common arguments\n-n 'John Smith' # the name\n-I \"a \\\"b\\\"\" @sub/more.txt\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("sub").join("more.txt"), "-I c\\ d\n-v").unwrap();
+    std::fs::write(dir.join("cycle1.txt"), "-v @cycle2.txt").unwrap();
+    std::fs::write(dir.join("cycle2.txt"), "@cycle1.txt").unwrap();
+    std::fs::write(dir.join("bad.txt"), "-n \"unterminated").unwrap();
+    let args_path = format!("@{}", dir.join("args.txt").display());
+    let cycle_path = format!("@{}", dir.join("cycle1.txt").display());
+    let bad_path = format!("@{}", dir.join("bad.txt").display());
+    let missing_path = format!("@{}", dir.join("missing.txt").display());
+
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-v", fixed = true).add_to(&mut parser);
+    dap_arg!("-n", default = "").add_to(&mut parser);
+    dap_arg!("-I").append("").add_to(&mut parser);
+    dap_arg!("rest", default = "").add_to(&mut parser);
+    parser.process_args(vec![&args_path]);
+    assert_eq!(args_path, parser.get::<String>("rest").unwrap());
+    parser.set_allow_response_files();
+    parser.process_args(vec![&args_path, "@"]);
+    assert!(parser.get::<bool>("-v").unwrap());
+    assert_eq!("John Smith", parser.get::<String>("-n").unwrap());
+    assert_eq!(
+        vec!["a \"b\"", "c d"],
+        parser.get_multi::<String>("-I").unwrap()
+    );
+    assert_eq!("@", parser.get::<String>("rest").unwrap());
+    let process_res = parser.check_process_args(vec![&cycle_path], true);
+    assert!(process_res
+        .unwrap_err()
+        .to_string()
+        .ends_with("cycle1.txt] includes itself"));
+    let process_res = parser.check_process_args(vec![&bad_path], true);
+    assert!(process_res
+        .unwrap_err()
+        .to_string()
+        .ends_with("bad.txt]: unterminated double quote"));
+    let process_res = parser.check_process_args(vec![&missing_path], true);
+    assert!(process_res
+        .unwrap_err()
+        .to_string()
+        .starts_with("cannot read response file ["));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+#[test]
+fn test_prompt_missing_args() {
+    println!("*** PROMPT MISSING ARGUMENTS ***");
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-n", flag2 = "--name", default = "nobody").add_to(&mut parser);
+    dap_arg!("level", value = 0)
+        .set_description("the level")
+        .set_range(1, 5)
+        .add_to(&mut parser);
+    dap_arg!("mode", value = "fast")
+        .set_with_desc_enums(vec!["fast:run fast", "slow:run slow"])
+        .add_to(&mut parser);
+    dap_arg!("files", value = "")
+        .set_multi()
+        .add_to(&mut parser);
+    let input = "x\n9\n\n3\n7\n2\na b c\n";
+    parser.set_prompt_reader(std::io::Cursor::new(input.as_bytes().to_vec()));
+    parser.process_args(vec![]);
+    assert_eq!("nobody", parser.get::<String>("-n").unwrap());
+    assert_eq!(3, parser.get::<i32>("level").unwrap());
+    assert_eq!("slow", parser.get::<String>("mode").unwrap());
+    assert_eq!(
+        vec!["a", "b", "c"],
+        parser.get_multi::<String>("files").unwrap()
+    );
+    parser.set_prompt_reader(std::io::Cursor::new("fast\nf1\n".as_bytes().to_vec()));
+    parser.process_args(vec!["4"]);
+    assert_eq!(4, parser.get::<i32>("level").unwrap());
+    assert_eq!("fast", parser.get::<String>("mode").unwrap());
+    assert_eq!(vec!["f1"], parser.get_multi::<String>("files").unwrap());
+    parser.set_prompt_reader(std::io::Cursor::new("1\n".as_bytes().to_vec()));
+    let process_res = parser.check_process_args(vec![], true);
+    assert_eq!(
+        "argument [mode] not provided",
+        process_res.unwrap_err().to_string()
+    );
+
+    // an enum value is taken as the value, even if it is also a number of the menu
+    let mut parser = DumbArgParser::new();
+    dap_arg!("count", value = "1")
+        .set_with_desc_enums(vec!["3:three", "2:two", "1:one"])
+        .add_to(&mut parser);
+    parser.set_prompt_reader(std::io::Cursor::new(
+        "1
+"
+        .as_bytes()
+        .to_vec(),
+    ));
+    parser.process_args(vec![]);
+    assert_eq!("1", parser.get::<String>("count").unwrap());
+    parser.set_prompt_reader(std::io::Cursor::new(
+        "4
+"
+        .as_bytes()
+        .to_vec(),
+    ));
+    assert!(parser.check_process_args(vec![], true).is_err()); // not a menu number either
+
+    // with missing arguments allowed, nothing is prompted
+    let mut parser = DumbArgParser::new();
+    dap_arg!("level", value = 0).add_to(&mut parser);
+    parser.set_allow_missing_arguments();
+    parser.set_prompt_reader(std::io::Cursor::new("".as_bytes().to_vec()));
+    parser.check_process_args(vec![], true).unwrap();
+    assert_eq!(None, parser.get::<i32>("level"));
+}
+#[test]
+fn test_compose_argv_and_json() {
+    println!("*** COMPOSE ARGV AND JSON ***");
+    use std::time::Duration;
+    let create_parser = || {
+        let mut parser = DumbArgParser::new_with_name("dumb");
+        dap_arg!("-v").count().add_to(&mut parser);
+        dap_arg!("--color").negatable(true).add_to(&mut parser);
+        dap_arg!("-d", fixed = true).add_to(&mut parser);
+        dap_arg!("-n", flag2 = "--name", default = "nobody").add_to(&mut parser);
+        dap_arg!("-I").append("").add_to(&mut parser);
+        dap_arg!("-t", value = Duration::from_secs(90)).add_to(&mut parser);
+        dap_arg!("ratio", value = 0.5).add_to(&mut parser);
+        dap_arg!("files").set_multi().add_to(&mut parser);
+        parser
+    };
+    let mut parser = create_parser();
+    parser.process_args(vec![
+        "-vv",
+        "--no-color",
+        "-I",
+        "a b",
+        "-I",
+        "it's",
+        "-t",
+        "1m30s",
+        "1.5",
+        "x\"y",
+        "z",
+    ]);
+    let argv = parser.compose_argv();
+    assert_eq!(
+        vec![
+            "-v",
+            "-v",
+            "--no-color",
+            "-n",
+            "nobody",
+            "-I",
+            "a b",
+            "-I",
+            "it's",
+            "-t",
+            "1m30s",
+            "1.5",
+            "x\"y",
+            "z"
+        ],
+        argv
+    );
+    assert_eq!(
+        "dumb -v -v --no-color -n nobody -I 'a b' -I 'it'\\''s' -t 1m30s 1.5 'x\"y' z",
+        parser.compose_command_line()
+    );
+    assert_eq!(
+        r#"{"v": 2, "color": false, "d": false, "name": "nobody", "I": ["a b", "it's"], "t": "1m30s", "ratio": 1.5, "files": ["x\"y", "z"]}"#,
+        parser.compose_json()
+    );
+    let mut replay_parser = create_parser();
+    replay_parser.process_args(argv.iter().map(|in_arg| in_arg.as_str()).collect());
+    assert_eq!(parser.compose_json(), replay_parser.compose_json());
+    parser.process_args(vec!["-d", "-t", "1m30s", "--", "-1.5", "-x"]);
+    let argv = parser.compose_argv();
+    assert_eq!(
+        vec!["--color", "-d", "-n", "nobody", "-t", "1m30s", "--", "-1.5", "-x"],
+        argv
+    );
+    assert_eq!(
+        r#"{"v": 0, "color": true, "d": true, "name": "nobody", "I": [], "t": "1m30s", "ratio": -1.5, "files": ["-x"]}"#,
+        parser.compose_json()
+    );
+    let mut replay_parser = create_parser();
+    replay_parser.process_args(argv.iter().map(|in_arg| in_arg.as_str()).collect());
+    assert_eq!(parser.compose_json(), replay_parser.compose_json());
+    for (in_duration, composed) in [
+        ("0.0005", "500us"),
+        ("1.0005", "1000500us"),
+        ("0.000000001", "1ns"),
+        ("1.5", "1500ms"),
+    ] {
+        parser.process_args(vec!["-t", in_duration, "0.5", "f"]);
+        let argv = parser.compose_argv();
+        assert!(argv.contains(&composed.to_string()), "{:?}", argv);
+        let mut replay_parser = create_parser();
+        replay_parser.process_args(argv.iter().map(|in_arg| in_arg.as_str()).collect());
+        assert_eq!(parser.compose_json(), replay_parser.compose_json());
+        assert_eq!(
+            parser.get::<Duration>("-t"),
+            replay_parser.get::<Duration>("-t")
+        );
+    }
+}
+#[test]
+fn test_arg_validators() {
+    println!("*** ARGUMENT VALIDATORS ***");
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-b", default = 64_u32)
+        .add_validator(|v: &u32| {
+            if v.is_power_of_two() {
+                Ok(())
+            } else {
+                Err(format!("[{}] is not a power of two", v))
+            }
+        })
+        .add_to(&mut parser);
+    dap_arg!("--min", default = 0).add_to(&mut parser);
+    dap_arg!("--max", default = 100).add_to(&mut parser);
+    dap_arg!("names")
+        .set_multi()
+        .add_validator(|v: &String| {
+            if v.chars().all(|c| c.is_ascii_lowercase()) {
+                Ok(())
+            } else {
+                Err(format!("name [{}] must be in lowercase", v))
+            }
+        })
+        .add_to(&mut parser);
+    parser.add_validator(|parser| {
+        let min = parser.get::<i32>("--min").unwrap();
+        let max = parser.get::<i32>("--max").unwrap();
+        if min > max {
+            Err(format!("--min [{}] is greater than --max [{}]", min, max))
+        } else {
+            Ok(())
+        }
+    });
+    parser.process_args(vec!["-b", "128", "--max", "10", "abc", "xyz"]);
+    assert_eq!(128, parser.get::<u32>("-b").unwrap());
+    let process_res = parser.check_process_args(vec!["-b", "100", "abc"], true);
+    assert_eq!(
+        "[100] is not a power of two",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["abc", "Xyz"], true);
+    assert_eq!(
+        "name [Xyz] must be in lowercase",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["--min", "50", "--max", "10", "abc"], true);
+    assert_eq!(
+        "--min [50] is greater than --max [10]",
+        process_res.unwrap_err().to_string()
+    );
+    parser.set_prompt_reader(std::io::Cursor::new("Abc\nabc\n".as_bytes().to_vec()));
+    parser.process_args(vec![]);
+    assert_eq!(vec!["abc"], parser.get_multi::<String>("names").unwrap());
+}
+#[test]
+fn test_hidden_deprecated_aliased_args() {
+    println!("*** HIDDEN / DEPRECATED / ALIASED ARGUMENTS ***");
+    let mut parser = DumbArgParser::new_with_name("dumb");
+    dap_arg!(
+        "-c",
+        flag2 = "--color",
+        aliases = ["--colour", "-C"],
+        fixed = true
+    )
+    .add_deprecated_alias("--colored")
+    .add_to(&mut parser);
+    dap_arg!("--debug-dump", fixed = true)
+        .set_hidden()
+        .add_to(&mut parser);
+    dap_arg!("-l", default = 1)
+        .set_deprecated("use -n instead")
+        .add_to(&mut parser);
+    dap_arg!("-n", default = 1).add_to(&mut parser);
+    assert_eq!("dumb [-h] [-c] [-l l] [-n n]", parser.compose_usage());
+    let help = parser.compose_help(80);
+    assert!(help.contains("|   -c, --color, --colour, -C  FLAG [true]"));
+    assert!(help.contains("DEPRECATED; use -n instead"));
+    assert!(!help.contains("--colored"));
+    assert!(!help.contains("--debug-dump"));
+    parser.process_args(vec!["--colour", "--debug-dump"]);
+    assert!(parser.get::<bool>("-c").unwrap());
+    assert!(parser.get::<bool>("--debug-dump").unwrap());
+    assert!(parser.get_warnings().is_empty());
+    parser.process_args(vec!["-C", "--colored", "-l", "2", "-l", "3"]);
+    assert!(parser.get::<bool>("--color").unwrap());
+    assert_eq!(3, parser.get::<i32>("-l").unwrap());
+    assert_eq!(
+        vec![
+            "flag [--colored] is deprecated; use [-c] instead",
+            "argument [-l] is deprecated; use -n instead"
+        ],
+        parser.get_warnings()
+    );
+    let process_res = parser.check_process_args(vec!["--debug-dum"], true);
+    assert_eq!(
+        "unknown input argument [--debug-dum]",
+        process_res.unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_invalid_arg_specs() {
+    println!("*** INVALID ARGUMENT SPECS ***");
+    let mut parser = DumbArgParser::new();
+    let add_res = dap_arg!("-i", value = 0)
+        .set_range("a", "z")
+        .add_to(&mut parser);
+    assert_eq!(
+        "[0] cannot be compared with [a]",
+        add_res.unwrap_err().to_string()
+    );
+    let add_res = dap_arg!("-d", default = 0)
+        .set_range(1, 9)
+        .add_to(&mut parser);
+    assert_eq!(
+        "invalid initial value of argument [-d]: [0] is out of range [1, 9]",
+        add_res.unwrap_err().to_string()
+    );
+    let add_res = dap_arg!("-v").count().set_multi().add_to(&mut parser);
+    assert_eq!(
+        "multi-argument [-v] cannot be fixed, count, append or negatable",
+        add_res.unwrap_err().to_string()
+    );
+    dap_arg!("multi", value = 0)
+        .set_multi()
+        .add_to(&mut parser)
+        .unwrap();
+    let add_res = dap_arg!("-m", value = 0).set_multi().add_to(&mut parser);
+    assert_eq!(
+        "argument [-m] cannot be multi-argument, since [multi] already is",
+        add_res.unwrap_err().to_string()
+    );
+    let add_res = dap_arg!("after", value = 0).add_to(&mut parser);
+    assert_eq!(
+        "positional argument [after] cannot follow multi-argument [multi]",
+        add_res.unwrap_err().to_string()
+    );
+    parser.process_args(vec!["1", "2"]);
+    assert_eq!(vec![1, 2], parser.get_multi::<i32>("multi").unwrap());
+}
+#[test]
+fn test_compose_help() {
+    println!("*** COMPOSE HELP ***");
+    let mut parser = DumbArgParser::new_with_name("pgm");
+    parser.set_description(
+        "This is a simple argument parser, with a description long enough to be wrapped.",
+    );
+    dap_arg!("-v", flag2 = "--verbose", fixed = true)
+        .set_description("turn on verbose mode")
+        .add_to(&mut parser);
+    dap_arg!("-n", flag2 = "--name", default = "nobody").add_to(&mut parser);
+    dap_arg!("-m", flag2 = "--mode", default = "fast")
+        .set_with_desc_enums(vec![
+            "fast:run fast",
+            "thorough:run slowly but check everything there is to check",
+        ])
+        .add_to(&mut parser);
+    dap_arg!("i32-arg", value = 123)
+        .set_range(1, 999)
+        .add_to(&mut parser);
+    dap_arg!("multi-arg").set_multi().add_to(&mut parser);
+    let help = parser.compose_help(60);
+    println!("{}", help);
+    let expected = vec![
+        "| USAGE: pgm [-h] [-v] [-n name] [-m mode] <i32-arg>",
+        "|        <multi-arg>",
+        "| : This is a simple argument parser, with a description",
+        "| : long enough to be wrapped.",
+        "| POSITIONAL ARGUMENTS:",
+        "|   <i32-arg>             REQUIRED; e.g. 123",
+        "|                         range: [1, 999]",
+        "|   <multi-arg> ...       REQUIRED ...",
+        "| OPTIONS:",
+        "|   -h, --help            HELP",
+        "|   -v, --verbose         FLAG [true]",
+        "|                         turn on verbose mode",
+        "|   -n name, --name name  OPTIONAL; default [nobody]",
+        "|   -m mode, --mode mode  OPTIONAL; default [fast]",
+        "|                         enum values:",
+        "|                         - fast     : run fast",
+        "|                         - thorough : run slowly but check",
+        "|                                      everything there is",
+        "|                                      to check",
+    ];
+    assert_eq!(expected.join("\n"), help);
+    for line in parser.compose_help(40).lines() {
+        assert!(line.chars().count() <= 40, "line too long: [{}]", line);
+    }
+}
+#[test]
+fn test_compose_docs() {
+    println!("*** COMPOSE DOCS ***");
+    let mut parser = DumbArgParser::new_with_name("dumb");
+    parser.set_description("A dumb tool.");
+    dap_arg!("-v", flag2 = "--verbose")
+        .count()
+        .set_description("more output")
+        .add_to(&mut parser);
+    dap_arg!("-n", flag2 = "--name", default = "nobody").add_to(&mut parser);
+    dap_arg!("-l", value = 5)
+        .set_range(1, 9)
+        .add_to(&mut parser);
+    dap_arg!("cmd", value = "run")
+        .set_with_desc_enums(vec!["run:run the *thing*", "stop:stop it"])
+        .set_rest()
+        .add_to(&mut parser);
+    dap_arg!("--bare", fixed = true).add_to(&mut parser);
+    let man_page = parser.compose_man_page(1);
+    println!("{}", man_page);
+    assert!(man_page.starts_with(".TH DUMB 1\n.SH NAME\ndumb \\- A dumb tool.\n"));
+    assert!(man_page.contains(
+        "\n.SH SYNOPSIS\ndumb [\\-h] [\\-v ...] [\\-n name] \\-l l [\\-\\-bare] <cmd>\n"
+    ));
+    assert!(man_page.contains("\n.B \\-v, \\-\\-verbose\nCOUNT; repeatable\n.br\nmore output\n"));
+    assert!(man_page.contains("\n.B \\-l l\nREQUIRED; e.g. 5\n.br\nrange: [1, 9]\n"));
+    assert!(man_page.contains("\nenum values:\n.br\n\\(bu run: run the *thing*\n"));
+    assert!(man_page
+        .contains("\n.SH SUBCOMMANDS\n.TP\n.B run\nrun the *thing*\n.TP\n.B stop\nstop it\n"));
+    let markdown = parser.compose_markdown();
+    println!("{}", markdown);
+    let expected = vec![
+        "# dumb",
+        "",
+        "A dumb tool.",
+        "",
+        "## USAGE",
+        "",
+        "```text",
+        "dumb [-h] [-v ...] [-n name] -l l [--bare] <cmd>",
+        "```",
+        "",
+        "## POSITIONAL ARGUMENTS",
+        "",
+        "* `<cmd> ...`",
+        "  * REQUIRED; e.g. run ...",
+        "  * enum values:",
+        "    * `run`: run the \\*thing\\*",
+        "    * `stop`: stop it",
+        "",
+        "## SUBCOMMANDS",
+        "",
+        "* `run`",
+        "  * run the \\*thing\\*",
+        "* `stop`",
+        "  * stop it",
+        "",
+        "## OPTIONS",
+        "",
+        "* `-h, --help`",
+        "  * HELP",
+        "* `-v, --verbose`",
+        "  * COUNT; repeatable",
+        "  * more output",
+        "* `-n name, --name name`",
+        "  * OPTIONAL; default [nobody]",
+        "* `-l l`",
+        "  * REQUIRED; e.g. 5",
+        "  * range: [1, 9]",
+        "* `--bare`",
+        "  * FLAG [true]",
+        "",
+    ];
+    assert_eq!(expected.join("\n"), markdown);
+}
+#[test]
+fn test_dap_struct() {
+    println!("*** DAP STRUCT ***");
+    dap_struct! {
+        #[derive(Debug)]
+        struct TestArgs {
+            /// verbosity
+            verbose: i32 = dap_arg!("-v").count(),
+            color: bool = dap_arg!("--color").negatable(true),
+            dry_run: bool = dap_arg!("--dry-run", fixed = true),
+            name: Option<String> = dap_arg!("-n", flag2 = "--name"),
+            includes: Vec<String> = dap_arg!("-I").append(""),
+            limit: u64 = dap_arg!("--limit", default = 100_u64).set_description("the limit"),
+            input: String = dap_arg!("input"),
+        }
+    }
+    let mut parser = TestArgs::create_parser();
+    parser.set_allow_missing_arguments();
+    parser.process_args(vec!["-vv", "--no-color", "-I", "a", "-I", "b", "IN"]);
+    let test_args = TestArgs::from_parser(&parser).unwrap();
+    assert_eq!(2, test_args.verbose);
+    assert!(!test_args.color);
+    assert!(!test_args.dry_run);
+    assert_eq!(None, test_args.name);
+    assert_eq!(vec!["a", "b"], test_args.includes);
+    assert_eq!(100, test_args.limit);
+    assert_eq!("IN", test_args.input);
+    let test_args = TestArgs::process_args(vec!["--dry-run", "-n", "me", "--limit", "5", "IN"]);
+    assert!(test_args.dry_run);
+    assert_eq!(Some("me".to_string()), test_args.name);
+    assert_eq!(5, test_args.limit);
+    parser.process_args(vec![]);
+    assert_eq!(
+        "argument [input] not provided",
+        TestArgs::from_parser(&parser).unwrap_err().to_string()
+    );
+}
+#[test]
+fn test_message_catalog() {
+    println!("*** MESSAGE CATALOG ***");
+    struct TestMessages;
+    impl ArgMessageCatalogTrait for TestMessages {
+        fn usage_label(&self) -> String {
+            "UTILISATION".to_owned()
+        }
+        fn options_title(&self) -> String {
+            "OPTIONS DU PROGRAMME".to_owned()
+        }
+        fn required_desc(&self, example: Option<&str>, multi: bool) -> String {
+            match example {
+                Some(example) => format!("OBLIGATOIRE; p.ex. {}", example),
+                None => "OBLIGATOIRE".to_owned(),
+            }
+        }
+        fn unknown_input_argument(&self, in_arg: &str, suggestion: Option<&str>) -> String {
+            match suggestion {
+                Some(suggestion) => format!("argument inconnu [{}]; [{}] ?", in_arg, suggestion),
+                None => format!("argument inconnu [{}]", in_arg),
+            }
+        }
+        fn out_of_range(&self, value: &str, min: &str, max: &str) -> String {
+            format!("[{}] hors de [{}, {}]", value, min, max)
+        }
+        fn failed_to_parse(&self, val: &str, type_name: &str) -> String {
+            format!("[{}] n'est pas {}", val, type_name)
+        }
+    }
+    let create_parser = || {
+        let mut parser = DumbArgParser::new_with_name("test");
+        parser.set_message_catalog(TestMessages);
+        dap_arg!("-v", flag2 = "--verbose", fixed = true)
+            .add_to(&mut parser)
+            .unwrap();
+        dap_arg!("-l", value = 5)
+            .set_range(1, 9)
+            .add_to(&mut parser)
+            .unwrap();
+        parser
+    };
+    let mut parser = create_parser();
+    let process_res = parser.check_process_args(vec!["--verbos"], false);
+    assert_eq!(
+        "argument inconnu [--verbos]; [--verbose] ?",
+        process_res.unwrap_err().to_string()
+    );
+    let process_res = parser.check_process_args(vec!["-l", "10"], false);
+    assert_eq!("[10] hors de [1, 9]", process_res.unwrap_err().to_string());
+    let process_res = parser.check_process_args(vec!["-l", "x"], false);
+    assert_eq!("[x] n'est pas i32", process_res.unwrap_err().to_string());
+    // messages not overridden are the default ones
+    let process_res = parser.check_process_args(vec![], false);
+    assert_eq!(
+        "argument [-l] not provided",
+        process_res.unwrap_err().to_string()
+    );
+    let help = parser.compose_help(60);
+    let expected = [
+        "| UTILISATION: test [-h] [-v] -l l",
+        "| OPTIONS DU PROGRAMME:",
+        "|   -h, --help     HELP",
+        "|   -v, --verbose  FLAG [true]",
+        "|   -l l           OBLIGATOIRE; p.ex. 5",
+        "|                  range: [1, 9]",
+    ];
+    assert_eq!(expected.join("\n"), help);
+}
+#[test]
+fn test_enum_aliases() {
+    println!("*** ENUM ALIASES ***");
+    let create_parser = || {
+        let mut parser = DumbArgParser::new_with_name("test");
+        dap_arg!("-a", flag2 = "--answer", default = "no")
+            .set_with_desc_alias_enums(vec!["yes|y|true:go ahead", "no|n:stop"])
+            .set_enums_ignore_case()
+            .add_to(&mut parser)
+            .unwrap();
+        dap_arg!("-l", default = 2)
+            .set_enums(vec![1, 2, 3])
+            .set_enum_aliases(1, vec!["low"])
+            .set_enum_aliases(3, vec!["high", "max"])
+            .add_to(&mut parser)
+            .unwrap();
+        parser
+    };
+    let mut parser = create_parser();
+    parser.process_args(vec!["-a", "Y", "-l", "high"]);
+    assert_eq!("yes", parser.get::<String>("-a").unwrap());
+    assert_eq!(3, parser.get::<i32>("-l").unwrap());
+    assert_eq!(vec!["-a", "yes", "-l", "3"], parser.compose_argv());
+    parser.process_args(vec!["--answer", "TRUE", "-l", "1"]);
+    assert_eq!("yes", parser.get::<String>("-a").unwrap());
+    assert_eq!(1, parser.get::<i32>("-l").unwrap());
+    parser.process_args(vec!["-a", "No"]);
+    assert_eq!("no", parser.get::<String>("-a").unwrap());
+    let process_res = parser.check_process_args(vec!["-a", "tru"], false);
+    assert_eq!(
+        "[tru] doesn't match any of the enum values [yes, no]; did you mean [true]?",
+        process_res.unwrap_err().to_string()
+    );
+    // aliases are case-sensitive unless set otherwise
+    let process_res = parser.check_process_args(vec!["-l", "HIGH"], false);
+    assert_eq!(
+        "failed to parse \"HIGH\" as i32",
+        process_res.unwrap_err().to_string()
+    );
+    let help = parser.compose_help(60);
+    let expected = [
+        "| USAGE: test [-h] [-a answer] [-l l]",
+        "| OPTIONS:",
+        "|   -h, --help            HELP",
+        "|   -a answer, --answer answer",
+        "|                         OPTIONAL; default [no]",
+        "|                         enum values:",
+        "|                         - yes : go ahead (aliases: y,",
+        "|                                 true)",
+        "|                         - no  : stop (aliases: n)",
+        "|   -l l                  OPTIONAL; default [2]",
+        "|                         enum values:",
+        "|                         - 1 : (aliases: low)",
+        "|                         - 2 :",
+        "|                         - 3 : (aliases: high, max)",
+    ];
+    assert_eq!(expected.join("\n"), help);
+    // "|" is not an alias separator for plain described enum values
+    let mut parser = DumbArgParser::new();
+    dap_arg!("-p", default = "a|b")
+        .set_with_desc_enums(vec!["a|b:either", "c:just c"])
+        .add_to(&mut parser)
+        .unwrap();
+    parser.process_args(vec![]);
+    assert_eq!("a|b", parser.get::<String>("-p").unwrap());
+    let process_res = parser.check_process_args(vec!["-p", "a"], false);
+    assert!(process_res.is_err());
+}
+#[test]
+fn test_invalid_enum_aliases() {
+    println!("*** INVALID ENUM ALIASES ***");
+    let mut parser = DumbArgParser::new();
+    let add_res = dap_arg!("-l", value = 1)
+        .set_enums(vec![1, 2])
+        .set_enum_aliases(3, vec!["high"])
+        .add_to(&mut parser);
+    assert_eq!(
+        "[3] is not an enum value of argument [-l]",
+        add_res.unwrap_err().to_string()
+    );
+    let add_res = dap_arg!("-m", value = "")
+        .set_enum_aliases("a", vec!["b"])
+        .add_to(&mut parser);
+    assert_eq!(
+        "argument [-m] has no enum values",
+        add_res.unwrap_err().to_string()
+    );
+    let add_res = dap_arg!("-n", value = "")
+        .set_enums(vec!["yes", "no"])
+        .set_enum_aliases("yes", vec!["y", "no"])
+        .add_to(&mut parser);
+    assert_eq!(
+        "[no] of argument [-n] is ambiguous among enum values [yes] and [no]",
+        add_res.unwrap_err().to_string()
+    );
+    let add_res = dap_arg!("-o", value = "")
+        .set_enums(vec!["fast", "Fast"])
+        .set_enums_ignore_case()
+        .add_to(&mut parser);
+    assert_eq!(
+        "[Fast] of argument [-o] is ambiguous among enum values [fast] and [Fast]",
+        add_res.unwrap_err().to_string()
+    );
+    // duplicated enum values are not ambiguous
+    dap_arg!("-p", value = "")
+        .set_enums(vec!["x", "x"])
+        .set_enums_ignore_case()
+        .add_to(&mut parser)
+        .unwrap();
+}
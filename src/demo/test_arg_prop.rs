@@ -0,0 +1,405 @@
+#![deny(warnings)]
+#![allow(unused)]
+
+//! property tests of [`DumbArgParser`]: random input arguments thrown at parsers built from random specifications
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::prelude::*;
+
+/// tiny pseudo-random generator (xorshift64*), so that failures are reproducible from the seed
+struct PropRng {
+    state: u64,
+}
+impl PropRng {
+    fn new(seed: u64) -> PropRng {
+        PropRng {
+            state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+        }
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+    /// random number in [0, n)
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PropType {
+    I32,
+    U32,
+    F64,
+    Str,
+    Bool,
+    Duration,
+    ByteSize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PropNature {
+    Value,
+    Default,
+    Fixed,
+    Count,
+    Append,
+    Negatable,
+}
+
+#[derive(Debug, Clone)]
+struct PropArgSpec {
+    flags: Vec<String>,
+    prop_type: PropType,
+    nature: PropNature,
+    range: Option<(String, String)>,
+    enums: Option<Vec<String>>,
+    multi: bool,
+    rest: bool,
+}
+
+#[derive(Debug, Clone)]
+struct PropSpec {
+    arg_specs: Vec<PropArgSpec>,
+    group: Option<(Vec<String>, ArgGroupRule)>,
+    allow_flag_prefix: bool,
+    allow_missing: bool,
+}
+
+const PROP_NAMES: [&str; 6] = ["alpha", "beta", "gamma", "delta", "eps", "zeta"];
+const PROP_WORDS: [&str; 22] = [
+    "0",
+    "1",
+    "-1",
+    "7",
+    "42",
+    "3.5",
+    "-2.5",
+    "true",
+    "false",
+    "abc",
+    "",
+    " ",
+    "é",
+    "1e3",
+    "250ms",
+    "1m30s",
+    "1e300",
+    "99999999999999999999d",
+    "4k",
+    "10MiB",
+    "1.5GB",
+    "99999999TiB",
+];
+
+fn random_spec(rng: &mut PropRng) -> PropSpec {
+    let mut arg_specs = Vec::new();
+    let arg_count = rng.below(6);
+    for name in PROP_NAMES.iter().take(arg_count) {
+        let is_flag = rng.chance(60);
+        let flags = if is_flag {
+            let mut flags = vec![format!("-{}", &name[..1])];
+            if rng.chance(60) {
+                flags.push(format!("--{}", name));
+            }
+            flags
+        } else {
+            vec![name.to_string()]
+        };
+        let prop_type = *rng.pick(&[
+            PropType::I32,
+            PropType::U32,
+            PropType::F64,
+            PropType::Str,
+            PropType::Bool,
+            PropType::Duration,
+            PropType::ByteSize,
+        ]);
+        let nature = if is_flag {
+            *rng.pick(&[
+                PropNature::Value,
+                PropNature::Default,
+                PropNature::Fixed,
+                PropNature::Count,
+                PropNature::Append,
+                PropNature::Negatable,
+            ])
+        } else {
+            *rng.pick(&[PropNature::Value, PropNature::Default])
+        };
+        // the range is sometimes deliberately of the wrong type
+        let range = if rng.chance(25) {
+            Some((
+                rng.pick(&PROP_WORDS).to_string(),
+                rng.pick(&PROP_WORDS).to_string(),
+            ))
+        } else {
+            None
+        };
+        let enums = if range.is_none() && rng.chance(20) {
+            Some(
+                (0..1 + rng.below(3))
+                    .map(|_| rng.pick(&PROP_WORDS).to_string())
+                    .collect(),
+            )
+        } else {
+            None
+        };
+        let multi = rng.chance(10);
+        let rest = !multi && rng.chance(5);
+        arg_specs.push(PropArgSpec {
+            flags,
+            prop_type,
+            nature,
+            range,
+            enums,
+            multi,
+            rest,
+        });
+    }
+    let group = if arg_specs.len() >= 2 && rng.chance(20) {
+        let names = vec![arg_specs[0].flags[0].clone(), arg_specs[1].flags[0].clone()];
+        let rule = rng
+            .pick(&[
+                ArgGroupRule::None,
+                ArgGroupRule::AtMostOne,
+                ArgGroupRule::ExactlyOne,
+                ArgGroupRule::AtLeastOne,
+            ])
+            .clone();
+        Some((names, rule))
+    } else {
+        None
+    };
+    PropSpec {
+        arg_specs,
+        group,
+        allow_flag_prefix: rng.chance(30),
+        allow_missing: rng.chance(50),
+    }
+}
+
+fn configure_builder<T: ArgValueTrait>(
+    builder: &mut DumbArgBuilder,
+    arg_spec: &PropArgSpec,
+    sample: T,
+    parse: fn(&str) -> Option<T>,
+) {
+    match arg_spec.nature {
+        PropNature::Value => builder.value(sample),
+        PropNature::Default => builder.default(sample),
+        PropNature::Fixed => builder.fixed(sample),
+        PropNature::Count => builder.count(),
+        PropNature::Append => builder.append(sample),
+        PropNature::Negatable => builder.negatable(false),
+    };
+    if let Some(enums) = &arg_spec.enums {
+        let enums: Vec<T> = enums.iter().filter_map(|word| parse(word)).collect();
+        if !enums.is_empty() {
+            builder.set_enums(enums);
+        }
+    }
+}
+
+fn parse_word<T: FromStr>(word: &str) -> Option<T> {
+    word.parse::<T>().ok()
+}
+
+fn build_parser(spec: &PropSpec) -> DumbArgParser {
+    let mut parser = DumbArgParser::new_with_name("prop");
+    if spec.allow_flag_prefix {
+        parser.set_allow_flag_prefix();
+    }
+    if spec.allow_missing {
+        parser.set_allow_missing_arguments();
+    }
+    for arg_spec in spec.arg_specs.iter() {
+        let mut builder = DumbArgBuilder::new(arg_spec.flags.clone());
+        match arg_spec.prop_type {
+            PropType::I32 => configure_builder(&mut builder, arg_spec, 1, parse_word),
+            PropType::U32 => configure_builder(&mut builder, arg_spec, 1_u32, parse_word),
+            PropType::F64 => configure_builder(&mut builder, arg_spec, 1.5, parse_word),
+            PropType::Str => configure_builder(&mut builder, arg_spec, "s".to_string(), parse_word),
+            PropType::Bool => configure_builder(&mut builder, arg_spec, true, parse_word),
+            PropType::Duration => configure_builder(
+                &mut builder,
+                arg_spec,
+                Duration::from_secs(1),
+                |_| None, // no FromStr for enums
+            ),
+            PropType::ByteSize => {
+                configure_builder(&mut builder, arg_spec, ArgByteSize(1024), |_| None)
+            }
+        };
+        if let Some((min, max)) = &arg_spec.range {
+            builder.set_range(min.clone(), max.clone());
+        }
+        if arg_spec.multi {
+            builder.set_multi();
+        }
+        if arg_spec.rest {
+            builder.set_rest();
+        }
+        // invalid specifications are rejected with error; just skip them
+        let _ = builder.add_to(&mut parser);
+    }
+    if let Some((names, rule)) = &spec.group {
+        let names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+        let _ = parser.add_group("grp", names, rule.clone());
+    }
+    parser
+}
+
+fn random_in_args(rng: &mut PropRng, spec: &PropSpec) -> Vec<String> {
+    let mut flags = vec!["-".to_owned(), "--".to_owned(), "-x".to_owned()];
+    for arg_spec in spec.arg_specs.iter() {
+        for flag in arg_spec.flags.iter() {
+            if let Some(flag_body) = flag.strip_prefix('-') {
+                flags.push(flag.clone());
+                if let Some(long_flag) = flag.strip_prefix("--") {
+                    flags.push(format!("--no-{}", long_flag));
+                    flags.push(flag[..flag.len() - 1].to_string());
+                } else {
+                    // combined short flags, like "-aa"
+                    flags.push(format!("{}{}", flag, flag_body));
+                }
+            }
+        }
+    }
+    let in_arg_count = rng.below(8);
+    (0..in_arg_count)
+        .map(|_| {
+            if rng.chance(45) {
+                rng.pick(&flags).clone()
+            } else {
+                rng.pick(&PROP_WORDS).to_string()
+            }
+        })
+        .collect()
+}
+
+fn check_consistency(parser: &DumbArgParser, spec: &PropSpec) {
+    for arg_spec in spec.arg_specs.iter() {
+        let name = &arg_spec.flags[0];
+        let value = parser.get::<String>(name);
+        for flag in arg_spec.flags.iter() {
+            assert_eq!(
+                value,
+                parser.get::<String>(flag),
+                "[{}] vs [{}]",
+                name,
+                flag
+            );
+        }
+        if let Some(values) = parser.get_multi::<String>(name) {
+            if arg_spec.nature == PropNature::Append {
+                assert_eq!(value.as_ref(), values.last(), "appended [{}]", name);
+            } else {
+                assert_eq!(value.as_ref(), values.first(), "multi [{}]", name);
+            }
+        }
+        if arg_spec.nature == PropNature::Count {
+            if let Some(count) = parser.get::<i32>(name) {
+                assert!(count >= 0, "count [{}]", name);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_prop_scan_args() {
+    println!("*** PROPERTY: SCAN ARGUMENTS ***");
+    for seed in 0..3000 {
+        let mut rng = PropRng::new(seed);
+        let spec = random_spec(&mut rng);
+        let in_args = random_in_args(&mut rng, &spec);
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut parser = build_parser(&spec);
+            let in_args: Vec<&str> = in_args.iter().map(|in_arg| in_arg.as_str()).collect();
+            if let Ok(true) = parser.check_process_args(in_args, false) {
+                check_consistency(&parser, &spec);
+                if spec.group.is_none() {
+                    // replaying the composed input arguments should give the same result
+                    let argv = parser.compose_argv();
+                    let mut replay_parser = build_parser(&spec);
+                    let replay_in_args: Vec<&str> =
+                        argv.iter().map(|in_arg| in_arg.as_str()).collect();
+                    let replay_result = replay_parser.check_process_args(replay_in_args, false);
+                    assert!(
+                        replay_result.is_ok(),
+                        "replay {:?}: {:?}",
+                        argv,
+                        replay_result
+                    );
+                    assert_eq!(parser.compose_json(), replay_parser.compose_json());
+                }
+            }
+            let _ = parser.compose_help(60);
+            let _ = parser.compose_usage();
+        }));
+        assert!(
+            outcome.is_ok(),
+            "seed [{}] panicked -- spec {:?}; input arguments {:?}",
+            seed,
+            spec,
+            in_args
+        );
+    }
+}
+
+#[test]
+fn test_prop_compare_range() {
+    println!("*** PROPERTY: COMPARE RANGE ***");
+    for seed in 0..500 {
+        let mut rng = PropRng::new(seed);
+        let prop_type = *rng.pick(&[
+            PropType::I32,
+            PropType::U32,
+            PropType::F64,
+            PropType::Str,
+            PropType::Duration,
+            PropType::ByteSize,
+        ]);
+        let min = rng.pick(&PROP_WORDS).to_string();
+        let max = rng.pick(&PROP_WORDS).to_string();
+        let word = rng.pick(&PROP_WORDS).to_string();
+        let outcome = panic::catch_unwind(|| {
+            let mut parser = DumbArgParser::new();
+            let mut builder = dap_arg!("-r");
+            match prop_type {
+                PropType::I32 => builder.value(0),
+                PropType::U32 => builder.value(0_u32),
+                PropType::F64 => builder.value(0.0),
+                PropType::Duration => builder.value(Duration::from_secs(0)),
+                PropType::ByteSize => builder.value(ArgByteSize(0)),
+                _ => builder.value(""),
+            };
+            builder.set_range(min.clone(), max.clone());
+            if builder.add_to(&mut parser).is_ok() {
+                let _ = parser.check_process_args(vec!["-r", &word], false);
+            }
+        });
+        assert!(
+            outcome.is_ok(),
+            "seed [{}] panicked -- {:?} range [{}, {}] with [{}]",
+            seed,
+            prop_type,
+            min,
+            max,
+            word
+        );
+    }
+}
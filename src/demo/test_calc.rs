@@ -43,7 +43,7 @@ macro_rules! assert_calc_eq_result {
         let res = $res;
         let calc_res = calc.get_result();
         let check_calc_res = match calc_res {
-            CalcResult::Final(calc_res) => calc_res,
+            CalcResult::Final(ref calc_res) => calc_res.to_f64(),
             _ => res + 1.0,
         };
         let res_diff = (check_calc_res - res).abs();
@@ -213,7 +213,7 @@ pub fn test_calc_unary() {
         test_calc_push!(vec!["0", "cos", "neg"], -1.0);
     }
     if true {
-        test_calc_prase_and_push!(" --2.5 ", -2.5);
+        test_calc_push!(vec!["-", "-", "2.5"], -2.5);
         test_calc_prase_and_push!("0 cos * 3", 3.0);
         test_calc_prase_and_push!("50% + 5", 5.5);
     }
@@ -359,3 +359,921 @@ pub fn test_calc_backup_and_restore() {
     calc.eval().unwrap();
     assert_eq!(2468.0, calc.get_result().unwrap());
 }
+#[test]
+pub fn test_calc_variables() {
+    let mut calc = DumbCalcProcessor::new();
+    assert_eq!(None, calc.get_variable("ans"));
+    calc.parse_and_push("x = 3 * 4").unwrap();
+    assert_eq!(12.0, calc.get_result().unwrap());
+    assert_eq!(Some(12.0), calc.get_variable("x"));
+    assert_eq!(Some(12.0), calc.get_variable("ans"));
+    calc.parse_and_push("x / 2 + 1").unwrap();
+    assert_eq!(7.0, calc.eval().unwrap());
+    assert_eq!(Some(7.0), calc.get_variable("ans"));
+    calc.parse_and_push("ans * 2").unwrap();
+    assert_eq!(14.0, calc.eval().unwrap());
+    calc.parse_and_push("y=x-2").unwrap();
+    assert_eq!(Some(10.0), calc.get_variable("y"));
+    if SUPPORT_IMP_OP {
+        calc.parse_and_push("2 y").unwrap();
+        assert_eq!(20.0, calc.eval().unwrap());
+        calc.parse_and_push("(1 + 1) x").unwrap();
+        assert_eq!(24.0, calc.eval().unwrap());
+    }
+    calc.set_variable("rate2", 0.5).unwrap();
+    calc.push("100").unwrap();
+    calc.push("*").unwrap();
+    calc.push("rate2").unwrap();
+    assert_eq!(50.0, calc.eval().unwrap());
+    assert_eq!(
+        vec![
+            ("ans".to_string(), 50.0),
+            ("rate2".to_string(), 0.5),
+            ("x".to_string(), 12.0),
+            ("y".to_string(), 10.0)
+        ],
+        calc.get_variables()
+    );
+    calc.reset();
+    assert_eq!(Some(12.0), calc.get_variable("x"));
+    assert_eq!(Some(0.5), calc.remove_variable("rate2"));
+    assert!(calc.push("rate2").is_err());
+    assert!(calc.set_variable("2x", 1.0).is_err());
+    assert!(calc.set_variable("sin", 1.0).is_err());
+    assert!(calc.set_variable("PI", 1.0).is_err());
+    assert!(calc.set_variable("inf", 1.0).is_err());
+    calc.parse_and_push("1 +").unwrap();
+    assert!(calc.parse_and_push("z = 2").is_err());
+    calc.reset();
+    let err = calc.parse_and_push("z = 1 / 0").unwrap_err();
+    assert_eq!(CalcErrorKind::DivisionByZero, err.kind());
+    assert!(calc.get_result().is_err());
+    assert_eq!(None, calc.get_variable("z"));
+    calc.reset();
+    let err = calc.parse_and_push("z =").unwrap_err();
+    assert_eq!(CalcErrorKind::InvalidSyntax, err.kind());
+    assert_eq!(Some((2, 3)), err.span());
+    assert_eq!(None, calc.get_variable("z"));
+    calc.clear_variables();
+    assert!(calc.get_variables().is_empty());
+}
+#[test]
+pub fn test_calc_functions() {
+    let mut calc = DumbCalcProcessor::new();
+    calc.parse_and_push("max(1, 5, 3)").unwrap();
+    assert_eq!(5.0, calc.eval().unwrap());
+    calc.parse_and_push("min(4, -2 * 3, 1)").unwrap();
+    assert_eq!(-6.0, calc.eval().unwrap());
+    calc.parse_and_push("1 + max(2, 3) * 2").unwrap();
+    assert_eq!(7.0, calc.eval().unwrap());
+    calc.parse_and_push("hypot(3, 4)").unwrap();
+    assert_eq!(5.0, calc.eval().unwrap());
+    calc.parse_and_push("round(1.23456, 2)").unwrap();
+    assert_eq!(1.23, calc.eval().unwrap());
+    calc.parse_and_push("round(2.6)").unwrap();
+    assert_eq!(3.0, calc.eval().unwrap());
+    calc.parse_and_push("round(2.5, 400)").unwrap();
+    assert_eq!(2.5, calc.eval().unwrap());
+    calc.parse_and_push("round(1e300, 100)").unwrap();
+    assert_eq!(1e300, calc.eval().unwrap());
+    calc.parse_and_push("round(1234, -400)").unwrap();
+    assert_eq!(0.0, calc.eval().unwrap());
+    calc.parse_and_push("atan2(1, 1)").unwrap();
+    assert_eq!(45.0, calc.eval().unwrap().round());
+    calc.use_angle_mode("rad");
+    calc.parse_and_push("atan2(1, 0)").unwrap();
+    assert_eq!(std::f64::consts::FRAC_PI_2, calc.eval().unwrap());
+    calc.parse_and_push("max(min(1, 2), max(3, (4 - 2)), 2)")
+        .unwrap();
+    assert_eq!(3.0, calc.eval().unwrap());
+    calc.parse_and_push("max(1, 2").unwrap();
+    assert_eq!(2.0, calc.eval().unwrap());
+    if SUPPORT_IMP_OP {
+        calc.parse_and_push("2 max(1, 3)").unwrap();
+        assert_eq!(6.0, calc.eval().unwrap());
+    }
+
+    calc.parse_and_push("atan2(1)").unwrap();
+    let result = calc.get_result();
+    assert!(result.is_err());
+    assert_eq!(
+        "Error: function 'atan2' expects 2 arguments, but got 1",
+        result.to_string()
+    );
+    assert!(calc.eval().is_err());
+    calc.parse_and_push("max()").unwrap();
+    assert!(calc.eval().is_err());
+    calc.parse_and_push("1 + 2").unwrap();
+    assert_eq!(3.0, calc.eval().unwrap());
+    calc.reset();
+    assert!(calc.parse_and_push("max 1").is_err());
+    calc.reset();
+    assert!(calc.parse_and_push("1, 2").is_err());
+    calc.reset();
+    assert!(calc.parse_and_push("(1, 2)").is_err());
+    calc.reset();
+
+    calc.register_function("fv", 3, |args| args[2] * (1.0 + args[0]).powf(args[1]))
+        .unwrap();
+    calc.parse_and_push("round(fv(0.1, 2, 100), 2)").unwrap();
+    assert_eq!(121.0, calc.eval().unwrap());
+    calc.parse_and_push("fv(0.1, 2)").unwrap();
+    assert!(calc.eval().is_err());
+    calc.register_function("fv", 1, |args| args[0] * 2.0)
+        .unwrap();
+    calc.push("fv").unwrap();
+    calc.push("(").unwrap();
+    calc.push("21").unwrap();
+    calc.push(")").unwrap();
+    assert_eq!(42.0, calc.eval().unwrap());
+    assert!(calc.register_function("max", 1, |args| args[0]).is_err());
+    assert!(calc.register_function("sin", 1, |args| args[0]).is_err());
+    assert!(calc.register_function("ans", 1, |args| args[0]).is_err());
+    assert!(calc.register_function("2f", 1, |args| args[0]).is_err());
+    calc.set_variable("x", 1.0).unwrap();
+    assert!(calc.register_function("x", 1, |args| args[0]).is_err());
+    assert!(calc.set_variable("fv", 1.0).is_err());
+}
+#[test]
+pub fn test_calc_prefix_unary() {
+    test_calc_prase_and_push!("sqrt(16) + sin 30", 4.5);
+    test_calc_prase_and_push!("sqrt 16 + 1", 5.0);
+    test_calc_prase_and_push!("sqrt(16) ^ 2", 16.0);
+    test_calc_prase_and_push!("sqrt 16 ^ 2", 16.0);
+    test_calc_prase_and_push!("sin 30 ^ 2", 0.0);
+    test_calc_prase_and_push!("2 * -3", -6.0);
+    test_calc_prase_and_push!("-(2 + 3)", -5.0);
+    test_calc_prase_and_push!("(-(2 + 3))", -5.0);
+    test_calc_prase_and_push!("2 ^ -1", 0.5);
+    test_calc_prase_and_push!("1 - -2", 3.0);
+    test_calc_prase_and_push!("(-2 ^ 2)", -4.0);
+    test_calc_prase_and_push!("3 + -(4 - 1) * 2", -3.0);
+    test_calc_prase_and_push!("max(-1, -2)", -1.0);
+    test_calc_prase_and_push!("abs -5", 5.0);
+    test_calc_prase_and_push!("neg neg 2", 2.0);
+    test_calc_prase_and_push!("cos 0 * 3", 3.0);
+    test_calc_prase_and_push!("16 sqrt sqrt", 2.0);
+    test_calc_prase_and_push!("-4 sqrt", -2.0);
+    // unlike pushing "-" twice, which keeps the last operator, the second "-" parsed is an unary minus
+    test_calc_prase_and_push!(" --2.5 ", 2.5);
+    let mut calc = DumbCalcProcessor::new();
+    let err = calc.parse_and_push("2 * (sqrt)").unwrap_err();
+    assert_eq!(CalcErrorKind::InvalidSyntax, err.kind());
+    assert_eq!(Some((9, 10)), err.span());
+
+    let mut calc = DumbCalcProcessor::new();
+    calc.parse_and_push("10").unwrap();
+    calc.eval().unwrap();
+    calc.parse_and_push("-(2 + 3)").unwrap();
+    assert_eq!(5.0, calc.eval().unwrap());
+    calc.parse_and_push("x = -3").unwrap();
+    assert_eq!(Some(-3.0), calc.get_variable("x"));
+    calc.parse_and_push("-x * 2").unwrap();
+    assert_eq!(3.0, calc.eval().unwrap());
+    assert!(calc.parse_and_push("2 * sqrt * 3").is_err());
+    calc.reset();
+    calc.push("16").unwrap();
+    calc.push("sqrt").unwrap();
+    calc.push("neg").unwrap();
+    assert_eq!(-4.0, calc.eval().unwrap());
+}
+#[test]
+pub fn test_calc_decimal_mode() {
+    let mut calc = DumbCalcProcessor::new();
+    calc.parse_and_push("0.1 + 0.2").unwrap();
+    calc.evaluate();
+    assert_eq!("0.30000000000000004", calc.get_result().to_string());
+
+    calc.use_decimal_mode(28, CalcRounding::HalfEven);
+    calc.parse_and_push("0.1 + 0.2").unwrap();
+    calc.evaluate();
+    let result = calc.get_result();
+    assert_eq!("0.3", result.to_string());
+    assert!(!result.unwrap_value().is_approximate());
+    assert_eq!(0.3, result.unwrap());
+    calc.parse_and_push("19.99 * 3 - 1.10 + 2.20").unwrap();
+    calc.evaluate();
+    assert_eq!("61.07", calc.get_result().to_string());
+    calc.parse_and_push("1 / 3 * 3").unwrap();
+    calc.evaluate();
+    assert_eq!(
+        "0.9999999999999999999999999999",
+        calc.get_result().to_string()
+    );
+    calc.parse_and_push("10 / 4 + 0.0015 + 1e3").unwrap();
+    calc.evaluate();
+    assert_eq!("1002.5015", calc.get_result().to_string());
+    calc.parse_and_push("2 ^ -2 + 1.5 ^ 2").unwrap();
+    calc.evaluate();
+    assert_eq!("2.5", calc.get_result().to_string());
+    calc.parse_and_push("(0.5 - 2) abs + 50% + round(2.675, 2) + max(0.1, 0.25)")
+        .unwrap();
+    calc.evaluate();
+    assert_eq!("4.93", calc.get_result().to_string());
+    calc.parse_and_push("x = 0.1").unwrap();
+    calc.parse_and_push("x + 0.2").unwrap();
+    calc.evaluate();
+    assert_eq!("0.3", calc.get_result().to_string());
+    calc.reset();
+    calc.parse_and_push("-1 / 3").unwrap();
+    calc.evaluate();
+    assert_eq!(
+        "-0.3333333333333333333333333333",
+        calc.get_result().to_string()
+    );
+    calc.reset();
+    calc.parse_and_push("-(0.1 + 0.2)").unwrap();
+    calc.evaluate();
+    assert_eq!("-0.3", calc.get_result().to_string());
+
+    calc.use_decimal_mode(50, CalcRounding::HalfEven);
+    calc.parse_and_push("12345678901234567890 * 98765432109876543210")
+        .unwrap();
+    calc.evaluate();
+    assert_eq!(
+        "1219326311370217952237463801111263526900",
+        calc.get_result().to_string()
+    );
+    calc.parse_and_push("2 ^ 100").unwrap();
+    calc.evaluate();
+    assert_eq!(
+        "1267650600228229401496703205376",
+        calc.get_result().to_string()
+    );
+
+    calc.use_decimal_mode(10, CalcRounding::HalfUp);
+    calc.parse_and_push("1 / 7").unwrap();
+    calc.evaluate();
+    assert_eq!("0.1428571429", calc.get_result().to_string());
+    calc.parse_and_push("2 / 3").unwrap();
+    calc.evaluate();
+    assert_eq!("0.6666666667", calc.get_result().to_string());
+    calc.use_decimal_mode(10, CalcRounding::Down);
+    calc.parse_and_push("2 / 3").unwrap();
+    calc.evaluate();
+    assert_eq!("0.6666666666", calc.get_result().to_string());
+
+    let roundings = [
+        (CalcRounding::HalfUp, "3", "-3"),
+        (CalcRounding::HalfDown, "2", "-2"),
+        (CalcRounding::HalfEven, "2", "-2"),
+        (CalcRounding::Up, "3", "-3"),
+        (CalcRounding::Down, "2", "-2"),
+        (CalcRounding::Ceiling, "3", "-2"),
+        (CalcRounding::Floor, "2", "-3"),
+    ];
+    for (rounding, positive, negative) in roundings {
+        calc.use_decimal_mode(1, rounding);
+        calc.parse_and_push("2.5 + 0").unwrap();
+        calc.evaluate();
+        assert_eq!(positive, calc.get_result().to_string());
+        calc.parse_and_push("0 - 2.5").unwrap();
+        calc.evaluate();
+        assert_eq!(negative, calc.get_result().to_string());
+    }
+
+    calc.use_decimal_mode(28, CalcRounding::HalfEven);
+    calc.parse_and_push("sin 30").unwrap();
+    calc.evaluate();
+    let result = calc.get_result();
+    assert!(result.unwrap_value().is_approximate());
+    assert_eq!(0.5, (result.unwrap() * 1e10).round() / 1e10);
+    calc.parse_and_push("2 * PI").unwrap();
+    calc.evaluate();
+    assert!(calc.get_result().unwrap_value().is_approximate());
+    calc.parse_and_push("1 / 0").unwrap();
+    calc.evaluate();
+    assert!(calc.get_result().is_err());
+    for expr in [
+        "1e9223372036854775807 * 1e9223372036854775807",
+        "1e900000000000000000 * 1e900000000000000000",
+        "(1e900000000000000000) square",
+        "1e900000000000000000 / (1 / 1e900000000000000000)",
+    ] {
+        calc.parse_and_push(expr).unwrap();
+        let err = calc.eval().unwrap_err();
+        assert_eq!(CalcErrorKind::Overflow, err.kind(), "{}", expr);
+    }
+    // exact decimal values beyond the range of f64 are not errors
+    for (expr, result) in [
+        ("1e4294967296 + 1", "1e4294967296"),
+        ("1e1000000000 + 1", "1e1000000000"),
+        ("max(1e1000000000, 1)", "1e1000000000"),
+        ("10^400 / 10^399", "10"),
+        ("1e300 * 1e300", "1e600"),
+    ] {
+        calc.parse_and_push(expr).unwrap();
+        calc.evaluate();
+        assert_eq!(result, calc.get_result().to_string(), "{}", expr);
+    }
+    calc.parse_and_push("min(1e1000000000, 1)").unwrap();
+    calc.evaluate();
+    assert_eq!("1", calc.get_result().to_string());
+    calc.parse_and_push("1 / 1e1000000000 + 1").unwrap();
+    calc.evaluate();
+    assert_eq!("1", calc.get_result().to_string());
+    calc.parse_and_push("1.5 / 1e1000000000").unwrap();
+    calc.evaluate();
+    assert_eq!("1.5e-1000000000", calc.get_result().to_string());
+    calc.parse_and_push("1e40 + 1").unwrap();
+    calc.evaluate();
+    assert_eq!(
+        "10000000000000000000000000000000000000000",
+        calc.get_result().to_string()
+    );
+    calc.use_decimal_mode(28, CalcRounding::Up);
+    calc.parse_and_push("1e40 + 1").unwrap(); // the 1 rounds the last digit up
+    calc.evaluate();
+    assert_eq!(
+        "10000000000000000000000000010000000000000",
+        calc.get_result().to_string()
+    );
+    calc.parse_and_push("1e40 - 1").unwrap();
+    calc.evaluate();
+    assert_eq!(
+        "10000000000000000000000000000000000000000",
+        calc.get_result().to_string()
+    );
+    calc.use_decimal_mode(28, CalcRounding::Down);
+    calc.parse_and_push("1e40 - 1").unwrap();
+    calc.evaluate();
+    assert_eq!(
+        "9999999999999999999999999999000000000000",
+        calc.get_result().to_string()
+    );
+
+    calc.use_float_mode();
+    calc.parse_and_push("0.1 + 0.2").unwrap();
+    assert_eq!(0.30000000000000004, calc.eval().unwrap());
+}
+#[test]
+pub fn test_calc_rational_mode() {
+    let mut calc = DumbCalcProcessor::new();
+    calc.use_rational_mode();
+    calc.parse_and_push("1/3 + 1/6").unwrap();
+    calc.evaluate();
+    let result = calc.get_result();
+    assert_eq!("1/2", result.to_string());
+    assert_eq!(0.5, result.unwrap());
+    calc.parse_and_push("2 + 1/3").unwrap();
+    calc.evaluate();
+    assert_eq!("7/3", calc.get_result().to_string());
+    assert_eq!("2 1/3", calc.get_result().to_mixed_string());
+    calc.parse_and_push("1/3 - 8/3").unwrap();
+    calc.evaluate();
+    assert_eq!("-2 1/3", calc.get_result().to_mixed_string());
+    calc.parse_and_push("1/3 - 1/3").unwrap();
+    calc.evaluate();
+    assert_eq!("0", calc.get_result().to_mixed_string());
+    calc.parse_and_push("0.25 * 6").unwrap();
+    calc.evaluate();
+    assert_eq!("3/2", calc.get_result().to_string());
+    calc.parse_and_push("(2/3) ^ -2 + 50%").unwrap();
+    calc.evaluate();
+    assert_eq!("11/4", calc.get_result().to_string());
+    calc.parse_and_push("1/3 inv + 1/4 square + max(1/3, 2/7)")
+        .unwrap();
+    calc.evaluate();
+    assert_eq!("163/48", calc.get_result().to_string());
+    calc.parse_and_push("round(2/3, 2)").unwrap();
+    calc.evaluate();
+    assert_eq!("67/100", calc.get_result().to_string());
+    calc.parse_and_push("round(1234, -2)").unwrap();
+    calc.evaluate();
+    assert_eq!("1200", calc.get_result().to_string());
+    calc.parse_and_push("round(-1250, -2)").unwrap();
+    calc.evaluate();
+    assert_eq!("-1300", calc.get_result().to_string());
+    calc.parse_and_push("round(7/3, -1)").unwrap();
+    calc.evaluate();
+    assert_eq!("0", calc.get_result().to_string());
+    for expr in [
+        "round(1/3, 4000000000)",
+        "round(1/3, 20000)",
+        "round(1/3, -20000)",
+    ] {
+        calc.parse_and_push(expr).unwrap();
+        let err = calc.eval().unwrap_err(); // too many places
+        assert_eq!(CalcErrorKind::InvalidArguments, err.kind(), "{}", expr);
+    }
+    calc.reset();
+    calc.parse_and_push("-1/3").unwrap();
+    calc.evaluate();
+    assert_eq!("-1/3", calc.get_result().to_string());
+    calc.reset();
+    calc.parse_and_push("-1/3 + 1/6").unwrap();
+    calc.evaluate();
+    assert_eq!("-1/6", calc.get_result().to_string());
+    calc.parse_and_push("x = 1/7").unwrap();
+    calc.parse_and_push("x * 14").unwrap();
+    calc.evaluate();
+    assert_eq!("2", calc.get_result().to_string());
+    calc.set_variable("y", 0.1).unwrap();
+    calc.parse_and_push("y * 3").unwrap();
+    calc.evaluate();
+    assert_eq!("3/10", calc.get_result().to_string());
+    calc.parse_and_push("2 ^ 70 / 3").unwrap();
+    calc.evaluate();
+    assert_eq!("1180591620717411303424/3", calc.get_result().to_string());
+    calc.parse_and_push("10^400").unwrap();
+    calc.evaluate();
+    assert_eq!(
+        format!("1{}", "0".repeat(400)),
+        calc.get_result().to_string()
+    );
+    calc.parse_and_push("2^10000 / 2^9999").unwrap(); // exact beyond the range of f64
+    calc.evaluate();
+    assert_eq!("2", calc.get_result().to_string());
+
+    calc.parse_and_push("sqrt 2 + 1/2").unwrap();
+    calc.evaluate();
+    let result = calc.get_result();
+    assert!(matches!(result.unwrap_value(), CalcValue::Float(_)));
+    assert_eq!(1.9142135623730951, result.unwrap());
+    calc.parse_and_push("PI * 2").unwrap();
+    calc.evaluate();
+    assert!(matches!(
+        calc.get_result().unwrap_value(),
+        CalcValue::Float(_)
+    ));
+    calc.parse_and_push("1 / 0").unwrap();
+    calc.evaluate();
+    assert!(calc.get_result().is_err());
+    calc.parse_and_push("1 / 4").unwrap();
+    calc.evaluate();
+    calc.use_float_mode();
+    calc.parse_and_push("ans + 1").unwrap();
+    assert_eq!(1.25, calc.eval().unwrap());
+}
+
+#[test]
+pub fn test_calc_programmer_mode() {
+    let mut calc = DumbCalcProcessor::new();
+    calc.use_programmer_mode(8, false);
+    calc.parse_and_push("0xFF + 2").unwrap();
+    assert_eq!(1.0, calc.eval().unwrap());
+    calc.parse_and_push("0 - 1").unwrap();
+    assert_eq!(255.0, calc.eval().unwrap());
+    calc.parse_and_push("not 0b1010").unwrap();
+    assert_eq!(245.0, calc.eval().unwrap());
+    calc.parse_and_push("1 << 9").unwrap();
+    assert_eq!(0.0, calc.eval().unwrap());
+    calc.parse_and_push("0x0F and 0b1100 or 0o20 xor 3")
+        .unwrap();
+    assert_eq!(31.0, calc.eval().unwrap());
+    calc.parse_and_push("1 + 2 << 3").unwrap();
+    assert_eq!(24.0, calc.eval().unwrap());
+    calc.parse_and_push("17 mod 5 + 7 / 2").unwrap();
+    assert_eq!(5.0, calc.eval().unwrap());
+    calc.parse_and_push("2 ^ 7 + 0.9").unwrap();
+    assert_eq!(128.0, calc.eval().unwrap());
+    calc.parse_and_push("sqrt 50").unwrap();
+    assert_eq!(7.0, calc.eval().unwrap());
+    calc.parse_and_push("1 / 0").unwrap();
+    calc.evaluate();
+    assert!(calc.get_result().is_err());
+
+    calc.use_programmer_mode(8, true);
+    calc.parse_and_push("127 + 1").unwrap();
+    assert_eq!(-128.0, calc.eval().unwrap());
+    calc.parse_and_push("0xFF").unwrap();
+    assert_eq!(-1.0, calc.eval().unwrap());
+    calc.parse_and_push("0x80 >> 3").unwrap();
+    assert_eq!(-16.0, calc.eval().unwrap());
+    calc.parse_and_push("1 << -1").unwrap();
+    let err = calc.eval().unwrap_err();
+    assert_eq!(CalcErrorKind::Domain, err.kind());
+    calc.parse_and_push("0 - 7 mod 3").unwrap();
+    assert_eq!(-1.0, calc.eval().unwrap());
+    calc.use_display_base(16);
+    calc.parse_and_push("0 - 1").unwrap();
+    calc.evaluate();
+    assert_eq!("0xFF", calc.get_result().to_string());
+    calc.use_display_base(2);
+    assert_eq!("0b11111111", calc.get_result().to_string());
+    calc.use_display_base(8);
+    assert_eq!("0o377", calc.get_result().to_string());
+    calc.use_display_base(10);
+    assert_eq!("-1", calc.get_result().to_string());
+
+    calc.use_programmer_mode(64, false);
+    calc.use_display_base(16);
+    calc.parse_and_push("0xFFFFFFFFFFFFFFFF * 0xFFFFFFFFFFFFFFFF")
+        .unwrap();
+    calc.evaluate();
+    assert_eq!("0x1", calc.get_result().to_string());
+    calc.use_programmer_mode(32, true);
+    calc.parse_and_push("0x7FFFFFFF + 1").unwrap();
+    assert_eq!(-2147483648.0, calc.eval().unwrap());
+
+    calc.use_float_mode();
+    calc.parse_and_push("0x10 + 0.5").unwrap();
+    assert_eq!(16.5, calc.eval().unwrap());
+    calc.parse_and_push("7.5 mod 2").unwrap();
+    assert_eq!(1.5, calc.eval().unwrap());
+    calc.parse_and_push("6 and 3 or 8").unwrap();
+    assert_eq!(10.0, calc.eval().unwrap());
+    calc.parse_and_push("1 << 64").unwrap();
+    assert_eq!(0.0, calc.eval().unwrap());
+    calc.parse_and_push("(0 - 8) >> 64").unwrap();
+    assert_eq!(-1.0, calc.eval().unwrap());
+    calc.parse_and_push("1 << -1").unwrap();
+    assert!(calc.eval().is_err());
+    calc.parse_and_push("1 << 0.5").unwrap();
+    assert!(calc.eval().is_err());
+}
+
+#[test]
+pub fn test_calc_compile() {
+    let mut calc = DumbCalcProcessor::new();
+    calc.set_variable("x", 2.5).unwrap();
+    calc.set_variable("y", 4.0).unwrap();
+    calc.register_function("twice", 1, |args| args[0] * 2.0)
+        .unwrap();
+    let exprs = [
+        "1 + 2 * x - y / 4",
+        "2 ^ 3 ^ 2 + x ^ (y - 3) ^ 2",
+        "x - -y * -(x + 1)",
+        "1 + -x ^ 2 + (-x) ^ 2",
+        "2 x + 3(y - 1)x + x y",
+        "sin 30 + sqrt(y) ^ 3 + y sqrt + 50%",
+        "(x + y)% * 8 + x%",
+        "max(x, y, 3) + min(1, x) * hypot(3, y) + round(x / 3, 2)",
+        "atan2(y, x + 1) + twice(x + 1) twice(y)",
+        "neg y + abs(1 - y) + inv(x) + 10 mod 4",
+        "((x + 1) * 2)",
+        "x / (y * 2) - x / y * 2 - x * (y / 2)",
+        "6 and 3 or 8 xor 1 + 1 << 2",
+    ];
+    for expr in exprs {
+        calc.parse_and_push(expr).unwrap();
+        let result = calc.eval().unwrap();
+        let compiled = calc.compile(expr).unwrap();
+        let evaluated = compiled.evaluate(&[]).unwrap();
+        assert!(
+            (result - evaluated).abs() < 1e-10,
+            "{}: {} vs {}",
+            expr,
+            result,
+            evaluated
+        );
+        let recompiled = calc.compile(compiled.to_string()).unwrap();
+        let reevaluated = recompiled.evaluate(&[]).unwrap();
+        assert!(
+            (result - reevaluated).abs() < 1e-10,
+            "{} => {}: {} vs {}",
+            expr,
+            compiled,
+            result,
+            reevaluated
+        );
+    }
+
+    let expr = calc.compile("2 * (3 + 4) * x + y - 1").unwrap();
+    assert_eq!("14 * x + y - 1", expr.to_string());
+    assert_eq!(vec!["x", "y"], expr.get_variables());
+    assert_eq!(35.0, expr.evaluate(&[("y", 1.0)]).unwrap());
+    assert_eq!(
+        0.0,
+        expr.evaluate(&[("x", 0.0), ("y", 1.0), ("z", 9.0)])
+            .unwrap()
+    );
+    assert_eq!(16.0, expr.evaluate_with(&[1.0, 3.0]).unwrap());
+    assert!(expr.evaluate_with(&[1.0]).is_err());
+    match expr.get_root() {
+        CalcNode::Binary(op, left, right) => {
+            assert_eq!("-", op.to_string());
+            assert!(matches!(**left, CalcNode::Binary(..)));
+            assert_eq!(CalcNode::Number(CalcValue::Float(1.0)), **right);
+        }
+        _ => panic!("unexpected root {:?}", expr.get_root()),
+    }
+
+    let expr = calc.compile("a * b + ans").unwrap();
+    assert_eq!(vec!["a", "ans", "b"], expr.get_variables());
+    assert!(expr.evaluate(&[("a", 1.0)]).is_err()); // "b" is not bound
+    let ans = calc.get_variable("ans").unwrap();
+    assert_eq!(6.0 + ans, expr.evaluate(&[("a", 2.0), ("b", 3.0)]).unwrap());
+    let expr = calc.compile("-a / (b - 1) + sin 90").unwrap();
+    assert_eq!("-a / (b - 1) + 1", expr.to_string());
+    assert!(expr.evaluate(&[("a", 1.0), ("b", 1.0)]).is_err());
+    assert_eq!(-1.0, expr.evaluate(&[("a", 4.0), ("b", 3.0)]).unwrap());
+    let expr = calc.compile("1 / 0 + a").unwrap();
+    assert_eq!("1 / 0 + a", expr.to_string()); // not folded, since it results in an error
+    let expr = calc.compile("twice(2) + (sqrt(9) + max(2, 3))").unwrap();
+    assert_eq!("twice(2) + 6", expr.to_string());
+    assert_eq!(10.0, expr.evaluate(&[]).unwrap());
+
+    let expr = calc
+        .compile_units(&["(", "a", "+", "1", ")", "sqrt"])
+        .unwrap();
+    assert_eq!("sqrt(a + 1)", expr.to_string());
+    assert_eq!(3.0, expr.evaluate(&[("a", 8.0)]).unwrap());
+    assert!(calc.compile("max(1)").is_ok());
+    assert!(calc.compile("atan2(1)").is_err());
+    assert!(calc.compile("a = 1").is_err());
+    assert!(calc.compile("").is_err());
+
+    calc.use_rational_mode();
+    let expr = calc.compile("1/3 + a / 6").unwrap();
+    assert_eq!("1/3 + a / 6", expr.to_string());
+    let result = expr.evaluate_value(&[("a", 1.0)]).unwrap();
+    assert_eq!("1/2", result.to_string());
+    let expr = calc.compile("(2 + 1/3) ^ 2 / a").unwrap();
+    assert_eq!("49/9 / a", expr.to_string());
+}
+
+#[test]
+pub fn test_calc_typed_errors() {
+    let mut calc = DumbCalcProcessor::new();
+    let cases = [
+        ("1 / 0", CalcErrorKind::DivisionByZero),
+        ("5 mod 0", CalcErrorKind::DivisionByZero),
+        ("0 ^ -1", CalcErrorKind::DivisionByZero),
+        ("sqrt(-1)", CalcErrorKind::Domain),
+        ("log 0", CalcErrorKind::Domain),
+        ("asin 2", CalcErrorKind::Domain),
+        ("exp 1000", CalcErrorKind::Overflow),
+        ("atan2(1)", CalcErrorKind::InvalidArguments),
+    ];
+    for (expr, kind) in cases {
+        calc.reset();
+        let err = match calc.parse_and_push(expr) {
+            Ok(_) => calc.eval().unwrap_err(),
+            Err(err) => err,
+        };
+        assert_eq!(kind, err.kind(), "{}: {}", expr, err);
+    }
+
+    calc.reset();
+    let err = calc.parse_and_push("1 + 2 $ 3").unwrap_err();
+    assert_eq!(CalcErrorKind::UnknownToken, err.kind());
+    assert_eq!(Some((6, 7)), err.span());
+
+    calc.reset();
+    let err = calc.parse_and_push("1 + , 2").unwrap_err();
+    assert_eq!(CalcErrorKind::InvalidSyntax, err.kind());
+    assert_eq!(Some((4, 5)), err.span());
+
+    calc.reset();
+    calc.parse_and_push("2 * sqrt(1 - 5)").unwrap();
+    let err = calc.eval().unwrap_err();
+    assert_eq!(CalcErrorKind::Domain, err.kind());
+    assert_eq!(Some((4, 8)), err.span());
+    assert!(err.unit_index().is_some());
+
+    calc.reset();
+    calc.push("8").unwrap();
+    calc.push("/").unwrap();
+    calc.push("0").unwrap();
+    let err = calc.eval().unwrap_err();
+    assert_eq!(CalcErrorKind::DivisionByZero, err.kind());
+    assert_eq!(Some(1), err.unit_index());
+    assert_eq!(None, err.span());
+
+    let err = calc.compile("(1 + 2").unwrap_err();
+    assert_eq!(CalcErrorKind::UnbalancedBracket, err.kind());
+    assert_eq!(Some((0, 1)), err.span());
+    let err = calc.compile("1 + 2)").unwrap_err();
+    assert_eq!(CalcErrorKind::UnbalancedBracket, err.kind());
+    assert_eq!(Some((5, 6)), err.span());
+
+    let expr = calc.compile("z + 1").unwrap();
+    let err = expr.evaluate(&[]).unwrap_err();
+    assert_eq!(CalcErrorKind::UnboundVariable, err.kind());
+    assert_eq!(3.0, expr.evaluate(&[("z", 2.0)]).unwrap());
+    let err = calc.compile("1 / z").unwrap().evaluate(&[("z", 0.0)]);
+    assert_eq!(CalcErrorKind::DivisionByZero, err.unwrap_err().kind());
+}
+
+#[test]
+pub fn test_calc_units() {
+    let mut calc = DumbCalcProcessor::new();
+    calc.use_units(true);
+    let cases = [
+        ("5 km / 20 min in km/h", "15 km/h"),
+        ("5 km / 20 min", "0.25 km/min"),
+        ("1 km + 500 m", "1.5 km"),
+        ("500 m + 1 km", "1500 m"),
+        ("2 h - 30 min in min", "90 min"),
+        ("3 m * 4 m", "12 m^2"),
+        ("10 m / 2 s / 5 s", "1 m/s^2"),
+        ("1 km / 1 m", "1000"),
+        ("60 mi/h to km/h", "96.56064 km/h"),
+        ("1 GiB in MB", "1073.741824 MB"),
+        ("8 bit in B", "1 B"),
+        ("100 degC in degF", "212 degF"),
+        ("0 K to degC", "-273.15 degC"),
+        ("2 lb + 3 oz in g", "992.233309375 g"),
+        ("sqrt(16 m^2)", "4 m"),
+        ("(2 h) inv", "0.5 h^-1"),
+        ("1 week / 1 day", "7"),
+        ("1000 MB / 10 s in MB/min", "6000 MB/min"),
+        ("2 * -(3 kg)", "-6 kg"),
+        ("10 degC + 5 degC", "15 degC"),
+    ];
+    for (expr, result) in cases {
+        calc.parse_and_push(expr).unwrap();
+        calc.evaluate();
+        assert_eq!(result, calc.get_result().to_string(), "{}", expr);
+    }
+
+    calc.parse_and_push("5 km / 20 min in km/h").unwrap();
+    assert_eq!(15.0, calc.eval().unwrap());
+    match calc.get_result().unwrap_value() {
+        CalcValue::Quantity(quantity) => {
+            assert_eq!(15.0, quantity.value());
+            assert_eq!("km/h", quantity.unit());
+        }
+        value => panic!("unexpected {}", value),
+    }
+    calc.parse_and_push("ans in m/s").unwrap();
+    calc.evaluate();
+    assert_eq!("4.16666666666667 m/s", calc.get_result().to_string());
+
+    calc.reset();
+    calc.parse_and_push("-3 km").unwrap(); // not "0 - 3 km"
+    calc.evaluate();
+    assert_eq!("-3 km", calc.get_result().to_string());
+    calc.parse_and_push("-3 km + 1 km").unwrap(); // with the previous quantity
+    calc.evaluate();
+    assert_eq!("-5 km", calc.get_result().to_string());
+
+    calc.parse_and_push("d = 42 km").unwrap();
+    calc.parse_and_push("d / 3 h").unwrap();
+    assert_eq!(14.0, calc.eval().unwrap());
+    calc.parse_and_push("min(1, 2) min in s").unwrap();
+    assert_eq!(60.0, calc.eval().unwrap());
+
+    let errors = [
+        "1 km + 1 kg",
+        "1 km + 1",
+        "5 km in kg",
+        "5 in km",
+        "sin(1 m)",
+        "max(1 m, 2 m)",
+        "2 ^ (1 m)",
+        "sqrt(2 m)",
+        "1 h in 2 min",
+        "1 h in min * 2",
+        "10 degC * 2",
+        "2 * 10 degC",
+        "10 degC / 2",
+        "(10 degC) square",
+    ];
+    for expr in errors {
+        calc.parse_and_push(expr).unwrap();
+        let err = calc.eval().unwrap_err();
+        assert_eq!(CalcErrorKind::DimensionMismatch, err.kind(), "{}", expr);
+    }
+    let overflows = [
+        "(1 m^2) ^ 1e10",
+        "(1 m) ^ 1e10 * 1 m",
+        "1 m ^ -1e10",
+        "(1 m) ^ 2147483647 * 1 m",
+        "(1 m) ^ 2000000000 * (1 km) ^ 2000000000",
+    ];
+    for expr in overflows {
+        calc.parse_and_push(expr).unwrap();
+        let err = calc.eval().unwrap_err();
+        assert_eq!(CalcErrorKind::Overflow, err.kind(), "{}", expr);
+    }
+    calc.reset();
+    calc.parse_and_push("1 km + 1 kg").unwrap();
+    let err = calc.eval().unwrap_err();
+    assert_eq!(Some((5, 6)), err.span());
+    assert_eq!(
+        "'+' needs quantities of the same dimension, but got km (length) and kg (mass)",
+        err.message()
+    );
+
+    let expr = calc.compile("x km / 20 min in km/h").unwrap();
+    assert_eq!(vec!["x"], expr.get_variables());
+    assert_eq!(15.0, expr.evaluate(&[("x", 5.0)]).unwrap());
+    assert_eq!(30.0, expr.evaluate(&[("x", 10.0)]).unwrap());
+    let recompiled = calc.compile(expr.to_string()).unwrap();
+    assert_eq!(15.0, recompiled.evaluate(&[("x", 5.0)]).unwrap());
+
+    calc.use_units(false);
+    assert!(calc.parse_and_push("5 km").is_err());
+}
+
+#[test]
+pub fn test_calc_complex_mode() {
+    let mut calc = DumbCalcProcessor::new();
+    calc.use_complex_mode();
+    calc.use_angle_mode("rad");
+    let cases = [
+        ("sqrt(-1)", "i"),
+        ("sqrt(-4) + 1", "1+2i"),
+        ("i * i", "-1"),
+        ("i ^ 2", "-1"),
+        ("i ^ 100", "1"),
+        ("i ^ 4001", "i"),
+        ("(1 + i) ^ 8", "16"),
+        ("i ^ -3", "i"),
+        ("2 ^ 10", "1024"),
+        ("0 - i ^ 3", "i"),
+        ("3 + 2i", "3+2i"),
+        ("3 - 2 i", "3-2i"),
+        ("(1 + 2i) * (3 - i)", "5+5i"),
+        ("(1 + i) / (1 - i)", "i"),
+        ("i inv", "-i"),
+        ("ln(-1)", "3.141592653589793i"),
+        ("sin(i)", "1.1752011936438014i"),
+        ("cos(i)", "1.5430806348152437"),
+        ("abs(3 + 4i)", "5"),
+        ("sqrt(-2i)", "1-i"),
+        ("10 mod 4", "2"),
+        ("max(1, 2) + 0.5i", "2+0.5i"),
+        ("sqrt 16 + log 100", "6"),
+        ("1 - -2i", "1+2i"),
+    ];
+    for (expr, result) in cases {
+        calc.parse_and_push(expr).unwrap();
+        calc.evaluate();
+        assert_eq!(result, calc.get_result().to_string(), "{}", expr);
+    }
+
+    for (expr, result) in [("-i", "-i"), ("-2i", "-2i"), ("-(i)", "-i")] {
+        calc.reset(); // for the leading "-" to be with the result 0
+        calc.parse_and_push(expr).unwrap();
+        calc.evaluate();
+        assert_eq!(result, calc.get_result().to_string(), "{}", expr);
+    }
+
+    calc.parse_and_push("exp(i * PI)").unwrap();
+    assert_eq!(-1.0, calc.eval().unwrap());
+    match calc.get_result().unwrap_value() {
+        CalcValue::Complex(value) => {
+            assert_eq!(-1.0, value.re());
+            assert!(value.im().abs() < 1e-15);
+        }
+        value => panic!("unexpected {}", value),
+    }
+    calc.parse_and_push("(1 + i) ^ 0.5 ^ 2").unwrap();
+    calc.evaluate();
+    match calc.get_result().unwrap_value() {
+        CalcValue::Complex(value) => {
+            assert!((value.re() - 1.0).abs() < 1e-12 && (value.im() - 1.0).abs() < 1e-12)
+        }
+        value => panic!("unexpected {}", value),
+    }
+    calc.parse_and_push("asin(2)").unwrap();
+    calc.evaluate();
+    match calc.get_result().unwrap_value() {
+        CalcValue::Complex(value) => {
+            assert!((value.re() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+            assert!((value.im() + 1.3169578969248166).abs() < 1e-12);
+        }
+        value => panic!("unexpected {}", value),
+    }
+
+    calc.use_polar_display(true);
+    calc.parse_and_push("1 + i").unwrap();
+    calc.evaluate();
+    assert_eq!(
+        "1.4142135623730951∠0.7853981633974483",
+        calc.get_result().to_string()
+    );
+    calc.use_angle_mode("deg");
+    assert_eq!("1.4142135623730951∠45°", calc.get_result().to_string());
+    calc.parse_and_push("2i").unwrap();
+    calc.evaluate();
+    assert_eq!("2∠90°", calc.get_result().to_string());
+    calc.use_polar_display(false);
+    assert_eq!("2i", calc.get_result().to_string());
+
+    let errors = ["1 / 0", "ln 0", "max(1, i)", "i mod 2", "not i"];
+    for expr in errors {
+        calc.parse_and_push(expr).unwrap();
+        assert!(calc.eval().is_err(), "{}", expr);
+    }
+    calc.parse_and_push("2 and i").unwrap();
+    assert_eq!(CalcErrorKind::Domain, calc.eval().unwrap_err().kind());
+    calc.use_units(true);
+    calc.parse_and_push("i km").unwrap();
+    assert_eq!(CalcErrorKind::Domain, calc.eval().unwrap_err().kind());
+    calc.parse_and_push("(1 + 0i) km").unwrap();
+    assert_eq!(1.0, calc.eval().unwrap());
+    calc.use_units(false);
+
+    let expr = calc.compile("x * (1 + 2i) - i").unwrap();
+    assert_eq!(vec!["x"], expr.get_variables());
+    match expr.evaluate_value(&[("x", 2.0)]).unwrap() {
+        CalcValue::Complex(value) => assert_eq!((2.0, 3.0), (value.re(), value.im())),
+        value => panic!("unexpected {}", value),
+    }
+    let recompiled = calc.compile(expr.to_string()).unwrap();
+    assert_eq!(
+        expr.evaluate_value(&[("x", 3.0)]),
+        recompiled.evaluate_value(&[("x", 3.0)])
+    );
+
+    calc.use_float_mode();
+    calc.set_variable("i", 3.0).unwrap();
+    calc.parse_and_push("2 i").unwrap();
+    assert_eq!(6.0, calc.eval().unwrap());
+    calc.parse_and_push("sqrt(-1)").unwrap();
+    assert_eq!(CalcErrorKind::Domain, calc.eval().unwrap_err().kind());
+}
@@ -495,3 +495,44 @@ fn test_calculator_bug_2() {
     calculator.push("cos");
     assert_eq!(calculator.get_display_sized(5), "    0");
 }
+#[test]
+fn test_calculator_programmer() {
+    let mut calculator = DumbCalculator::new();
+    calculator.use_programmer_mode(8, true);
+    calculator.push_chars("100+28=").unwrap();
+    assert_eq!(calculator.get_display(), "-128");
+    calculator.push("hex").unwrap();
+    assert_eq!(calculator.get_display(), "80");
+    calculator.push("and").unwrap();
+    calculator.push_chars("F").unwrap();
+    assert_eq!(calculator.get_display(), "F");
+    calculator.push("A").unwrap();
+    assert_eq!(calculator.get_display(), "FA");
+    calculator.push("=").unwrap();
+    assert_eq!(calculator.get_display(), "80");
+    calculator.push("bin").unwrap();
+    assert_eq!(calculator.get_display(), "10000000");
+    assert!(calculator.push("2").is_err());
+    assert!(calculator.push(".").is_err());
+    calculator.push_chars("101").unwrap();
+    calculator.push("<<").unwrap();
+    calculator.push_chars("11=").unwrap();
+    assert_eq!(calculator.get_display(), "101000");
+    calculator.push("not").unwrap();
+    assert_eq!(calculator.get_display(), "11010111");
+    calculator.push("dec").unwrap();
+    assert_eq!(calculator.get_display(), "-41");
+    calculator.push("undo").unwrap();
+    assert_eq!(calculator.get_display(), "40");
+    calculator.push("oct").unwrap();
+    assert!(calculator.push("8").is_err());
+    calculator.push_chars("777").unwrap();
+    assert_eq!(calculator.get_display(), "777");
+    calculator.push("=").unwrap();
+    assert_eq!(calculator.get_display(), "377");
+
+    calculator.use_float_mode();
+    calculator.push_chars("1.5+1=").unwrap();
+    assert_eq!(calculator.get_display(), "2.5");
+    assert!(calculator.push("hex").is_err());
+}
@@ -58,6 +58,7 @@ pub mod prelude {
     pub use crate::calc::*;
     pub use crate::calculator::*;
     pub use crate::dap_arg;
+    pub use crate::dap_struct;
     pub use crate::dlt_comps;
     pub use crate::dltc;
     pub use crate::dpi_into_iter;